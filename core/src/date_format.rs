@@ -0,0 +1,380 @@
+//! Date strings in explicit formats and Excel serial dates.
+
+use std::{error::Error, fmt, str::FromStr};
+
+use time::{Date, Month, Time, UtcOffset, Weekday};
+
+use crate::models::{DateLike, NANOS_PER_DAY, UNIX_EPOCH_JULIAN_DAY};
+
+/// An error returned when a date can't be parsed.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ParseDateError {
+    /// The input does not match the format.
+    Invalid(String),
+    /// Both month-first and day-first readings are valid, e.g. `03/04/2024`.
+    Ambiguous(String),
+}
+
+impl fmt::Display for ParseDateError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Invalid(message) => message.fmt(f),
+            Self::Ambiguous(s) => write!(
+                f,
+                "ambiguous date {s:?}: could be month/day or day/month, specify the date format"
+            ),
+        }
+    }
+}
+
+impl Error for ParseDateError {}
+
+impl From<time::error::Parse> for ParseDateError {
+    fn from(value: time::error::Parse) -> Self {
+        Self::Invalid(value.to_string())
+    }
+}
+
+impl From<time::error::ComponentRange> for ParseDateError {
+    fn from(value: time::error::ComponentRange) -> Self {
+        Self::Invalid(value.to_string())
+    }
+}
+
+/// A `strftime`-like date format, e.g. `%d.%m.%Y` or `%G-W%V-%u`.
+///
+/// Supported directives: `%Y`, `%y`, `%m`, `%d`, `%j`, `%b`, `%B`, `%G`, `%V`, `%u`,
+/// `%H`, `%M`, `%S`, `%f`, `%z` and `%%`. As in Python's `strptime`, numbers may be
+/// unpadded, and `%y` maps 69-99 to 1969-1999 and 0-68 to 2000-2068.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DateFormat(String);
+
+impl FromStr for DateFormat {
+    type Err = ParseDateError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        while let Some(c) = chars.next() {
+            if c != '%' {
+                continue;
+            }
+            match chars.next() {
+                Some(
+                    'Y' | 'y' | 'm' | 'd' | 'j' | 'b' | 'B' | 'G' | 'V' | 'u' | 'H' | 'M' | 'S'
+                    | 'f' | 'z' | '%',
+                ) => {}
+                Some(other) => {
+                    return Err(ParseDateError::Invalid(format!(
+                        "unsupported directive %{other} in date format {s:?}"
+                    )))
+                }
+                None => {
+                    return Err(ParseDateError::Invalid(format!(
+                        "date format {s:?} ends with a lone %"
+                    )))
+                }
+            }
+        }
+        Ok(Self(s.to_owned()))
+    }
+}
+
+#[derive(Default)]
+struct Fields {
+    year: Option<i32>,
+    month: Option<u8>,
+    day: Option<u8>,
+    ordinal: Option<u16>,
+    iso_year: Option<i32>,
+    iso_week: Option<u8>,
+    weekday: Option<u8>,
+    hour: u8,
+    minute: u8,
+    second: u8,
+    nanos: u32,
+    offset: Option<UtcOffset>,
+}
+
+const MONTHS: [&str; 12] = [
+    "january",
+    "february",
+    "march",
+    "april",
+    "may",
+    "june",
+    "july",
+    "august",
+    "september",
+    "october",
+    "november",
+    "december",
+];
+
+// up to `max` leading digits
+fn take_number(s: &mut &str, max: usize) -> Option<u32> {
+    let len = s.bytes().take(max).take_while(u8::is_ascii_digit).count();
+    let (digits, rest) = s.split_at(len);
+    *s = rest;
+    digits.parse().ok()
+}
+
+fn take_month_name(s: &mut &str) -> Option<u8> {
+    let lower = s.get(..s.len().min(9))?.to_ascii_lowercase();
+    MONTHS.iter().zip(1..).find_map(|(name, month)| {
+        let len = if lower.starts_with(name) {
+            name.len()
+        } else if lower.starts_with(&name[..3]) {
+            3
+        } else {
+            return None;
+        };
+        *s = &s[len..];
+        Some(month)
+    })
+}
+
+// ±HH[:]MM or Z
+fn take_offset(s: &mut &str) -> Option<UtcOffset> {
+    if let Some(rest) = s.strip_prefix('Z') {
+        *s = rest;
+        return Some(UtcOffset::UTC);
+    }
+    let sign = match s.chars().next()? {
+        '+' => 1,
+        '-' => -1,
+        _ => return None,
+    };
+    *s = &s[1..];
+    let hours = take_number(s, 2)? as i8;
+    *s = s.strip_prefix(':').unwrap_or(s);
+    let minutes = take_number(s, 2)? as i8;
+    UtcOffset::from_hms(sign * hours, sign * minutes, 0).ok()
+}
+
+impl DateFormat {
+    pub fn parse(&self, input: &str) -> Result<DateLike, ParseDateError> {
+        let invalid = || {
+            ParseDateError::Invalid(format!("date {input:?} does not match format {:?}", self.0))
+        };
+
+        let mut fields = Fields::default();
+        let mut s = input;
+        let mut format = self.0.chars();
+
+        while let Some(c) = format.next() {
+            if c != '%' {
+                s = s.strip_prefix(c).ok_or_else(invalid)?;
+                continue;
+            }
+            let s = &mut s;
+            let number = |s: &mut &str, max| take_number(s, max).ok_or_else(invalid);
+            // validated in from_str
+            match format.next().unwrap() {
+                'Y' => fields.year = Some(number(s, 4)? as i32),
+                'y' => {
+                    let y = number(s, 2)? as i32;
+                    fields.year = Some(if y < 69 {
+                        2000 + y
+                    } else {
+                        1900 + y
+                    });
+                }
+                'm' => fields.month = Some(number(s, 2)? as u8),
+                'd' => fields.day = Some(number(s, 2)? as u8),
+                'j' => fields.ordinal = Some(number(s, 3)? as u16),
+                'b' | 'B' => fields.month = Some(take_month_name(s).ok_or_else(invalid)?),
+                'G' => fields.iso_year = Some(number(s, 4)? as i32),
+                'V' => fields.iso_week = Some(number(s, 2)? as u8),
+                'u' => fields.weekday = Some(number(s, 1)? as u8),
+                'H' => fields.hour = number(s, 2)? as u8,
+                'M' => fields.minute = number(s, 2)? as u8,
+                'S' => fields.second = number(s, 2)? as u8,
+                'f' => {
+                    let len = s.bytes().take(9).take_while(u8::is_ascii_digit).count();
+                    let nanos = number(s, 9)?;
+                    fields.nanos = nanos * 10u32.pow(9 - len as u32);
+                }
+                'z' => fields.offset = Some(take_offset(s).ok_or_else(invalid)?),
+                _ => *s = s.strip_prefix('%').ok_or_else(invalid)?,
+            }
+        }
+
+        if !s.is_empty() {
+            return Err(ParseDateError::Invalid(format!(
+                "unconverted data remains in date {input:?}: {s:?}"
+            )));
+        }
+
+        let date = match fields {
+            Fields {
+                iso_year: Some(year),
+                iso_week: Some(week),
+                weekday: Some(weekday),
+                ..
+            } => {
+                let weekday = match weekday {
+                    1 => Weekday::Monday,
+                    2 => Weekday::Tuesday,
+                    3 => Weekday::Wednesday,
+                    4 => Weekday::Thursday,
+                    5 => Weekday::Friday,
+                    6 => Weekday::Saturday,
+                    7 => Weekday::Sunday,
+                    _ => return Err(invalid()),
+                };
+                Date::from_iso_week_date(year, week, weekday)?
+            }
+            Fields {
+                iso_year: Some(_),
+                ..
+            }
+            | Fields {
+                iso_week: Some(_),
+                ..
+            } => {
+                return Err(ParseDateError::Invalid(
+                    "ISO week dates require %G, %V and %u".to_owned(),
+                ))
+            }
+            Fields {
+                year: Some(year),
+                ordinal: Some(ordinal),
+                ..
+            } => Date::from_ordinal_date(year, ordinal)?,
+            Fields {
+                year: Some(year),
+                month,
+                day,
+                ..
+            } => {
+                let month = Month::try_from(month.unwrap_or(1))?;
+                Date::from_calendar_date(year, month, day.unwrap_or(1))?
+            }
+            _ => return Err(ParseDateError::Invalid("date format requires a year".to_owned())),
+        };
+
+        let time = Time::from_hms_nano(fields.hour, fields.minute, fields.second, fields.nanos)?;
+
+        Ok(DateLike::new(date, time, fields.offset.unwrap_or(UtcOffset::UTC)))
+    }
+}
+
+/// The date system of an Excel workbook.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExcelDateSystem {
+    /// Serial 1 is 1900-01-01. Excel treats 1900 as a leap year, so serial 60
+    /// is the non-existent 1900-02-29, which is read as 1900-02-28.
+    Excel1900,
+    /// Serial 0 is 1904-01-01, the default of old Excel versions for Mac.
+    Excel1904,
+}
+
+impl FromStr for ExcelDateSystem {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "1900" => Ok(Self::Excel1900),
+            "1904" => Ok(Self::Excel1904),
+            _ => Err("Excel date system must be either 1900 or 1904"),
+        }
+    }
+}
+
+impl DateLike {
+    /// Converts an Excel serial date, the fractional part is the time of day
+    /// (rounded to milliseconds, the precision of Excel).
+    pub fn from_excel_serial(serial: f64, system: ExcelDateSystem) -> Result<Self, ParseDateError> {
+        let invalid = || ParseDateError::Invalid(format!("invalid Excel serial date: {serial}"));
+        if !serial.is_finite() {
+            return Err(invalid());
+        }
+
+        let day = serial.floor();
+        let millis = ((serial - day) * 86_400_000.0).round() as i128;
+
+        let (epoch, day) = match system {
+            ExcelDateSystem::Excel1900 if day < 1.0 => return Err(invalid()),
+            // before the fictitious 1900-02-29
+            ExcelDateSystem::Excel1900 if day < 60.0 => (time::macros::date!(1899 - 12 - 31), day),
+            ExcelDateSystem::Excel1900 if day < 61.0 => (time::macros::date!(1900 - 02 - 28), 0.0),
+            ExcelDateSystem::Excel1900 => (time::macros::date!(1899 - 12 - 30), day),
+            ExcelDateSystem::Excel1904 if day < 0.0 => return Err(invalid()),
+            ExcelDateSystem::Excel1904 => (time::macros::date!(1904 - 01 - 01), day),
+        };
+
+        let days = (epoch.to_julian_day() as f64 + day) as i128 - UNIX_EPOCH_JULIAN_DAY as i128;
+        if days.abs() > 10_000_000 {
+            return Err(invalid());
+        }
+
+        Ok(DateLike::from_unix_nanos(days * NANOS_PER_DAY + millis * 1_000_000))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+    use time::macros::date;
+
+    use super::*;
+
+    #[rstest]
+    #[case("%d.%m.%Y", "03.04.2024", "2024-04-03")]
+    #[case("%d/%m/%Y", "3/4/2024", "2024-04-03")]
+    #[case("%m/%d/%y", "04/03/24", "2024-04-03")]
+    #[case("%d %b %Y", "3 Apr 2024", "2024-04-03")]
+    #[case("%B %d, %Y", "april 03, 2024", "2024-04-03")]
+    #[case("%Y%j", "2024094", "2024-04-03")]
+    #[case("%G-W%V-%u", "2024-W14-3", "2024-04-03")]
+    #[case("%G-W%V-%u", "2020-W01-1", "2019-12-30")]
+    #[case("%d.%m.%Y %H:%M:%S.%f%z", "03.04.2024 12:30:15.5+02:00", "2024-04-03T12:30:15.5+02:00")]
+    #[case("%Y-%m-%d %%", "2024-04-03 %", "2024-04-03")]
+    fn test_parse(#[case] format: &str, #[case] input: &str, #[case] expected: &str) {
+        let format: DateFormat = format.parse().unwrap();
+        assert_eq!(format.parse(input).unwrap(), expected.parse().unwrap());
+    }
+
+    #[rstest]
+    #[case("%d.%m.%Y", "2024-04-03")]
+    #[case("%d.%m.%Y", "31.04.2024")]
+    #[case("%d.%m.%Y", "03.04.2024 12:00")]
+    #[case("%G-W%V", "2024-W14")]
+    #[case("%m/%d", "04/03")]
+    fn test_parse_error(#[case] format: &str, #[case] input: &str) {
+        let format: DateFormat = format.parse().unwrap();
+        assert!(format.parse(input).is_err());
+    }
+
+    #[rstest]
+    fn test_unsupported_directive() {
+        assert!("%d.%m.%Y %Q".parse::<DateFormat>().is_err());
+        assert!("%Y-%m-%".parse::<DateFormat>().is_err());
+    }
+
+    #[rstest]
+    #[case(ExcelDateSystem::Excel1900, 1.0, date!(1900 - 01 - 01))]
+    #[case(ExcelDateSystem::Excel1900, 59.0, date!(1900 - 02 - 28))]
+    #[case(ExcelDateSystem::Excel1900, 60.0, date!(1900 - 02 - 28))]
+    #[case(ExcelDateSystem::Excel1900, 61.0, date!(1900 - 03 - 01))]
+    #[case(ExcelDateSystem::Excel1900, 45385.0, date!(2024 - 04 - 03))]
+    #[case(ExcelDateSystem::Excel1904, 0.0, date!(1904 - 01 - 01))]
+    #[case(ExcelDateSystem::Excel1904, 43923.0, date!(2024 - 04 - 03))]
+    fn test_excel_serial(
+        #[case] system: ExcelDateSystem,
+        #[case] serial: f64,
+        #[case] expected: Date,
+    ) {
+        assert_eq!(DateLike::from_excel_serial(serial, system).unwrap(), expected.into());
+    }
+
+    #[rstest]
+    fn test_excel_serial_time() {
+        let d = DateLike::from_excel_serial(45385.75, ExcelDateSystem::Excel1900).unwrap();
+        assert_eq!(d, "2024-04-03T18:00".parse().unwrap());
+
+        assert!(DateLike::from_excel_serial(0.0, ExcelDateSystem::Excel1900).is_err());
+        assert!(DateLike::from_excel_serial(-1.0, ExcelDateSystem::Excel1904).is_err());
+        assert!(DateLike::from_excel_serial(f64::NAN, ExcelDateSystem::Excel1904).is_err());
+    }
+}
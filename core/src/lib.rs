@@ -0,0 +1,22 @@
+//! Financial functions behind [pyxirr](https://github.com/Anexen/pyxirr), usable from Rust
+//! without Python.
+//!
+//! Python integration (`DayCount` class, date conversions and exceptions)
+//! is available with the `pyo3` feature.
+
+pub mod broadcasting;
+mod date_format;
+mod models;
+mod optimize;
+pub mod periodic;
+pub mod private_equity;
+#[cfg(feature = "pyo3")]
+pub mod python;
+mod scheduled;
+pub mod simulation;
+mod utils;
+
+pub use date_format::{DateFormat, ExcelDateSystem, ParseDateError};
+pub use models::{DateLike, InvalidPaymentsError};
+pub use periodic::*;
+pub use scheduled::*;
@@ -0,0 +1,466 @@
+use std::{error::Error, fmt, str::FromStr};
+
+use time::{macros::format_description, Date, OffsetDateTime, PrimitiveDateTime, Time, UtcOffset};
+
+use crate::date_format::ParseDateError;
+
+// time::Date::from_ordinal_date(1970, 1).unwrap().to_julian_day();
+pub(crate) static UNIX_EPOCH_JULIAN_DAY: i32 = 2440588;
+
+pub(crate) const NANOS_PER_DAY: i128 = 86_400_000_000_000;
+
+/// A date with an optional time of day and UTC offset.
+///
+/// Plain dates are at midnight UTC, so the time only matters for intraday
+/// timestamps (see [`DateLike::to_utc`] and [`DateLike::to_date`]).
+/// Dates are compared by the instant, whatever their offsets.
+#[derive(Debug, Clone, Copy)]
+pub struct DateLike {
+    date: Date,
+    time: Time,
+    offset: UtcOffset,
+}
+
+impl DateLike {
+    pub fn new(date: Date, time: Time, offset: UtcOffset) -> Self {
+        Self {
+            date,
+            time,
+            offset,
+        }
+    }
+
+    /// Nanoseconds since the Unix epoch.
+    pub fn from_unix_nanos(nanos: i128) -> Self {
+        let days = nanos.div_euclid(NANOS_PER_DAY) as i64;
+        let nanos = nanos.rem_euclid(NANOS_PER_DAY) as u64;
+        let time = Time::from_hms_nano(
+            (nanos / 3_600_000_000_000) as u8,
+            (nanos / 60_000_000_000 % 60) as u8,
+            (nanos / 1_000_000_000 % 60) as u8,
+            (nanos % 1_000_000_000) as u32,
+        )
+        .unwrap();
+
+        Self {
+            time,
+            ..Self::from(days)
+        }
+    }
+
+    pub fn time(&self) -> Time {
+        self.time
+    }
+
+    pub fn offset(&self) -> UtcOffset {
+        self.offset
+    }
+
+    /// The local date at midnight, the time of day and the offset are discarded.
+    pub fn to_date(self) -> Self {
+        self.date.into()
+    }
+
+    /// The same instant in UTC.
+    pub fn to_utc(self) -> Self {
+        if self.offset.is_utc() {
+            return self;
+        }
+        OffsetDateTime::from(self).to_offset(UtcOffset::UTC).into()
+    }
+
+    /// The elapsed fraction of the (local) day.
+    pub fn day_fraction(&self) -> f64 {
+        let (h, m, s, nanos) = self.time.as_hms_nano();
+        (h as f64 * 3600.0 + m as f64 * 60.0 + s as f64 + nanos as f64 / 1e9) / 86400.0
+    }
+}
+
+impl PartialEq for DateLike {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other).is_eq()
+    }
+}
+
+impl Eq for DateLike {}
+
+impl PartialOrd for DateLike {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for DateLike {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        if self.offset == other.offset {
+            return (self.date, self.time).cmp(&(other.date, other.time));
+        }
+        OffsetDateTime::from(*self).cmp(&OffsetDateTime::from(*other))
+    }
+}
+
+impl std::hash::Hash for DateLike {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        let utc = self.to_utc();
+        (utc.date, utc.time).hash(state);
+    }
+}
+
+impl From<DateLike> for Date {
+    fn from(val: DateLike) -> Self {
+        val.date
+    }
+}
+
+impl From<Date> for DateLike {
+    fn from(value: Date) -> Self {
+        Self {
+            date: value,
+            time: Time::MIDNIGHT,
+            offset: UtcOffset::UTC,
+        }
+    }
+}
+
+/// Naive date and time, treated as UTC.
+impl From<PrimitiveDateTime> for DateLike {
+    fn from(value: PrimitiveDateTime) -> Self {
+        Self {
+            date: value.date(),
+            time: value.time(),
+            offset: UtcOffset::UTC,
+        }
+    }
+}
+
+impl From<OffsetDateTime> for DateLike {
+    fn from(value: OffsetDateTime) -> Self {
+        Self {
+            date: value.date(),
+            time: value.time(),
+            offset: value.offset(),
+        }
+    }
+}
+
+impl From<DateLike> for OffsetDateTime {
+    fn from(value: DateLike) -> Self {
+        PrimitiveDateTime::new(value.date, value.time).assume_offset(value.offset)
+    }
+}
+
+/// Days since the Unix epoch.
+impl From<i64> for DateLike {
+    fn from(value: i64) -> Self {
+        Date::from_julian_day(UNIX_EPOCH_JULIAN_DAY + (value as i32)).unwrap().into()
+    }
+}
+
+impl AsRef<Date> for DateLike {
+    fn as_ref(&self) -> &Date {
+        &self.date
+    }
+}
+
+/// Parses `%Y-%m-%d`, ISO week dates (`%G-W%V-%u`), `%d.%m.%Y` and slash dates
+/// followed by an optional time after 'T' or a space. Slash dates are read as `%m/%d/%Y`
+/// or `%d/%m/%Y` (with optional zero padding), whichever is valid, and are ambiguous
+/// if both are (except for equal day and month).
+impl FromStr for DateLike {
+    type Err = ParseDateError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // the date part, followed by an optional time after 'T' or a space
+        let (s, time) = match s.find(['T', ' ']) {
+            Some(i) => (&s[..i], parse_time(&s[i..])),
+            None if s.len() > 10 && s.is_char_boundary(10) => (&s[0..10], parse_time(&s[10..])),
+            None => (s, None),
+        };
+
+        let date = if s.contains('/') {
+            let month_first = Date::parse(
+                s,
+                &format_description!("[month padding:none]/[day padding:none]/[year]"),
+            );
+            let day_first = Date::parse(
+                s,
+                &format_description!("[day padding:none]/[month padding:none]/[year]"),
+            );
+            match (month_first, day_first) {
+                (Ok(a), Ok(b)) if a != b => return Err(ParseDateError::Ambiguous(s.to_owned())),
+                (Ok(d), _) | (_, Ok(d)) => d,
+                (Err(e), _) => return Err(e.into()),
+            }
+        } else if s.contains('.') {
+            Date::parse(s, &format_description!("[day].[month].[year]"))?
+        } else if s.contains('W') {
+            Date::parse(
+                s,
+                &format_description!(
+                    "[year base:iso_week]-W[week_number repr:iso]-[weekday repr:monday]"
+                ),
+            )?
+        } else {
+            Date::parse(s, &format_description!("[year]-[month]-[day]"))?
+        };
+
+        Ok(match time {
+            Some((time, offset)) => Self {
+                date,
+                time,
+                offset,
+            },
+            // anything else after the date is ignored
+            None => date.into(),
+        })
+    }
+}
+
+// [T ]HH:MM[:SS[.fraction]][Z|±HH[:MM]]
+fn parse_time(s: &str) -> Option<(Time, UtcOffset)> {
+    let s = s.strip_prefix(['T', ' '])?;
+    let (time, offset) = s.split_at(s.find(['Z', '+', '-']).unwrap_or(s.len()));
+
+    let mut parts = time.split(':');
+    let hour = parts.next()?.parse().ok()?;
+    let minute = parts.next()?.parse().ok()?;
+    let second: f64 = parts.next().map_or(Some(0.0), |s| s.parse().ok())?;
+    if parts.next().is_some() {
+        return None;
+    }
+    let nanos = ((second.fract() * 1e9).round() as u32).min(999_999_999);
+    let time = Time::from_hms_nano(hour, minute, second.trunc() as u8, nanos).ok()?;
+
+    let offset = match offset {
+        "" | "Z" => UtcOffset::UTC,
+        offset => {
+            let sign = if offset.starts_with('-') {
+                -1
+            } else {
+                1
+            };
+            let offset = &offset[1..];
+            let (h, m) = match offset.split_once(':') {
+                Some(hm) => hm,
+                None if offset.len() == 4 => offset.split_at(2),
+                None => (offset, "0"),
+            };
+            let (h, m): (i8, i8) = (h.parse().ok()?, m.parse().ok()?);
+            UtcOffset::from_hms(sign * h, sign * m, 0).ok()?
+        }
+    };
+
+    Some((time, offset))
+}
+
+/// An error returned when the input of a function is not valid.
+#[derive(Clone, Debug, PartialEq)]
+pub enum InvalidPaymentsError {
+    /// The amounts and dates arrays are of different lengths.
+    LengthMismatch {
+        amounts: usize,
+        dates: usize,
+    },
+    /// The amounts and index arrays are of different lengths.
+    IndexLengthMismatch {
+        amounts: usize,
+        index: usize,
+    },
+    /// The payments do not contain both negative and positive payments.
+    NoSignChange,
+    /// The sum of contributions is zero.
+    ZeroContributions,
+    /// The input array is empty.
+    Empty,
+    /// The amount at the given position is NaN.
+    NaN {
+        index: usize,
+    },
+    /// The date at the given position is missing (None or NaT).
+    MissingDate {
+        index: usize,
+    },
+    /// Any other invalid input, e.g. out of range parameters.
+    Invalid(String),
+}
+
+impl InvalidPaymentsError {
+    pub fn new<T: fmt::Display>(message: T) -> Self {
+        Self::Invalid(message.to_string())
+    }
+}
+
+impl fmt::Display for InvalidPaymentsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::LengthMismatch {
+                amounts,
+                dates,
+            } => {
+                write!(
+                    f,
+                    "the amounts and dates arrays are of different lengths \
+                     (amounts: {amounts}, dates: {dates})"
+                )
+            }
+            Self::IndexLengthMismatch {
+                amounts,
+                index,
+            } => {
+                write!(
+                    f,
+                    "Amounts must be the same length as index (amounts: {amounts}, index: {index})"
+                )
+            }
+            Self::NoSignChange => write!(f, "negative and positive payments are required"),
+            Self::ZeroContributions => write!(f, "Contributions are zero"),
+            Self::Empty => write!(f, "Input array must contain at least one value"),
+            Self::NaN {
+                index,
+            } => write!(f, "amount at position {index} is NaN"),
+            Self::MissingDate {
+                index,
+            } => write!(f, "date at position {index} is missing"),
+            Self::Invalid(message) => message.fmt(f),
+        }
+    }
+}
+
+impl Error for InvalidPaymentsError {}
+
+pub fn validate(payments: &[f64], dates: Option<&[DateLike]>) -> Result<(), InvalidPaymentsError> {
+    if let Some(dates) = dates {
+        validate_length(payments, dates)?;
+    }
+    validate_not_nan(payments)?;
+    validate_positive_negative(payments)
+}
+
+pub fn validate_length(payments: &[f64], dates: &[DateLike]) -> Result<(), InvalidPaymentsError> {
+    if payments.len() != dates.len() {
+        Err(InvalidPaymentsError::LengthMismatch {
+            amounts: payments.len(),
+            dates: dates.len(),
+        })
+    } else {
+        Ok(())
+    }
+}
+
+pub fn validate_positive_negative(payments: &[f64]) -> Result<(), InvalidPaymentsError> {
+    let positive = payments.iter().any(|&p| p > 0.0);
+    let negative = payments.iter().any(|&p| p < 0.0);
+
+    if positive && negative {
+        Ok(())
+    } else {
+        Err(InvalidPaymentsError::NoSignChange)
+    }
+}
+
+pub fn validate_not_nan(payments: &[f64]) -> Result<(), InvalidPaymentsError> {
+    match payments.iter().position(|p| p.is_nan()) {
+        Some(index) => Err(InvalidPaymentsError::NaN {
+            index,
+        }),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case(&[-1., 1.], Ok(()))]
+    #[case(&[1., 1.], Err(InvalidPaymentsError::NoSignChange))]
+    #[case(&[-1., f64::NAN, 1.], Err(InvalidPaymentsError::NaN { index: 1 }))]
+    fn test_validate(#[case] payments: &[f64], #[case] expected: Result<(), InvalidPaymentsError>) {
+        assert_eq!(validate(payments, None), expected);
+    }
+
+    #[rstest]
+    #[case("2020-01-01", (0, 0, 0), 0)]
+    #[case("2020-01-01T12:30", (12, 30, 0), 0)]
+    #[case("2020-01-01 12:30:15.5Z", (12, 30, 15), 0)]
+    #[case("2020-01-01T12:30:15+03:00", (12, 30, 15), 3)]
+    #[case("2020-01-01T12:30:15-0500", (12, 30, 15), -5)]
+    // not a time, ignored as before
+    #[case("2020-01-01 is the date", (0, 0, 0), 0)]
+    #[case("1/1/2020 10:00", (10, 0, 0), 0)]
+    #[case("01.01.2020T08:15+01:00", (8, 15, 0), 1)]
+    fn test_parse_time(#[case] s: &str, #[case] hms: (u8, u8, u8), #[case] offset: i8) {
+        let d: DateLike = s.parse().unwrap();
+        assert_eq!(d.as_ref(), &time::macros::date!(2020 - 01 - 01));
+        assert_eq!(d.time().as_hms(), hms);
+        assert_eq!(d.offset().whole_hours(), offset);
+    }
+
+    #[rstest]
+    #[case("2024-04-03", time::macros::date!(2024 - 04 - 03))]
+    #[case("03.04.2024", time::macros::date!(2024 - 04 - 03))]
+    #[case("2024-W14-3", time::macros::date!(2024 - 04 - 03))]
+    // only one of month/day and day/month is valid
+    #[case("04/30/2024", time::macros::date!(2024 - 04 - 30))]
+    #[case("30/04/2024", time::macros::date!(2024 - 04 - 30))]
+    #[case("4/30/2024", time::macros::date!(2024 - 04 - 30))]
+    #[case("30/4/2024", time::macros::date!(2024 - 04 - 30))]
+    fn test_parse_date(#[case] s: &str, #[case] expected: Date) {
+        let d: DateLike = s.parse().unwrap();
+        assert_eq!(d.as_ref(), &expected);
+    }
+
+    #[rstest]
+    fn test_parse_ambiguous_date() {
+        assert_eq!(
+            "03/04/2024".parse::<DateLike>(),
+            Err(ParseDateError::Ambiguous("03/04/2024".to_owned()))
+        );
+        // day and month are the same
+        assert!("04/04/2024".parse::<DateLike>().is_ok());
+    }
+
+    #[rstest]
+    fn test_to_utc() {
+        let d: DateLike = "2020-01-01T01:00+03:00".parse().unwrap();
+        let expected: DateLike = "2019-12-31T22:00".parse().unwrap();
+        assert_eq!(d.to_utc(), expected);
+        assert_eq!(d.to_date(), "2020-01-01".parse().unwrap());
+        assert_eq!(expected.day_fraction(), 22.0 / 24.0);
+
+        assert_eq!(
+            DateLike::from_unix_nanos(-3_600_000_000_000),
+            "1969-12-31T23:00".parse().unwrap()
+        );
+    }
+
+    #[rstest]
+    fn test_compare_instants() {
+        use std::hash::BuildHasher;
+
+        let a: DateLike = "2020-01-01T10:00+05:00".parse().unwrap();
+        let b: DateLike = "2020-01-01T06:00Z".parse().unwrap();
+        assert!(a < b);
+        assert_eq!([a, b].iter().min(), Some(&a));
+
+        // the same instant
+        let c: DateLike = "2020-01-01T05:00Z".parse().unwrap();
+        assert_eq!(a, c);
+        let state = std::collections::hash_map::RandomState::new();
+        assert_eq!(state.hash_one(a), state.hash_one(c));
+    }
+
+    #[rstest]
+    fn test_validate_length() {
+        let dates = ["2020-01-01".parse().unwrap()];
+        assert_eq!(
+            validate(&[-1., 1.], Some(&dates)),
+            Err(InvalidPaymentsError::LengthMismatch {
+                amounts: 2,
+                dates: 1
+            })
+        );
+    }
+}
@@ -259,8 +259,8 @@ where
 //         for root in roots {
 //             let guess = root - 1.;
 //             dbg!(guess);
-//             let rate = crate::core::irr(cf, Some(guess)).unwrap();
-//             assert_approx_eq!(crate::core::npv(rate, cf, None), 0.0);
+//             let rate = crate::irr(cf, Some(guess)).unwrap();
+//             assert_approx_eq!(crate::npv(rate, cf, None), 0.0);
 //         }
 //     }
 // }
@@ -416,14 +416,24 @@ pub fn nfv(rate: f64, nper: f64, amounts: &[f64]) -> f64 {
 
 pub fn npv(rate: f64, values: &[f64], start_from_zero: Option<bool>) -> f64 {
     if rate == 0.0 {
-        return values.iter().sum();
+        return utils::sum_compensated(values.iter().copied());
     }
 
-    powers(1. + rate, values.len(), start_from_zero.unwrap_or(true))
-        .iter()
-        .zip(values.iter())
-        .map(|(p, v)| v / p)
-        .sum()
+    let powers = powers(1. + rate, values.len(), start_from_zero.unwrap_or(true));
+    utils::sum_compensated(powers.iter().zip(values).map(|(p, v)| v / p))
+}
+
+/// The number of periods until the cumulative `values` turn non-negative, interpolated
+/// within the crossing period. NaN if the values never pay back.
+pub fn payback(values: &[f64]) -> f64 {
+    utils::payback_point(values.iter().copied()).map_or(f64::NAN, |(i, f)| (i - 1) as f64 + f)
+}
+
+/// [`payback`] of the values discounted at `rate`, `values[0]` is not discounted.
+pub fn discounted_payback(rate: f64, values: &[f64]) -> f64 {
+    let powers = powers(1. + rate, values.len(), true);
+    let discounted = powers.iter().zip(values).map(|(p, v)| v / p);
+    utils::payback_point(discounted).map_or(f64::NAN, |(i, f)| (i - 1) as f64 + f)
 }
 
 fn npv_deriv(rate: f64, values: &[f64]) -> f64 {
@@ -570,3 +580,66 @@ pub fn mirr(
 
     Ok((positive / -negative).powf(1.0 / (values.len() - 1) as f64) - 1.0)
 }
+
+/// Present value of the inflows divided by the present value of the outflows,
+/// discounted as in [`npv`].
+pub fn profitability_index(
+    rate: f64,
+    values: &[f64],
+    start_from_zero: Option<bool>,
+) -> Result<f64, InvalidPaymentsError> {
+    let split = |inflows: bool| -> Vec<f64> {
+        values
+            .iter()
+            .map(|&v| {
+                if (v > 0.0) == inflows {
+                    v
+                } else {
+                    0.0
+                }
+            })
+            .collect()
+    };
+    let outflows = npv(rate, &split(false), start_from_zero);
+    if outflows == 0.0 {
+        return Err(InvalidPaymentsError::ZeroContributions);
+    }
+
+    Ok(npv(rate, &split(true), start_from_zero) / -outflows)
+}
+
+/// Equivalent annual annuity: the level payment over the life of the project
+/// with the same [`npv`]. The life is `values.len() - 1` periods when starting from zero.
+pub fn eaa(rate: f64, values: &[f64], start_from_zero: Option<bool>) -> f64 {
+    let start_from_zero = start_from_zero.unwrap_or(true);
+    let nper = values.len() as f64
+        - if start_from_zero {
+            1.0
+        } else {
+            0.0
+        };
+    if nper < 1.0 {
+        return f64::NAN;
+    }
+
+    -pmt(rate, nper, npv(rate, values, Some(start_from_zero)), 0.0, false)
+}
+
+/// [`npv`] at every rate.
+pub fn npv_profile(rates: &[f64], values: &[f64], start_from_zero: Option<bool>) -> Vec<f64> {
+    rates.iter().map(|&rate| npv(rate, values, start_from_zero)).collect()
+}
+
+/// The rate at which two projects have the same [`npv`]:
+/// the [`irr`] of the difference of their cash flows.
+pub fn crossover_rate(
+    values: &[f64],
+    other: &[f64],
+    guess: Option<f64>,
+) -> Result<f64, InvalidPaymentsError> {
+    let n = values.len().max(other.len());
+    let at = |v: &[f64], i: usize| v.get(i).copied().unwrap_or(0.0);
+    let difference: Vec<f64> = (0..n).map(|i| at(values, i) - at(other, i)).collect();
+
+    irr(&difference, guess)
+}
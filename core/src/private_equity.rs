@@ -1,50 +1,61 @@
 // https://www.insead.edu/sites/default/files/assets/dept/centres/gpei/docs/Measuring_PE_Fund-Performance-2019.pdf
 
+use super::optimize::brentq;
 use super::utils;
 use super::InvalidPaymentsError;
 
+mod fees;
+mod pooled;
+mod ranking;
+mod waterfall;
+
+pub use fees::*;
+pub use pooled::*;
+pub use ranking::*;
+pub use waterfall::*;
+
 type Result<T> = std::result::Result<T, InvalidPaymentsError>;
 
-#[doc = include_str!("../../docs/_inline/pe/dpi.md")]
+#[doc = include_str!("../docs/pe/dpi.md")]
 pub fn dpi(amounts: &[f64]) -> Result<f64> {
     let (cs, ds) = utils::sum_negatives_positives(amounts);
     check_zero_contributions(cs)?;
     Ok(ds / -cs)
 }
 
-#[doc = include_str!("../../docs/_inline/pe/dpi.md")]
+#[doc = include_str!("../docs/pe/dpi.md")]
 pub fn dpi_2(contributions: &[f64], distributions: &[f64]) -> Result<f64> {
-    let cs: f64 = contributions.iter().sum();
+    let cs = utils::sum_compensated(contributions.iter().copied());
     check_zero_contributions(cs)?;
-    let ds: f64 = distributions.iter().sum();
+    let ds = utils::sum_compensated(distributions.iter().copied());
     Ok(ds / cs)
 }
 
-#[doc = include_str!("../../docs/_inline/pe/rvpi.md")]
+#[doc = include_str!("../docs/pe/rvpi.md")]
 pub fn rvpi(contributions: &[f64], nav: f64) -> Result<f64> {
-    let cs: f64 = contributions.iter().sum();
+    let cs = utils::sum_compensated(contributions.iter().copied());
     check_zero_contributions(cs)?;
     let sign = utils::series_signum(contributions);
     Ok(nav / (sign * cs))
 }
 
-#[doc = include_str!("../../docs/_inline/pe/tvpi.md")]
+#[doc = include_str!("../docs/pe/tvpi.md")]
 pub fn tvpi(amounts: &[f64], nav: f64) -> Result<f64> {
     let (cs, ds) = utils::sum_negatives_positives(amounts);
     check_zero_contributions(cs)?;
     Ok((ds + nav) / -cs)
 }
 
-#[doc = include_str!("../../docs/_inline/pe/tvpi.md")]
+#[doc = include_str!("../docs/pe/tvpi.md")]
 pub fn tvpi_2(contributions: &[f64], distributions: &[f64], nav: f64) -> Result<f64> {
     // this is basically dpi_2(contributions, distributions) + rvpi(&contributions, nav)
-    let cs: f64 = contributions.iter().sum();
+    let cs = utils::sum_compensated(contributions.iter().copied());
     check_zero_contributions(cs)?;
-    let ds: f64 = distributions.iter().sum();
+    let ds = utils::sum_compensated(distributions.iter().copied());
     Ok((ds + nav) / cs)
 }
 
-#[doc = include_str!("../../docs/_inline/pe/moic.md")]
+#[doc = include_str!("../docs/pe/moic.md")]
 pub fn moic(amounts: &[f64], nav: f64) -> Result<f64> {
     // MOIC divides the total value of the investment or fund by the total invested capital,
     // whereas TVPI divides it by the paid-in capital (meaning, the capital that investors have
@@ -54,19 +65,19 @@ pub fn moic(amounts: &[f64], nav: f64) -> Result<f64> {
     tvpi(amounts, nav)
 }
 
-#[doc = include_str!("../../docs/_inline/pe/moic.md")]
+#[doc = include_str!("../docs/pe/moic.md")]
 pub fn moic_2(contributions: &[f64], distributions: &[f64], nav: f64) -> Result<f64> {
     tvpi_2(contributions, distributions, nav)
 }
 
-#[doc = include_str!("../../docs/_inline/pe/ks_pme_flows.md")]
+#[doc = include_str!("../docs/pe/ks_pme_flows.md")]
 pub fn ks_pme_flows(amounts: &[f64], index: &[f64]) -> Result<Vec<f64>> {
     check_input_len(amounts, index)?;
 
     Ok(utils::pairwise_mul(amounts, &index_performance(index)))
 }
 
-#[doc = include_str!("../../docs/_inline/pe/ks_pme_flows.md")]
+#[doc = include_str!("../docs/pe/ks_pme_flows.md")]
 pub fn ks_pme_flows_2(
     contributions: &[f64],
     distributions: &[f64],
@@ -82,12 +93,12 @@ pub fn ks_pme_flows_2(
     Ok((c, d))
 }
 
-#[doc = include_str!("../../docs/_inline/pe/ks_pme.md")]
+#[doc = include_str!("../docs/pe/ks_pme.md")]
 pub fn ks_pme(amounts: &[f64], index: &[f64], nav: f64) -> Result<f64> {
     ks_pme_flows(amounts, index).and_then(|a| tvpi(&a, nav))
 }
 
-#[doc = include_str!("../../docs/_inline/pe/ks_pme.md")]
+#[doc = include_str!("../docs/pe/ks_pme.md")]
 pub fn ks_pme_2(
     contributions: &[f64],
     distributions: &[f64],
@@ -97,13 +108,13 @@ pub fn ks_pme_2(
     ks_pme_flows_2(contributions, distributions, index).and_then(|(c, d)| tvpi_2(&c, &d, nav))
 }
 
-#[doc = include_str!("../../docs/_inline/pe/m_pme.md")]
+#[doc = include_str!("../docs/pe/m_pme.md")]
 pub fn m_pme(amounts: &[f64], index: &[f64], nav: &[f64]) -> Result<f64> {
     let (contributions, distributions) = split_amounts(amounts);
     m_pme_2(&contributions, &distributions, index, nav)
 }
 
-#[doc = include_str!("../../docs/_inline/pe/m_pme.md")]
+#[doc = include_str!("../docs/pe/m_pme.md")]
 pub fn m_pme_2(
     contributions: &[f64],
     distributions: &[f64],
@@ -136,7 +147,7 @@ pub fn m_pme_2(
     super::irr(&cf, None)
 }
 
-#[doc = include_str!("../../docs/_inline/pe/pme_plus_flows.md")]
+#[doc = include_str!("../docs/pe/pme_plus_flows.md")]
 pub fn pme_plus_flows(amounts: &[f64], index: &[f64], nav: f64) -> Result<Vec<f64>> {
     check_input_len(amounts, index)?;
 
@@ -147,7 +158,7 @@ pub fn pme_plus_flows(amounts: &[f64], index: &[f64], nav: f64) -> Result<Vec<f6
     Ok(scaled_amounts)
 }
 
-#[doc = include_str!("../../docs/_inline/pe/pme_plus_flows.md")]
+#[doc = include_str!("../docs/pe/pme_plus_flows.md")]
 pub fn pme_plus_flows_2(
     contributions: &[f64],
     distributions: &[f64],
@@ -158,7 +169,7 @@ pub fn pme_plus_flows_2(
     Ok(utils::scale(distributions, lambda))
 }
 
-#[doc = include_str!("../../docs/_inline/pe/pme_plus_lambda.md")]
+#[doc = include_str!("../docs/pe/pme_plus_lambda.md")]
 pub fn pme_plus_lambda(amounts: &[f64], index: &[f64], nav: f64) -> Result<f64> {
     check_input_len(amounts, index)?;
 
@@ -166,7 +177,7 @@ pub fn pme_plus_lambda(amounts: &[f64], index: &[f64], nav: f64) -> Result<f64>
     pme_plus_lambda_2(&contributions, &distributions, index, nav)
 }
 
-#[doc = include_str!("../../docs/_inline/pe/pme_plus_lambda.md")]
+#[doc = include_str!("../docs/pe/pme_plus_lambda.md")]
 pub fn pme_plus_lambda_2(
     contributions: &[f64],
     distributions: &[f64],
@@ -183,7 +194,7 @@ pub fn pme_plus_lambda_2(
     Ok((cs - nav) / ds)
 }
 
-#[doc = include_str!("../../docs/_inline/pe/pme_plus.md")]
+#[doc = include_str!("../docs/pe/pme_plus.md")]
 pub fn pme_plus(amounts: &[f64], index: &[f64], nav: f64) -> Result<f64> {
     let mut cf = pme_plus_flows(amounts, index, nav)?;
 
@@ -194,7 +205,7 @@ pub fn pme_plus(amounts: &[f64], index: &[f64], nav: f64) -> Result<f64> {
     super::irr(&cf, None)
 }
 
-#[doc = include_str!("../../docs/_inline/pe/pme_plus.md")]
+#[doc = include_str!("../docs/pe/pme_plus.md")]
 pub fn pme_plus_2(
     contributions: &[f64],
     distributions: &[f64],
@@ -210,13 +221,13 @@ pub fn pme_plus_2(
 
     super::irr(&cf, None)
 }
-#[doc = include_str!("../../docs/_inline/pe/ln_pme_nav.md")]
+#[doc = include_str!("../docs/pe/ln_pme_nav.md")]
 pub fn ln_pme_nav(amounts: &[f64], index: &[f64]) -> Result<f64> {
     check_input_len(amounts, index)?;
     Ok(-utils::sum_pairwise_mul(amounts, &index_performance(index)))
 }
 
-#[doc = include_str!("../../docs/_inline/pe/ln_pme_nav.md")]
+#[doc = include_str!("../docs/pe/ln_pme_nav.md")]
 pub fn ln_pme_nav_2(contributions: &[f64], distributions: &[f64], index: &[f64]) -> Result<f64> {
     check_input_len(contributions, index)?;
     check_input_len(distributions, index)?;
@@ -225,7 +236,7 @@ pub fn ln_pme_nav_2(contributions: &[f64], distributions: &[f64], index: &[f64])
     ln_pme_nav(&amounts, index)
 }
 
-#[doc = include_str!("../../docs/_inline/pe/ln_pme.md")]
+#[doc = include_str!("../docs/pe/ln_pme.md")]
 pub fn ln_pme(amounts: &[f64], index: &[f64]) -> Result<f64> {
     let pme_nav = ln_pme_nav(amounts, index)?;
     let mut cf = amounts.to_owned();
@@ -235,7 +246,7 @@ pub fn ln_pme(amounts: &[f64], index: &[f64]) -> Result<f64> {
     super::irr(&cf, None)
 }
 
-#[doc = include_str!("../../docs/_inline/pe/ln_pme.md")]
+#[doc = include_str!("../docs/pe/ln_pme.md")]
 pub fn ln_pme_2(contributions: &[f64], distributions: &[f64], index: &[f64]) -> Result<f64> {
     let mut amounts = combine_amounts(contributions, distributions);
     let pme_nav = ln_pme_nav(&amounts, index)?;
@@ -245,7 +256,7 @@ pub fn ln_pme_2(contributions: &[f64], distributions: &[f64], index: &[f64]) ->
     super::irr(&amounts, None)
 }
 
-#[doc = include_str!("../../docs/_inline/pe/direct_alpha.md")]
+#[doc = include_str!("../docs/pe/direct_alpha.md")]
 pub fn direct_alpha(amounts: &[f64], index: &[f64], nav: f64) -> Result<f64> {
     let mut cf = ks_pme_flows(amounts, index)?;
     if let Some(last) = cf.last_mut() {
@@ -254,7 +265,7 @@ pub fn direct_alpha(amounts: &[f64], index: &[f64], nav: f64) -> Result<f64> {
     super::irr(&cf, None)
 }
 
-#[doc = include_str!("../../docs/_inline/pe/direct_alpha.md")]
+#[doc = include_str!("../docs/pe/direct_alpha.md")]
 pub fn direct_alpha_2(
     contributions: &[f64],
     distributions: &[f64],
@@ -265,9 +276,153 @@ pub fn direct_alpha_2(
     direct_alpha(amounts, index, nav)
 }
 
+/// Projected fund cash flows. Contributions and distributions are positive.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FundProjection {
+    pub contributions: Vec<f64>,
+    pub distributions: Vec<f64>,
+    pub nav: Vec<f64>,
+}
+
+/// Parameters of the Takahashi-Alexander model.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TakahashiAlexanderParams {
+    pub rate_of_contribution: f64,
+    pub bow: f64,
+    pub growth: f64,
+    pub yield_: f64,
+}
+
+#[doc = include_str!("../docs/pe/takahashi_alexander.md")]
+pub fn takahashi_alexander(
+    commitment: f64,
+    fund_life: f64,
+    params: &TakahashiAlexanderParams,
+    periods: usize,
+) -> Result<FundProjection> {
+    check_fund_terms(commitment, fund_life)?;
+
+    // the rate grows (or falls) monotonically with `t`, so the ends bound it
+    let rate_of_distribution = |t: usize| params.yield_ * (t as f64 / fund_life).powf(params.bow);
+    if periods > 0 && [1, periods].iter().any(|&t| !(0. ..=1.).contains(&rate_of_distribution(t))) {
+        return Err(InvalidPaymentsError::new(
+            "The distribution rate yield_ * (t / fund_life) ^ bow must be between 0 and 1",
+        ));
+    }
+
+    let mut contributions = Vec::with_capacity(periods);
+    let mut distributions = Vec::with_capacity(periods);
+    let mut nav = Vec::with_capacity(periods);
+
+    let (mut paid_in, mut last_nav) = (0., 0.);
+
+    for t in 1..=periods {
+        let c = params.rate_of_contribution * (commitment - paid_in);
+        let d = rate_of_distribution(t) * last_nav * (1. + params.growth);
+
+        paid_in += c;
+        last_nav = last_nav * (1. + params.growth) + c - d;
+
+        contributions.push(c);
+        distributions.push(d);
+        nav.push(last_nav);
+    }
+
+    Ok(FundProjection {
+        contributions,
+        distributions,
+        nav,
+    })
+}
+
+#[doc = include_str!("../docs/pe/takahashi_alexander.md")]
+pub fn takahashi_alexander_calibrate(
+    commitment: f64,
+    fund_life: f64,
+    contributions: &[f64],
+    distributions: &[f64],
+    nav: &[f64],
+) -> Result<TakahashiAlexanderParams> {
+    check_fund_terms(commitment, fund_life)?;
+    check_input_len(contributions, nav)?;
+    check_input_len(distributions, nav)?;
+
+    let n = nav.len();
+    let paid_in: f64 = contributions.iter().sum();
+
+    if paid_in >= commitment {
+        return Err(InvalidPaymentsError::new("Contributions must be less than commitment"));
+    }
+
+    // paid_in = commitment * (1 - (1 - rc)^n)
+    let rate_of_contribution = 1. - (1. - paid_in / commitment).powf(1. / n as f64);
+
+    // growth is the rate that rolls the realized net flows forward into the last NAV:
+    // nav[n] = sum((c[t] - d[t]) * (1 + g)^(n - t))
+    let last_nav = nav[n - 1];
+    let f = |g: f64| {
+        contributions.iter().zip(distributions).fold(0., |acc, (c, d)| acc * (1. + g) + c - d)
+            - last_nav
+    };
+    let growth = brentq(&f, -0.999999999999999, 100., 100);
+
+    if !growth.is_finite() {
+        return Err(InvalidPaymentsError::new("Unable to calibrate the growth rate"));
+    }
+
+    // rd[t] = yield * (t / fund_life)^bow  =>
+    // ln(rd[t]) = ln(yield) + bow * ln(t / fund_life)
+    // the model is linear in logs, so ordinary least squares gives the exact fit
+    // and, unlike the growth rate, needs no root finding
+    let points: Vec<(f64, f64)> = (1..n)
+        .filter(|&t| distributions[t] > 0. && nav[t - 1] > 0.)
+        .map(|t| {
+            let rd = distributions[t] / (nav[t - 1] * (1. + growth));
+            (((t + 1) as f64 / fund_life).ln(), rd.ln())
+        })
+        .collect();
+
+    if points.len() < 2 {
+        return Err(InvalidPaymentsError::new(
+            "At least two periods with distributions are required",
+        ));
+    }
+
+    let (bow, intercept) = linear_regression(&points);
+
+    Ok(TakahashiAlexanderParams {
+        rate_of_contribution,
+        bow,
+        growth,
+        yield_: intercept.exp(),
+    })
+}
+
+fn check_fund_terms(commitment: f64, fund_life: f64) -> Result<()> {
+    if commitment <= 0. {
+        Err(InvalidPaymentsError::new("Commitment must be positive"))
+    } else if fund_life <= 0. {
+        Err(InvalidPaymentsError::new("Fund life must be positive"))
+    } else {
+        Ok(())
+    }
+}
+
+/// Ordinary least squares fit of `y = intercept + slope * x`, returns `(slope, intercept)`.
+fn linear_regression(points: &[(f64, f64)]) -> (f64, f64) {
+    let n = points.len() as f64;
+    let (sx, sy) = points.iter().fold((0., 0.), |acc, (x, y)| (acc.0 + x, acc.1 + y));
+    let (mx, my) = (sx / n, sy / n);
+    let (sxy, sxx) = points
+        .iter()
+        .fold((0., 0.), |acc, (x, y)| (acc.0 + (x - mx) * (y - my), acc.1 + (x - mx).powi(2)));
+    let slope = sxy / sxx;
+    (slope, my - slope * mx)
+}
+
 fn check_zero_contributions(contributions: f64) -> Result<()> {
     if contributions == 0. {
-        Err(InvalidPaymentsError::new("Contributions are zero"))
+        Err(InvalidPaymentsError::ZeroContributions)
     } else {
         Ok(())
     }
@@ -275,9 +430,12 @@ fn check_zero_contributions(contributions: f64) -> Result<()> {
 
 fn check_input_len(amounts: &[f64], index: &[f64]) -> Result<()> {
     if amounts.len() != index.len() {
-        Err(InvalidPaymentsError::new("Amounts must be the same length as index."))
+        Err(InvalidPaymentsError::IndexLengthMismatch {
+            amounts: amounts.len(),
+            index: index.len(),
+        })
     } else if index.is_empty() {
-        Err(InvalidPaymentsError::new("Input array must contain at least one value"))
+        Err(InvalidPaymentsError::Empty)
     } else {
         Ok(())
     }
@@ -316,7 +474,7 @@ mod tests {
     #[case(&[-12., 0., 0., 40.], 0.494)]
     #[case(&[-12., -10., -4., 40., 0., 15., 5.], 0.324)]
     fn test_irr(#[case] amounts: &[f64], #[case] expected: f64) {
-        let result = crate::core::irr(amounts, None).unwrap();
+        let result = crate::irr(amounts, None).unwrap();
         assert_approx_eq!(result, expected, 1e-3);
     }
 
@@ -325,7 +483,7 @@ mod tests {
         let amounts = &[-12.0, -10.0, -4.0, 40.0, 0.0, 15.0, 5.0];
         let finance_rate = 0.07;
         let reinvest_rate = 0.12;
-        let result = crate::core::mirr(amounts, finance_rate, reinvest_rate).unwrap();
+        let result = crate::mirr(amounts, finance_rate, reinvest_rate).unwrap();
         assert_approx_eq!(result, 0.21, 1e-3);
     }
 
@@ -486,4 +644,70 @@ mod tests {
         let result = direct_alpha_2(contributions, distributions, index, nav).unwrap();
         assert_approx_eq!(result, 0.028, 1e-3);
     }
+
+    #[rstest]
+    fn test_takahashi_alexander() {
+        let params = TakahashiAlexanderParams {
+            rate_of_contribution: 0.25,
+            bow: 2.5,
+            growth: 0.12,
+            yield_: 0.1,
+        };
+        let result = takahashi_alexander(100., 12., &params, 12).unwrap();
+
+        assert_approx_eq!(result.contributions[0], 25.);
+        assert_approx_eq!(result.contributions[1], 18.75);
+        assert_approx_eq!(result.distributions[0], 0.);
+        assert_approx_eq!(result.nav[0], 25.);
+        // 25 * 1.12 * 0.1 * (2/12)^2.5
+        assert_approx_eq!(result.distributions[1], 0.0317526, 1e-6);
+        assert_approx_eq!(result.nav[1], 25. * 1.12 + 18.75 - result.distributions[1]);
+
+        let paid_in: f64 = result.contributions.iter().sum();
+        assert_approx_eq!(paid_in, 100. * (1. - 0.75_f64.powi(12)));
+    }
+
+    #[rstest]
+    fn test_takahashi_alexander_distribution_rate() {
+        let params = TakahashiAlexanderParams {
+            rate_of_contribution: 0.25,
+            bow: 2.,
+            growth: 0.12,
+            yield_: 0.5,
+        };
+        // 0.5 * (20 / 10)^2 = 2
+        assert!(takahashi_alexander(100., 10., &params, 10).is_ok());
+        assert!(takahashi_alexander(100., 10., &params, 20).is_err());
+    }
+
+    #[rstest]
+    fn test_takahashi_alexander_calibrate() {
+        let expected = TakahashiAlexanderParams {
+            rate_of_contribution: 0.3,
+            bow: 2.0,
+            growth: 0.08,
+            yield_: 0.2,
+        };
+        let history = takahashi_alexander(50., 10., &expected, 8).unwrap();
+
+        let result = takahashi_alexander_calibrate(
+            50.,
+            10.,
+            &history.contributions,
+            &history.distributions,
+            &history.nav,
+        )
+        .unwrap();
+
+        assert_approx_eq!(result.rate_of_contribution, expected.rate_of_contribution);
+        assert_approx_eq!(result.bow, expected.bow, 1e-6);
+        assert_approx_eq!(result.growth, expected.growth, 1e-6);
+        assert_approx_eq!(result.yield_, expected.yield_, 1e-6);
+    }
+
+    #[rstest]
+    fn test_takahashi_alexander_calibrate_no_distributions() {
+        let result = takahashi_alexander_calibrate(100., 10., &[10., 20.], &[0., 0.], &[10., 32.]);
+        assert!(result.is_err());
+    }
 }
@@ -0,0 +1,231 @@
+use time::{util::days_in_year_month, Date, Month};
+
+use super::Result;
+use crate::{
+    models::{validate_length, DateLike, InvalidPaymentsError},
+    year_fraction, DayCount,
+};
+
+#[derive(Debug, Clone, Copy)]
+pub struct FeeTerms {
+    pub commitment: f64,
+    /// Annual fee rate on commitment during the investment period.
+    pub rate: f64,
+    /// Annual fee rate on invested capital after the investment period.
+    pub post_rate: f64,
+    /// Annual reduction of the post-investment period fee rate, e.g. 0.1 = -10% per year.
+    pub step_down: f64,
+    /// Fee payments per year.
+    pub frequency: u8,
+    /// Share of the fee income that is offset against the management fee.
+    pub offset: f64,
+    /// Annual fund expenses.
+    pub expenses: f64,
+    pub start_date: DateLike,
+    pub investment_period_end: DateLike,
+    pub end_date: DateLike,
+    pub day_count: Option<DayCount>,
+}
+
+/// Dated fee flows, paid at the beginning of each period. All amounts are positive.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct FeeFlows {
+    pub dates: Vec<DateLike>,
+    pub management_fee: Vec<f64>,
+    pub offset: Vec<f64>,
+    pub expenses: Vec<f64>,
+    /// management_fee - offset + expenses
+    pub total: Vec<f64>,
+}
+
+#[doc = include_str!("../../docs/pe/management_fees.md")]
+pub fn management_fees(
+    terms: &FeeTerms,
+    invested: (&[DateLike], &[f64]),
+    fee_income: (&[DateLike], &[f64]),
+) -> Result<FeeFlows> {
+    validate_length(invested.1, invested.0)?;
+    validate_length(fee_income.1, fee_income.0)?;
+    check_fee_terms(terms)?;
+
+    let dc = terms.day_count.unwrap_or_default();
+    let ip_end = terms.investment_period_end;
+    let months = 12 / terms.frequency as i32;
+
+    let mut result = FeeFlows::default();
+    let mut credit = 0.;
+    let mut period_start = terms.start_date;
+
+    for n in 1.. {
+        if period_start >= terms.end_date {
+            break;
+        }
+
+        let period_end = add_months(&terms.start_date, months * n).min(terms.end_date);
+
+        // a period that straddles the end of the investment period is charged on both bases
+        let commitment_fee = if period_start < ip_end {
+            terms.commitment
+                * terms.rate
+                * year_fraction(&period_start, &period_end.min(ip_end), dc)
+        } else {
+            0.
+        };
+
+        let invested_fee = if period_end > ip_end {
+            let from = period_start.max(ip_end);
+            let years_after = year_fraction(&ip_end, &from, dc).trunc();
+            let rate = terms.post_rate * (1. - terms.step_down).powf(years_after);
+            sum_until(invested, &from) * rate * year_fraction(&from, &period_end, dc)
+        } else {
+            0.
+        };
+
+        let fee = commitment_fee + invested_fee.max(0.);
+
+        // fee income received during the period reduces the fee,
+        // the unused credit is carried forward
+        credit += terms.offset * sum_between(fee_income, &period_start, &period_end);
+        let offset = credit.min(fee);
+        credit -= offset;
+
+        let expenses = terms.expenses * year_fraction(&period_start, &period_end, dc);
+
+        result.dates.push(period_start);
+        result.management_fee.push(fee);
+        result.offset.push(offset);
+        result.expenses.push(expenses);
+        result.total.push(fee - offset + expenses);
+
+        period_start = period_end;
+    }
+
+    Ok(result)
+}
+
+fn check_fee_terms(terms: &FeeTerms) -> Result<()> {
+    if terms.commitment <= 0. {
+        Err(InvalidPaymentsError::new("Commitment must be positive"))
+    } else if !(terms.rate >= 0. && terms.rate.is_finite()) {
+        Err(InvalidPaymentsError::new("Rate must be positive"))
+    } else if !(terms.post_rate >= 0. && terms.post_rate.is_finite()) {
+        Err(InvalidPaymentsError::new("Post rate must be positive"))
+    } else if !(0. ..=1.).contains(&terms.step_down) {
+        Err(InvalidPaymentsError::new("Step down must be between 0 and 1"))
+    } else if !(0. ..=1.).contains(&terms.offset) {
+        Err(InvalidPaymentsError::new("Offset must be between 0 and 1"))
+    } else if !(terms.expenses >= 0. && terms.expenses.is_finite()) {
+        Err(InvalidPaymentsError::new("Expenses must be positive"))
+    } else if ![1, 2, 3, 4, 6, 12].contains(&terms.frequency) {
+        Err(InvalidPaymentsError::new("Frequency must be one of: 1, 2, 3, 4, 6, 12"))
+    } else if terms.start_date > terms.investment_period_end
+        || terms.investment_period_end > terms.end_date
+    {
+        Err(InvalidPaymentsError::new("Investment period must end between start date and end date"))
+    } else {
+        Ok(())
+    }
+}
+
+fn sum_until(flows: (&[DateLike], &[f64]), date: &DateLike) -> f64 {
+    flows.0.iter().zip(flows.1).filter(|(d, _)| *d <= date).map(|(_, a)| a).sum()
+}
+
+fn sum_between(flows: (&[DateLike], &[f64]), start: &DateLike, end: &DateLike) -> f64 {
+    flows.0.iter().zip(flows.1).filter(|(d, _)| *d >= start && *d < end).map(|(_, a)| a).sum()
+}
+
+fn add_months(date: &DateLike, months: i32) -> DateLike {
+    let date: &Date = date.as_ref();
+    let total = date.year() * 12 + date.month() as i32 - 1 + months;
+    let year = total.div_euclid(12);
+    let month = Month::try_from((total.rem_euclid(12) + 1) as u8).unwrap();
+    let day = date.day().min(days_in_year_month(year, month));
+    Date::from_calendar_date(year, month, day).unwrap().into()
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_approx_eq::assert_approx_eq;
+    use rstest::rstest;
+
+    use super::*;
+
+    fn terms() -> FeeTerms {
+        FeeTerms {
+            commitment: 100.,
+            rate: 0.02,
+            post_rate: 0.015,
+            step_down: 0.,
+            frequency: 1,
+            offset: 1.,
+            expenses: 0.,
+            start_date: "2021-01-01".parse().unwrap(),
+            investment_period_end: "2023-01-01".parse().unwrap(),
+            end_date: "2025-01-01".parse().unwrap(),
+            day_count: Some(DayCount::THIRTY_360_ISDA),
+        }
+    }
+
+    #[rstest]
+    #[case(FeeTerms { rate: -0.02, ..terms() }, "Rate must be positive")]
+    #[case(FeeTerms { post_rate: f64::NAN, ..terms() }, "Post rate must be positive")]
+    #[case(FeeTerms { step_down: -0.1, ..terms() }, "Step down must be between 0 and 1")]
+    #[case(FeeTerms { offset: 1.5, ..terms() }, "Offset must be between 0 and 1")]
+    #[case(FeeTerms { expenses: -1., ..terms() }, "Expenses must be positive")]
+    fn test_invalid_fee_terms(#[case] terms: FeeTerms, #[case] message: &str) {
+        let err = management_fees(&terms, (&[], &[]), (&[], &[])).unwrap_err();
+        assert_eq!(err.to_string(), message);
+    }
+
+    #[rstest]
+    fn test_add_months() {
+        let date: DateLike = "2021-01-31".parse().unwrap();
+        assert_eq!(add_months(&date, 1), "2021-02-28".parse().unwrap());
+        assert_eq!(add_months(&date, 13), "2022-02-28".parse().unwrap());
+        assert_eq!(add_months(&date, -2), "2020-11-30".parse().unwrap());
+    }
+
+    #[rstest]
+    fn test_management_fees() {
+        let invested_dates = ["2021-06-01".parse().unwrap(), "2023-06-01".parse().unwrap()];
+        let invested_amounts = [80., -20.];
+
+        let result =
+            management_fees(&terms(), (&invested_dates, &invested_amounts), (&[], &[])).unwrap();
+
+        assert_eq!(result.dates.len(), 4);
+        assert_eq!(result.total, result.management_fee);
+        // commitment basis during the investment period
+        assert_approx_eq!(result.management_fee[0], 2.);
+        assert_approx_eq!(result.management_fee[1], 2.);
+        // invested capital basis after the investment period
+        assert_approx_eq!(result.management_fee[2], 80. * 0.015);
+        assert_approx_eq!(result.management_fee[3], 60. * 0.015);
+    }
+
+    #[rstest]
+    fn test_management_fees_step_down_offset_expenses() {
+        let terms = FeeTerms {
+            step_down: 0.1,
+            frequency: 2,
+            expenses: 0.5,
+            offset: 0.8,
+            ..terms()
+        };
+        let invested_dates = ["2021-01-01".parse().unwrap()];
+        let income_dates = ["2021-02-01".parse().unwrap()];
+
+        let result =
+            management_fees(&terms, (&invested_dates, &[50.]), (&income_dates, &[1.5])).unwrap();
+
+        assert_eq!(result.dates.len(), 8);
+        assert_eq!(result.dates[1], "2021-07-01".parse().unwrap());
+        // credit: 0.8 * 1.5 = 1.2, semi-annual fee: 1.0
+        assert_approx_eq!(result.offset[0], 1.);
+        assert_approx_eq!(result.offset[1], 0.2);
+        assert_approx_eq!(result.expenses[0], 0.25);
+        assert_approx_eq!(result.management_fee[5], 50. * 0.015 / 2.);
+        assert_approx_eq!(result.management_fee[6], 50. * 0.015 * 0.9 / 2.);
+    }
+}
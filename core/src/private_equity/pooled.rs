@@ -0,0 +1,287 @@
+use std::{collections::BTreeMap, str::FromStr};
+
+use super::{dpi, rvpi, tvpi, Result};
+use crate::{
+    models::{validate_length, DateLike, InvalidPaymentsError},
+    xirr, DayCount,
+};
+
+/// Dated cash flows of a single fund and its NAV at the end of the period.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct FundFlows {
+    pub dates: Vec<DateLike>,
+    pub amounts: Vec<f64>,
+    pub nav: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PooledWeights<'a> {
+    Equal,
+    Commitment(&'a [f64]),
+    PaidIn,
+}
+
+impl FromStr for PooledWeights<'_> {
+    type Err = &'static str;
+
+    /// Parses weights that do not require extra data, use `PooledWeights::Commitment` directly.
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "equal" => Ok(PooledWeights::Equal),
+            "paid_in" | "paid-in" => Ok(PooledWeights::PaidIn),
+            _ => Err("Invalid weights, expected: 'equal', 'commitment' or 'paid_in'"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct PooledMetrics {
+    pub irr: f64,
+    pub tvpi: f64,
+    pub dpi: f64,
+    pub rvpi: f64,
+    pub average_irr: f64,
+    pub median_irr: f64,
+    pub fund_irr: Vec<f64>,
+    pub fund_tvpi: Vec<f64>,
+    pub fund_dpi: Vec<f64>,
+    pub fund_rvpi: Vec<f64>,
+}
+
+#[doc = include_str!("../../docs/pe/pooled.md")]
+pub fn pooled_flows(funds: &[FundFlows]) -> Result<(Vec<DateLike>, Vec<f64>)> {
+    check_funds(funds)?;
+
+    let mut flows: BTreeMap<DateLike, f64> = BTreeMap::new();
+
+    for fund in funds {
+        for (date, amount) in fund.dates.iter().zip(&fund.amounts) {
+            *flows.entry(*date).or_default() += amount;
+        }
+    }
+
+    // all NAVs are valued at the last date of the portfolio
+    let nav: f64 = funds.iter().map(|f| f.nav).sum();
+    if let Some(mut last) = flows.last_entry() {
+        *last.get_mut() += nav;
+    }
+
+    Ok(flows.into_iter().unzip())
+}
+
+#[doc = include_str!("../../docs/pe/pooled.md")]
+pub fn pooled_irr(funds: &[FundFlows], day_count: Option<DayCount>) -> Result<f64> {
+    let (dates, amounts) = pooled_flows(funds)?;
+    xirr(&dates, &amounts, None, day_count)
+}
+
+#[doc = include_str!("../../docs/pe/pooled.md")]
+pub fn pooled_dpi(funds: &[FundFlows]) -> Result<f64> {
+    check_funds(funds)?;
+    dpi(&concat_amounts(funds))
+}
+
+#[doc = include_str!("../../docs/pe/pooled.md")]
+pub fn pooled_tvpi(funds: &[FundFlows]) -> Result<f64> {
+    check_funds(funds)?;
+    tvpi(&concat_amounts(funds), total_nav(funds))
+}
+
+#[doc = include_str!("../../docs/pe/pooled.md")]
+pub fn pooled_rvpi(funds: &[FundFlows]) -> Result<f64> {
+    check_funds(funds)?;
+    rvpi(&contributions(&concat_amounts(funds)), total_nav(funds))
+}
+
+#[doc = include_str!("../../docs/pe/pooled.md")]
+pub fn pooled_metrics(
+    funds: &[FundFlows],
+    weights: PooledWeights,
+    day_count: Option<DayCount>,
+) -> Result<PooledMetrics> {
+    check_funds(funds)?;
+
+    let weights = match weights {
+        PooledWeights::Equal => vec![1.; funds.len()],
+        PooledWeights::Commitment(commitments) => {
+            if commitments.len() != funds.len() {
+                return Err(InvalidPaymentsError::new(
+                    "Commitments must be the same length as funds",
+                ));
+            }
+            commitments.to_vec()
+        }
+        PooledWeights::PaidIn => {
+            funds.iter().map(|f| -contributions(&f.amounts).iter().sum::<f64>()).collect()
+        }
+    };
+
+    let mut result = PooledMetrics {
+        // the multiples are still defined when the pooled IRR has no solution
+        irr: pooled_irr(funds, day_count).unwrap_or(f64::NAN),
+        tvpi: pooled_tvpi(funds)?,
+        dpi: pooled_dpi(funds)?,
+        rvpi: pooled_rvpi(funds)?,
+        ..Default::default()
+    };
+
+    // NAVs are valued at the last date of the portfolio, the same as in `pooled_flows`
+    let valuation_date = funds.iter().flat_map(|f| f.dates.iter()).max().copied();
+
+    for fund in funds {
+        let mut dates = fund.dates.clone();
+        let mut amounts = fund.amounts.clone();
+        if let Some(date) = valuation_date {
+            dates.push(date);
+            amounts.push(fund.nav);
+        }
+        // a single fund must not fail the whole portfolio
+        result.fund_irr.push(xirr(&dates, &amounts, None, day_count).unwrap_or(f64::NAN));
+        result.fund_tvpi.push(tvpi(&fund.amounts, fund.nav).unwrap_or(f64::NAN));
+        result.fund_dpi.push(dpi(&fund.amounts).unwrap_or(f64::NAN));
+        result.fund_rvpi.push(rvpi(&contributions(&fund.amounts), fund.nav).unwrap_or(f64::NAN));
+    }
+
+    result.average_irr = weighted_mean(&result.fund_irr, &weights);
+    result.median_irr = weighted_median(&result.fund_irr, &weights);
+
+    Ok(result)
+}
+
+/// Weighted mean, NaN values are ignored.
+pub fn weighted_mean(values: &[f64], weights: &[f64]) -> f64 {
+    let (sum, total) = values
+        .iter()
+        .zip(weights)
+        .filter(|(v, _)| v.is_finite())
+        .fold((0., 0.), |acc, (v, w)| (acc.0 + v * w, acc.1 + w));
+
+    sum / total
+}
+
+/// Weighted median, NaN values are ignored.
+pub fn weighted_median(values: &[f64], weights: &[f64]) -> f64 {
+    let mut pairs: Vec<(f64, f64)> =
+        values.iter().zip(weights).filter(|(v, _)| v.is_finite()).map(|(&v, &w)| (v, w)).collect();
+
+    pairs.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let half = pairs.iter().map(|p| p.1).sum::<f64>() / 2.;
+    let mut cumulative = 0.;
+
+    for (i, (value, weight)) in pairs.iter().enumerate() {
+        cumulative += weight;
+        if cumulative > half {
+            return *value;
+        }
+        if cumulative == half {
+            // exactly in the middle between two values
+            return pairs.get(i + 1).map_or(*value, |next| (value + next.0) / 2.);
+        }
+    }
+
+    f64::NAN
+}
+
+fn check_funds(funds: &[FundFlows]) -> Result<()> {
+    if funds.is_empty() {
+        return Err(InvalidPaymentsError::new("At least one fund is required"));
+    }
+    funds.iter().try_for_each(|f| validate_length(&f.amounts, &f.dates))
+}
+
+fn concat_amounts(funds: &[FundFlows]) -> Vec<f64> {
+    funds.iter().flat_map(|f| f.amounts.iter().copied()).collect()
+}
+
+fn contributions(amounts: &[f64]) -> Vec<f64> {
+    amounts.iter().copied().filter(|a| *a < 0.).collect()
+}
+
+fn total_nav(funds: &[FundFlows]) -> f64 {
+    funds.iter().map(|f| f.nav).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_approx_eq::assert_approx_eq;
+    use rstest::rstest;
+
+    use super::*;
+
+    fn fund(flows: &[(&str, f64)], nav: f64) -> FundFlows {
+        FundFlows {
+            dates: flows.iter().map(|f| f.0.parse().unwrap()).collect(),
+            amounts: flows.iter().map(|f| f.1).collect(),
+            nav,
+        }
+    }
+
+    fn funds() -> Vec<FundFlows> {
+        vec![
+            fund(&[("2020-01-01", -100.), ("2021-01-01", 50.)], 80.),
+            fund(&[("2020-01-01", -50.), ("2020-07-01", -50.), ("2021-01-01", 20.)], 60.),
+        ]
+    }
+
+    #[rstest]
+    fn test_pooled_flows() {
+        let (dates, amounts) = pooled_flows(&funds()).unwrap();
+        assert_eq!(dates.len(), 3);
+        assert_eq!(amounts, vec![-150., -50., 210.]);
+    }
+
+    #[rstest]
+    fn test_pooled_multiples() {
+        let funds = funds();
+        assert_approx_eq!(pooled_dpi(&funds).unwrap(), 70. / 200.);
+        assert_approx_eq!(pooled_rvpi(&funds).unwrap(), 140. / 200.);
+        assert_approx_eq!(pooled_tvpi(&funds).unwrap(), 210. / 200.);
+    }
+
+    #[rstest]
+    fn test_pooled_metrics() {
+        let funds = funds();
+        let result = pooled_metrics(&funds, PooledWeights::PaidIn, None).unwrap();
+
+        assert_approx_eq!(result.irr, pooled_irr(&funds, None).unwrap());
+        assert_approx_eq!(result.fund_tvpi[0], 1.3);
+        assert_approx_eq!(result.fund_tvpi[1], 0.8);
+        assert_approx_eq!(result.fund_irr[0], 1.3_f64.powf(365. / 366.) - 1., 1e-6);
+        // equal paid-in capital
+        assert_approx_eq!(result.average_irr, (result.fund_irr[0] + result.fund_irr[1]) / 2.);
+        assert_approx_eq!(result.median_irr, result.average_irr);
+
+        let result =
+            pooled_metrics(&funds, PooledWeights::Commitment(&[300., 100.]), None).unwrap();
+        assert_approx_eq!(result.median_irr, result.fund_irr[0]);
+    }
+
+    #[rstest]
+    fn test_pooled_metrics_without_irr() {
+        let funds = vec![
+            fund(&[("2020-01-01", -100.)], 0.),
+            fund(&[("2020-07-01", -50.), ("2021-01-01", -50.)], 0.),
+        ];
+        let result = pooled_metrics(&funds, PooledWeights::Equal, None).unwrap();
+
+        assert!(result.irr.is_nan());
+        assert!(result.average_irr.is_nan());
+        assert_approx_eq!(result.dpi, 0.);
+        assert_approx_eq!(result.tvpi, 0.);
+        assert_approx_eq!(result.fund_tvpi[1], 0.);
+    }
+
+    #[rstest]
+    #[case(&[1., 2., 3.], &[1., 1., 1.], 2.)]
+    #[case(&[1., 2., 3., 4.], &[1., 1., 1., 1.], 2.5)]
+    #[case(&[3., 1., 2.], &[1., 5., 1.], 1.)]
+    #[case(&[1., f64::NAN, 3.], &[1., 10., 3.], 3.)]
+    fn test_weighted_median(
+        #[case] values: &[f64],
+        #[case] weights: &[f64],
+        #[case] expected: f64,
+    ) {
+        assert_eq!(weighted_median(values, weights), expected);
+    }
+}
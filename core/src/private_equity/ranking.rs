@@ -0,0 +1,298 @@
+use std::{fmt, str::FromStr};
+
+use super::{dpi, ks_pme, tvpi, Result};
+use crate::{
+    models::{validate_length, DateLike, InvalidPaymentsError},
+    xirr, DayCount,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RankMethod {
+    /// Linear interpolation between the closest ranks, (n - 1) * p.
+    /// The same as Excel PERCENTRANK.INC / QUARTILE.INC and numpy "linear".
+    #[default]
+    Inclusive,
+    /// Linear interpolation between the closest ranks, (n + 1) * p.
+    /// The same as Excel PERCENTRANK.EXC / QUARTILE.EXC.
+    Exclusive,
+    /// Share of the peers less than or equal to the value.
+    Weak,
+    /// Share of the peers strictly less than the value.
+    Strict,
+    /// Average of `Weak` and `Strict`.
+    Mean,
+}
+
+impl fmt::Display for RankMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let repr = match self {
+            RankMethod::Inclusive => "inclusive",
+            RankMethod::Exclusive => "exclusive",
+            RankMethod::Weak => "weak",
+            RankMethod::Strict => "strict",
+            RankMethod::Mean => "mean",
+        };
+        write!(f, "{}", repr)
+    }
+}
+
+impl FromStr for RankMethod {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "inclusive" | "linear" => Ok(RankMethod::Inclusive),
+            "exclusive" => Ok(RankMethod::Exclusive),
+            "weak" => Ok(RankMethod::Weak),
+            "strict" => Ok(RankMethod::Strict),
+            "mean" => Ok(RankMethod::Mean),
+            _ => Err("Invalid rank method, expected: 'inclusive', 'exclusive', 'weak', 'strict' or 'mean'"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BenchmarkMetric {
+    Tvpi,
+    Dpi,
+    Irr,
+    KsPme,
+}
+
+impl FromStr for BenchmarkMetric {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "tvpi" => Ok(BenchmarkMetric::Tvpi),
+            "dpi" => Ok(BenchmarkMetric::Dpi),
+            "irr" | "xirr" => Ok(BenchmarkMetric::Irr),
+            "ks_pme" => Ok(BenchmarkMetric::KsPme),
+            _ => Err("Invalid metric, expected: 'tvpi', 'dpi', 'xirr' or 'ks_pme'"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ranking {
+    pub value: f64,
+    /// Share of the peers ranked below the value, from 0 to 1.
+    pub percentile_rank: f64,
+    /// 1 is the top quartile, 4 is the bottom quartile.
+    pub quartile: u8,
+}
+
+#[doc = include_str!("../../docs/pe/quartile.md")]
+pub fn percentile_rank(value: f64, peers: &[f64], method: RankMethod) -> Result<f64> {
+    let peers = sorted_peers(peers)?;
+    let n = peers.len() as f64;
+
+    let below = peers.partition_point(|p| *p < value) as f64;
+    let below_or_equal = peers.partition_point(|p| *p <= value) as f64;
+
+    let rank = match method {
+        RankMethod::Weak => below_or_equal / n,
+        RankMethod::Strict => below / n,
+        RankMethod::Mean => (below + below_or_equal) / (2. * n),
+        RankMethod::Inclusive if peers.len() == 1 => (value >= peers[0]) as u8 as f64,
+        RankMethod::Inclusive => position(value, &peers) / (n - 1.),
+        RankMethod::Exclusive => {
+            ((position(value, &peers) + 1.) / (n + 1.)).clamp(1. / (n + 1.), n / (n + 1.))
+        }
+    };
+
+    Ok(rank)
+}
+
+#[doc = include_str!("../../docs/pe/quartile.md")]
+pub fn quartile(value: f64, peers: &[f64], method: RankMethod) -> Result<u8> {
+    Ok(rank_to_quartile(percentile_rank(value, peers, method)?))
+}
+
+#[doc = include_str!("../../docs/pe/quartile.md")]
+pub fn quantile(peers: &[f64], p: f64, method: RankMethod) -> Result<f64> {
+    if !(0. ..=1.).contains(&p) {
+        return Err(InvalidPaymentsError::new("Quantile must be between 0 and 1"));
+    }
+
+    let peers = sorted_peers(peers)?;
+    let n = peers.len();
+
+    let h = match method {
+        RankMethod::Inclusive => (n - 1) as f64 * p,
+        RankMethod::Exclusive => ((n + 1) as f64 * p - 1.).clamp(0., (n - 1) as f64),
+        // inverted empirical distribution function
+        _ => return Ok(peers[((n as f64 * p).ceil() as usize).clamp(1, n) - 1]),
+    };
+
+    let lo = h.floor() as usize;
+    let hi = (lo + 1).min(n - 1);
+    Ok(peers[lo] + (h - lo as f64) * (peers[hi] - peers[lo]))
+}
+
+/// Lower quartile, median and upper quartile of the peers.
+pub fn quartile_breakpoints(peers: &[f64], method: RankMethod) -> Result<[f64; 3]> {
+    Ok([
+        quantile(peers, 0.25, method)?,
+        quantile(peers, 0.5, method)?,
+        quantile(peers, 0.75, method)?,
+    ])
+}
+
+#[doc = include_str!("../../docs/pe/quartile.md")]
+pub fn rank_fund(value: f64, peers: &[f64], method: RankMethod) -> Result<Ranking> {
+    let percentile_rank = percentile_rank(value, peers, method)?;
+    Ok(Ranking {
+        value,
+        percentile_rank,
+        quartile: rank_to_quartile(percentile_rank),
+    })
+}
+
+/// Computes the fund's own metric from its flows. NAV is valued at the last date.
+pub fn fund_metric(
+    metric: BenchmarkMetric,
+    dates: &[DateLike],
+    amounts: &[f64],
+    nav: f64,
+    index: Option<&[f64]>,
+    day_count: Option<DayCount>,
+) -> Result<f64> {
+    validate_length(amounts, dates)?;
+
+    match metric {
+        BenchmarkMetric::Tvpi => tvpi(amounts, nav),
+        BenchmarkMetric::Dpi => dpi(amounts),
+        BenchmarkMetric::Irr => {
+            let mut amounts = amounts.to_vec();
+            if let Some(last) = amounts.last_mut() {
+                *last += nav;
+            }
+            xirr(dates, &amounts, None, day_count)
+        }
+        BenchmarkMetric::KsPme => match index {
+            Some(index) => ks_pme(amounts, index, nav),
+            None => Err(InvalidPaymentsError::new("KS-PME requires index")),
+        },
+    }
+}
+
+fn rank_to_quartile(rank: f64) -> u8 {
+    match rank {
+        r if r >= 0.75 => 1,
+        r if r >= 0.5 => 2,
+        r if r >= 0.25 => 3,
+        _ => 4,
+    }
+}
+
+// zero-based fractional position of the value in the sorted peers,
+// tied peers take the position of the first of them
+fn position(value: f64, peers: &[f64]) -> f64 {
+    let n = peers.len();
+    if value <= peers[0] {
+        return 0.;
+    }
+    if value > peers[n - 1] {
+        return (n - 1) as f64;
+    }
+    let i = peers.partition_point(|p| *p < value);
+    if peers[i] == value {
+        return i as f64;
+    }
+    let below = peers[i - 1];
+    let j = peers.partition_point(|p| *p < below);
+    j as f64 + (i - j) as f64 * (value - below) / (peers[i] - below)
+}
+
+// NaN values are ignored
+fn sorted_peers(peers: &[f64]) -> Result<Vec<f64>> {
+    let mut peers: Vec<f64> = peers.iter().copied().filter(|p| !p.is_nan()).collect();
+    if peers.is_empty() {
+        return Err(InvalidPaymentsError::new("Peers must contain at least one value"));
+    }
+    peers.sort_by(f64::total_cmp);
+    Ok(peers)
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_approx_eq::assert_approx_eq;
+    use rstest::rstest;
+
+    use super::*;
+
+    const PEERS: [f64; 5] = [1.5, 0.8, 1.2, f64::NAN, 2.0];
+
+    #[rstest]
+    #[case(1.2, RankMethod::Inclusive, 1. / 3.)]
+    #[case(1.35, RankMethod::Inclusive, 0.5)]
+    #[case(0.5, RankMethod::Inclusive, 0.)]
+    #[case(3.0, RankMethod::Inclusive, 1.)]
+    #[case(1.2, RankMethod::Exclusive, 0.4)]
+    #[case(0.5, RankMethod::Exclusive, 0.2)]
+    #[case(1.2, RankMethod::Weak, 0.5)]
+    #[case(1.2, RankMethod::Strict, 0.25)]
+    #[case(1.2, RankMethod::Mean, 0.375)]
+    fn test_percentile_rank(#[case] value: f64, #[case] method: RankMethod, #[case] expected: f64) {
+        assert_approx_eq!(percentile_rank(value, &PEERS, method).unwrap(), expected);
+    }
+
+    #[rstest]
+    #[case(1., RankMethod::Inclusive, 1. / 3.)]
+    #[case(2., RankMethod::Inclusive, 1.)]
+    #[case(1.5, RankMethod::Inclusive, 2. / 3.)]
+    #[case(1., RankMethod::Exclusive, 0.4)]
+    fn test_percentile_rank_ties(
+        #[case] value: f64,
+        #[case] method: RankMethod,
+        #[case] expected: f64,
+    ) {
+        // the same as Excel PERCENTRANK.INC / PERCENTRANK.EXC
+        let peers = [0., 1., 1., 2.];
+        assert_approx_eq!(percentile_rank(value, &peers, method).unwrap(), expected);
+        let peers = [0., 1., 2., 2.];
+        assert_approx_eq!(percentile_rank(2., &peers, RankMethod::Inclusive).unwrap(), 2. / 3.);
+    }
+
+    #[rstest]
+    #[case(RankMethod::Inclusive, [1.1, 1.35, 1.625])]
+    #[case(RankMethod::Exclusive, [0.9, 1.35, 1.875])]
+    #[case(RankMethod::Weak, [0.8, 1.2, 1.5])]
+    fn test_quartile_breakpoints(#[case] method: RankMethod, #[case] expected: [f64; 3]) {
+        let result = quartile_breakpoints(&PEERS, method).unwrap();
+        for (a, e) in result.iter().zip(expected) {
+            assert_approx_eq!(a, e);
+        }
+    }
+
+    #[rstest]
+    #[case(2.5, 1)]
+    #[case(1.6, 2)]
+    #[case(1.2, 3)]
+    #[case(0.9, 4)]
+    fn test_quartile(#[case] value: f64, #[case] expected: u8) {
+        assert_eq!(quartile(value, &PEERS, RankMethod::Inclusive).unwrap(), expected);
+        let breakpoints = quartile_breakpoints(&PEERS, RankMethod::Inclusive).unwrap();
+        assert_eq!(4 - breakpoints.iter().filter(|b| value >= **b).count() as u8, expected);
+    }
+
+    #[rstest]
+    fn test_rank_fund_metric() {
+        let dates: Vec<DateLike> =
+            ["2020-01-01", "2021-01-01"].iter().map(|d| d.parse().unwrap()).collect();
+        let value =
+            fund_metric(BenchmarkMetric::Tvpi, &dates, &[-100., 50.], 100., None, None).unwrap();
+        let result = rank_fund(value, &PEERS, RankMethod::Inclusive).unwrap();
+
+        assert_eq!(result.value, 1.5);
+        assert_approx_eq!(result.percentile_rank, 2. / 3.);
+        assert_eq!(result.quartile, 2);
+        assert!(fund_metric(BenchmarkMetric::KsPme, &dates, &[-100., 50.], 0., None, None).is_err());
+    }
+
+    #[rstest]
+    fn test_empty_peers() {
+        assert!(percentile_rank(1., &[f64::NAN], RankMethod::Inclusive).is_err());
+    }
+}
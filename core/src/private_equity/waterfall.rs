@@ -0,0 +1,388 @@
+use std::{collections::HashMap, fmt, str::FromStr};
+
+use super::Result;
+use crate::{
+    models::{validate_length, DateLike, InvalidPaymentsError},
+    year_fraction, DayCount,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WaterfallKind {
+    /// Whole-fund waterfall: carried interest is paid only after
+    /// the capital of the whole fund has been returned.
+    #[default]
+    European,
+    /// Deal-by-deal waterfall: each deal runs its own waterfall.
+    American,
+}
+
+impl fmt::Display for WaterfallKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let repr = match self {
+            WaterfallKind::European => "european",
+            WaterfallKind::American => "american",
+        };
+        write!(f, "{}", repr)
+    }
+}
+
+impl FromStr for WaterfallKind {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "european" | "whole-fund" | "whole_fund" => Ok(WaterfallKind::European),
+            "american" | "deal-by-deal" | "deal_by_deal" => Ok(WaterfallKind::American),
+            _ => Err("Invalid waterfall kind"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct WaterfallTerms {
+    /// Annual preferred return rate.
+    pub hurdle: f64,
+    /// GP share of the profits.
+    pub carry: f64,
+    /// GP share of the distributions in the catch-up tier, 1.0 means full catch-up.
+    pub catch_up: f64,
+    pub kind: WaterfallKind,
+    pub clawback: bool,
+    pub day_count: Option<DayCount>,
+}
+
+impl Default for WaterfallTerms {
+    fn default() -> Self {
+        Self {
+            hurdle: 0.08,
+            carry: 0.2,
+            catch_up: 1.0,
+            kind: WaterfallKind::default(),
+            clawback: true,
+            day_count: None,
+        }
+    }
+}
+
+/// Allocations of each distribution between the tiers of the waterfall.
+/// All vectors are aligned with the input dates.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Waterfall {
+    pub return_of_capital: Vec<f64>,
+    pub preferred_return: Vec<f64>,
+    pub lp_catch_up: Vec<f64>,
+    pub gp_catch_up: Vec<f64>,
+    pub lp_split: Vec<f64>,
+    pub gp_carry: Vec<f64>,
+    pub clawback: Vec<f64>,
+    /// Net LP cash flows: contributions are negative.
+    pub lp_flows: Vec<f64>,
+    pub gp_flows: Vec<f64>,
+}
+
+impl Waterfall {
+    fn zeros(n: usize) -> Self {
+        Self {
+            return_of_capital: vec![0.; n],
+            preferred_return: vec![0.; n],
+            lp_catch_up: vec![0.; n],
+            gp_catch_up: vec![0.; n],
+            lp_split: vec![0.; n],
+            gp_carry: vec![0.; n],
+            clawback: vec![0.; n],
+            lp_flows: vec![0.; n],
+            gp_flows: vec![0.; n],
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Pool {
+    // outstanding LP capital
+    capital: f64,
+    // outstanding capital plus accrued and unpaid preferred return
+    hurdle_balance: f64,
+    // distributed profits: everything above the return of capital
+    profit: f64,
+    gp_total: f64,
+    last_date: Option<DateLike>,
+}
+
+#[doc = include_str!("../../docs/pe/waterfall.md")]
+pub fn waterfall(
+    dates: &[DateLike],
+    contributions: &[f64],
+    distributions: &[f64],
+    deals: Option<&[usize]>,
+    terms: &WaterfallTerms,
+) -> Result<Waterfall> {
+    validate_length(contributions, dates)?;
+    validate_length(distributions, dates)?;
+    check_amounts(contributions, "Contributions")?;
+    check_amounts(distributions, "Distributions")?;
+    check_terms(terms)?;
+
+    if dates.is_empty() {
+        return Err(InvalidPaymentsError::Empty);
+    }
+
+    let deals = match (terms.kind, deals) {
+        (WaterfallKind::European, _) => None,
+        (WaterfallKind::American, Some(deals)) if deals.len() == dates.len() => Some(deals),
+        (WaterfallKind::American, Some(_)) => {
+            return Err(InvalidPaymentsError::new("Deals must be the same length as dates"))
+        }
+        (WaterfallKind::American, None) => {
+            return Err(InvalidPaymentsError::new("Deal-by-deal waterfall requires deals"))
+        }
+    };
+
+    let dc = terms.day_count.unwrap_or_default();
+    let mut order: Vec<usize> = (0..dates.len()).collect();
+    order.sort_by_key(|&i| dates[i]);
+
+    let mut result = Waterfall::zeros(dates.len());
+    let mut pools: HashMap<usize, Pool> = HashMap::new();
+
+    for &i in &order {
+        let pool = pools.entry(deals.map_or(0, |d| d[i])).or_default();
+
+        if let Some(last_date) = pool.last_date {
+            let yf = year_fraction(&last_date, &dates[i], dc);
+            pool.hurdle_balance *= (1. + terms.hurdle).powf(yf);
+        }
+        pool.last_date = Some(dates[i]);
+
+        let (c, d) = (contributions[i], distributions[i]);
+        pool.capital += c;
+        pool.hurdle_balance += c;
+
+        // 1. return of capital
+        let roc = d.min(pool.capital);
+        pool.capital -= roc;
+        pool.hurdle_balance -= roc;
+        let mut rest = d - roc;
+
+        // 2. preferred return
+        let pref = rest.min((pool.hurdle_balance - pool.capital).max(0.));
+        pool.hurdle_balance -= pref;
+        pool.profit += pref;
+        rest -= pref;
+
+        // 3. catch-up: GP takes `catch_up` share until it has `carry` share of the profits
+        // gp_total + catch_up * y = carry * (profit + y)
+        let mut gp_catch_up = 0.;
+        if terms.catch_up > terms.carry {
+            let y = ((terms.carry * pool.profit - pool.gp_total) / (terms.catch_up - terms.carry))
+                .clamp(0., rest);
+            gp_catch_up = terms.catch_up * y;
+            result.lp_catch_up[i] = y - gp_catch_up;
+            pool.profit += y;
+            pool.gp_total += gp_catch_up;
+            rest -= y;
+        }
+
+        // 4. carried interest split
+        let gp_carry = terms.carry * rest;
+        pool.profit += rest;
+        pool.gp_total += gp_carry;
+
+        result.return_of_capital[i] = roc;
+        result.preferred_return[i] = pref;
+        result.gp_catch_up[i] = gp_catch_up;
+        result.gp_carry[i] = gp_carry;
+        result.lp_split[i] = rest - gp_carry;
+        result.gp_flows[i] = gp_catch_up + gp_carry;
+        result.lp_flows[i] = d - c - result.gp_flows[i];
+    }
+
+    if terms.clawback {
+        // GP returns carried interest received in excess of what a whole-fund waterfall
+        // would pay, i.e. after the LPs received their preferred return on the whole fund,
+        // and of `carry` share of the fund profits
+        let gp_total: f64 = pools.values().map(|p| p.gp_total).sum();
+        let whole_fund = match terms.kind {
+            WaterfallKind::European => gp_total,
+            WaterfallKind::American => {
+                let terms = WaterfallTerms {
+                    kind: WaterfallKind::European,
+                    clawback: false,
+                    ..*terms
+                };
+                waterfall(dates, contributions, distributions, None, &terms)?.gp_flows.iter().sum()
+            }
+        };
+        let profit: f64 = distributions.iter().sum::<f64>() - contributions.iter().sum::<f64>();
+        let excess = gp_total - whole_fund.min(terms.carry * profit.max(0.));
+
+        // ignore rounding errors
+        if excess > 1e-9 {
+            let last = *order.last().unwrap();
+            result.clawback[last] = excess;
+            result.lp_flows[last] += excess;
+            result.gp_flows[last] -= excess;
+        }
+    }
+
+    Ok(result)
+}
+
+fn check_amounts(amounts: &[f64], name: &str) -> Result<()> {
+    if let Some(index) = amounts.iter().position(|a| a.is_nan()) {
+        Err(InvalidPaymentsError::NaN {
+            index,
+        })
+    } else if amounts.iter().any(|a| !(0. ..f64::INFINITY).contains(a)) {
+        Err(InvalidPaymentsError::new(format!("{name} must be positive and finite")))
+    } else {
+        Ok(())
+    }
+}
+
+fn check_terms(terms: &WaterfallTerms) -> Result<()> {
+    if !(0. ..=1.).contains(&terms.carry) {
+        Err(InvalidPaymentsError::new("Carry must be between 0 and 1"))
+    } else if !(0. ..=1.).contains(&terms.catch_up) {
+        Err(InvalidPaymentsError::new("Catch-up must be between 0 and 1"))
+    } else if terms.hurdle <= -1. {
+        Err(InvalidPaymentsError::new("Hurdle must be greater than -1"))
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_approx_eq::assert_approx_eq;
+    use rstest::rstest;
+
+    use super::*;
+
+    fn parse_dates(dates: &[&str]) -> Vec<DateLike> {
+        dates.iter().map(|d| d.parse().unwrap()).collect()
+    }
+
+    #[rstest]
+    fn test_european_waterfall() {
+        let dates = parse_dates(&["2021-01-01", "2022-01-01"]);
+        let result =
+            waterfall(&dates, &[100., 0.], &[0., 150.], None, &WaterfallTerms::default()).unwrap();
+
+        assert_approx_eq!(result.return_of_capital[1], 100.);
+        assert_approx_eq!(result.preferred_return[1], 8.);
+        assert_approx_eq!(result.gp_catch_up[1], 2.);
+        assert_approx_eq!(result.gp_carry[1], 8.);
+        assert_approx_eq!(result.lp_split[1], 32.);
+        assert_eq!(result.lp_flows, vec![-100., 140.]);
+        assert_eq!(result.gp_flows, vec![0., 10.]);
+    }
+
+    #[rstest]
+    fn test_european_waterfall_below_hurdle() {
+        let dates = parse_dates(&["2021-01-01", "2022-01-01"]);
+        let result =
+            waterfall(&dates, &[100., 0.], &[0., 105.], None, &WaterfallTerms::default()).unwrap();
+
+        assert_approx_eq!(result.preferred_return[1], 5.);
+        assert_eq!(result.gp_flows, vec![0., 0.]);
+        assert_eq!(result.lp_flows, vec![-100., 105.]);
+    }
+
+    #[rstest]
+    #[case(true, vec![-200., 200.], vec![0., 0.])]
+    #[case(false, vec![-200., 190.], vec![0., 10.])]
+    fn test_american_waterfall_clawback(
+        #[case] clawback: bool,
+        #[case] lp_flows: Vec<f64>,
+        #[case] gp_flows: Vec<f64>,
+    ) {
+        let dates = parse_dates(&["2021-01-01", "2021-01-01", "2022-01-01", "2022-01-01"]);
+        let terms = WaterfallTerms {
+            kind: WaterfallKind::American,
+            clawback,
+            ..Default::default()
+        };
+        let result = waterfall(
+            &dates,
+            &[100., 100., 0., 0.],
+            &[0., 0., 150., 50.],
+            Some(&[0, 1, 0, 1]),
+            &terms,
+        )
+        .unwrap();
+
+        assert_approx_eq!(result.gp_carry[2] + result.gp_catch_up[2], 10.);
+        assert_approx_eq!(result.gp_carry[3] + result.gp_catch_up[3], 0.);
+
+        let total_lp =
+            [result.lp_flows[0] + result.lp_flows[1], result.lp_flows[2] + result.lp_flows[3]];
+        let total_gp =
+            [result.gp_flows[0] + result.gp_flows[1], result.gp_flows[2] + result.gp_flows[3]];
+
+        for (a, e) in total_lp.iter().zip(lp_flows) {
+            assert_approx_eq!(a, e);
+        }
+        for (a, e) in total_gp.iter().zip(gp_flows) {
+            assert_approx_eq!(a, e);
+        }
+    }
+
+    #[rstest]
+    fn test_american_waterfall_clawback_below_hurdle() {
+        // the fund profit is below the preferred return, the GP keeps no carry
+        let dates = parse_dates(&["2021-01-01", "2021-01-01", "2022-01-01", "2022-01-01"]);
+        let terms = WaterfallTerms {
+            kind: WaterfallKind::American,
+            ..Default::default()
+        };
+        let result = waterfall(
+            &dates,
+            &[100., 100., 0., 0.],
+            &[0., 0., 150., 55.],
+            Some(&[0, 1, 0, 1]),
+            &terms,
+        )
+        .unwrap();
+
+        assert_approx_eq!(result.clawback.iter().sum::<f64>(), 10.);
+        assert_approx_eq!(result.gp_flows.iter().sum::<f64>(), 0.);
+    }
+
+    #[rstest]
+    #[case(&[-100., 0.], &[0., 150.], "Contributions must be positive and finite")]
+    #[case(&[100., 0.], &[0., -150.], "Distributions must be positive and finite")]
+    #[case(&[100., 0.], &[0., f64::INFINITY], "Distributions must be positive and finite")]
+    fn test_waterfall_invalid_amounts(
+        #[case] contributions: &[f64],
+        #[case] distributions: &[f64],
+        #[case] message: &str,
+    ) {
+        let dates = parse_dates(&["2021-01-01", "2022-01-01"]);
+        let err = waterfall(&dates, contributions, distributions, None, &WaterfallTerms::default())
+            .unwrap_err();
+        assert_eq!(err.to_string(), message);
+    }
+
+    #[rstest]
+    fn test_waterfall_nan() {
+        let dates = parse_dates(&["2021-01-01", "2022-01-01"]);
+        let err = waterfall(&dates, &[100., 0.], &[0., f64::NAN], None, &WaterfallTerms::default())
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            InvalidPaymentsError::NaN {
+                index: 1
+            }
+        ));
+    }
+
+    #[rstest]
+    fn test_american_waterfall_requires_deals() {
+        let dates = parse_dates(&["2021-01-01", "2022-01-01"]);
+        let terms = WaterfallTerms {
+            kind: WaterfallKind::American,
+            ..Default::default()
+        };
+        assert!(waterfall(&dates, &[100., 0.], &[0., 150.], None, &terms).is_err());
+    }
+}
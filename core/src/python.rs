@@ -0,0 +1,258 @@
+//! Python integration, enabled by the `pyo3` feature.
+
+use std::str::FromStr;
+
+#[cfg(not(feature = "abi"))]
+use pyo3::types::{PyDateAccess, PyDateTime, PyTimeAccess};
+use pyo3::{
+    create_exception,
+    exceptions::{PyException, PyTypeError, PyValueError},
+    intern,
+    prelude::*,
+    types::{PyBool, PyDate, PyString},
+};
+use time::{Date, PrimitiveDateTime, Time, UtcOffset};
+
+use crate::{broadcasting, models, Compounding, DateLike, DayCount, ParseDateError};
+
+create_exception!(pyxirr, InvalidPaymentsError, PyException);
+create_exception!(pyxirr, LengthMismatchError, InvalidPaymentsError);
+create_exception!(pyxirr, NoSignChangeError, InvalidPaymentsError);
+create_exception!(pyxirr, ZeroContributionsError, InvalidPaymentsError);
+create_exception!(pyxirr, EmptyInputError, InvalidPaymentsError);
+create_exception!(pyxirr, NaNAmountError, InvalidPaymentsError);
+create_exception!(pyxirr, MissingDateError, InvalidPaymentsError);
+create_exception!(pyxirr, BroadcastingError, PyException);
+create_exception!(pyxirr, AmbiguousDateError, PyValueError);
+
+/// The variant data is set as attributes of the exception: `amounts_len` and `dates_len`
+/// (or `index_len`) for [`LengthMismatchError`], `index` for [`NaNAmountError`]
+/// and [`MissingDateError`].
+impl From<models::InvalidPaymentsError> for PyErr {
+    fn from(value: models::InvalidPaymentsError) -> Self {
+        use models::InvalidPaymentsError::*;
+
+        let message = value.to_string();
+        match value {
+            LengthMismatch {
+                amounts,
+                dates,
+            } => with_attributes(
+                LengthMismatchError::new_err(message),
+                &[("amounts_len", amounts), ("dates_len", dates)],
+            ),
+            IndexLengthMismatch {
+                amounts,
+                index,
+            } => with_attributes(
+                LengthMismatchError::new_err(message),
+                &[("amounts_len", amounts), ("index_len", index)],
+            ),
+            NoSignChange => NoSignChangeError::new_err(message),
+            ZeroContributions => ZeroContributionsError::new_err(message),
+            Empty => EmptyInputError::new_err(message),
+            NaN {
+                index,
+            } => with_attributes(NaNAmountError::new_err(message), &[("index", index)]),
+            MissingDate {
+                index,
+            } => with_attributes(MissingDateError::new_err(message), &[("index", index)]),
+            Invalid(_) => InvalidPaymentsError::new_err(message),
+        }
+    }
+}
+
+fn with_attributes(err: PyErr, attributes: &[(&str, usize)]) -> PyErr {
+    Python::with_gil(|py| {
+        let value = err.value(py);
+        for &(name, attribute) in attributes {
+            // exceptions always have a __dict__
+            let _ = value.setattr(name, attribute);
+        }
+    });
+    err
+}
+
+impl From<ParseDateError> for PyErr {
+    fn from(value: ParseDateError) -> Self {
+        match value {
+            ParseDateError::Ambiguous(_) => AmbiguousDateError::new_err(value.to_string()),
+            ParseDateError::Invalid(message) => PyValueError::new_err(message),
+        }
+    }
+}
+
+impl From<broadcasting::BroadcastingError> for PyErr {
+    fn from(value: broadcasting::BroadcastingError) -> Self {
+        BroadcastingError::new_err(value.to_string())
+    }
+}
+
+#[pymethods]
+impl DayCount {
+    #[staticmethod]
+    pub fn of(value: &str) -> PyResult<Self> {
+        DayCount::from_str(value).map_err(PyValueError::new_err)
+    }
+
+    fn __str__(&self) -> String {
+        self.to_string()
+    }
+}
+
+/// `'annual'`, `'continuous'`, `'simple'` or the number of compounding periods per year.
+impl<'py> FromPyObject<'py> for Compounding {
+    fn extract_bound(obj: &Bound<'py, PyAny>) -> PyResult<Self> {
+        if let Ok(value) = obj.downcast::<PyString>() {
+            return Compounding::from_str(&value.to_cow()?).map_err(PyValueError::new_err);
+        }
+        if obj.is_instance_of::<PyBool>() {
+            return Err(PyTypeError::new_err("compounding must be a string or an integer"));
+        }
+        let periods = obj
+            .extract::<i64>()
+            .map_err(|_| PyTypeError::new_err("compounding must be a string or an integer"))?;
+        match u32::try_from(periods) {
+            Ok(m) if m > 0 => Ok(Compounding::Periodic(m)),
+            _ => Err(PyValueError::new_err(format!(
+                "compounding periods per year must be a positive integer, got {periods}"
+            ))),
+        }
+    }
+}
+
+impl TryFrom<&Bound<'_, PyDate>> for DateLike {
+    type Error = PyErr;
+
+    /// Datetimes keep their time of day and UTC offset.
+    fn try_from(value: &Bound<'_, PyDate>) -> Result<Self, Self::Error> {
+        let date = py_date(value)?;
+        let Some(time) = py_time(value)? else {
+            return Ok(date.into());
+        };
+
+        let offset = value.call_method0(intern!(value.py(), "utcoffset"))?;
+        let offset = if offset.is_none() {
+            UtcOffset::UTC
+        } else {
+            let seconds =
+                offset.call_method0(intern!(value.py(), "total_seconds"))?.extract::<f64>()?;
+            UtcOffset::from_whole_seconds(seconds as i32)
+                .map_err(|e| PyValueError::new_err(e.to_string()))?
+        };
+
+        Ok(PrimitiveDateTime::new(date, time).assume_offset(offset).into())
+    }
+}
+
+#[cfg(feature = "abi")]
+fn py_date(value: &Bound<'_, PyDate>) -> PyResult<Date> {
+    let py = value.py();
+    let date = Date::from_calendar_date(
+        value.getattr(intern!(py, "year"))?.extract::<i32>()?,
+        value.getattr(intern!(py, "month"))?.extract::<u8>()?.try_into().unwrap(),
+        value.getattr(intern!(py, "day"))?.extract::<u8>()?,
+    );
+
+    Ok(date.unwrap())
+}
+
+#[cfg(not(feature = "abi"))]
+fn py_date(value: &Bound<'_, PyDate>) -> PyResult<Date> {
+    let date = Date::from_calendar_date(
+        value.get_year(),
+        value.get_month().try_into().unwrap(),
+        value.get_day(),
+    );
+
+    Ok(date.unwrap())
+}
+
+// None for dates
+#[cfg(feature = "abi")]
+fn py_time(value: &Bound<'_, PyDate>) -> PyResult<Option<Time>> {
+    let py = value.py();
+    if !value.hasattr(intern!(py, "hour"))? {
+        return Ok(None);
+    }
+    let time = Time::from_hms_micro(
+        value.getattr(intern!(py, "hour"))?.extract()?,
+        value.getattr(intern!(py, "minute"))?.extract()?,
+        value.getattr(intern!(py, "second"))?.extract()?,
+        value.getattr(intern!(py, "microsecond"))?.extract()?,
+    );
+
+    Ok(Some(time.unwrap()))
+}
+
+#[cfg(not(feature = "abi"))]
+fn py_time(value: &Bound<'_, PyDate>) -> PyResult<Option<Time>> {
+    let Ok(value) = value.downcast::<PyDateTime>() else {
+        return Ok(None);
+    };
+    let time = Time::from_hms_micro(
+        value.get_hour(),
+        value.get_minute(),
+        value.get_second(),
+        value.get_microsecond(),
+    );
+
+    Ok(Some(time.unwrap()))
+}
+
+impl<'py> IntoPyObject<'py> for DateLike {
+    type Target = PyDate;
+    type Output = Bound<'py, PyDate>;
+    type Error = PyErr;
+
+    fn into_pyobject(self, py: Python<'py>) -> Result<Self::Output, Self::Error> {
+        let date: Date = self.into();
+        PyDate::new(py, date.year(), date.month().into(), date.day())
+    }
+}
+
+/// Extracts a date, the time of day and the UTC offset are discarded
+/// (see [`extract_datetime`]).
+impl<'py> FromPyObject<'py> for DateLike {
+    fn extract_bound(obj: &Bound<'py, PyAny>) -> PyResult<Self> {
+        extract_datetime(obj).map(DateLike::to_date)
+    }
+}
+
+/// Extracts a date, keeping the time of day and the UTC offset of datetimes,
+/// timestamps and strings.
+pub fn extract_datetime(obj: &Bound<PyAny>) -> PyResult<DateLike> {
+    if let Ok(py_date) = obj.downcast::<PyDate>() {
+        return py_date.try_into();
+    }
+
+    if let Ok(py_string) = obj.downcast::<PyString>() {
+        return Ok(py_string.to_cow()?.parse::<DateLike>()?);
+    }
+
+    let py = obj.py();
+
+    match obj.get_type().name()?.to_cow()?.as_ref() {
+        "datetime64" => {
+            let micros = obj
+                .call_method1(intern!(py, "astype"), (intern!(py, "datetime64[us]"),))?
+                .call_method1(intern!(py, "astype"), (intern!(py, "int64"),))?
+                .extract::<i64>()?;
+
+            // NaT is the minimal int64 value
+            if micros == i64::MIN {
+                return Err(PyValueError::new_err("date is NaT"));
+            }
+
+            Ok(DateLike::from_unix_nanos(micros as i128 * 1000))
+        }
+
+        "Timestamp" => {
+            Ok(obj.call_method0(intern!(py, "to_pydatetime"))?.downcast::<PyDate>()?.try_into()?)
+        }
+
+        other => {
+            Err(PyTypeError::new_err(format!("Type {other:?} is not understood. Expected: date")))
+        }
+    }
+}
@@ -0,0 +1,166 @@
+use std::{fmt, str::FromStr};
+
+use crate::utils::fast_pow;
+
+/// How a rate compounds over a year fraction `t`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Compounding {
+    /// `(1 + r)^t`
+    #[default]
+    Annual,
+    /// `exp(r * t)`
+    Continuous,
+    /// `1 + r * t`
+    Simple,
+    /// `(1 + r / m)^(m * t)`, `m` times a year (`m >= 1`)
+    Periodic(u32),
+}
+
+impl fmt::Display for Compounding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Compounding::Annual => write!(f, "annual"),
+            Compounding::Continuous => write!(f, "continuous"),
+            Compounding::Simple => write!(f, "simple"),
+            Compounding::Periodic(m) => write!(f, "{m}"),
+        }
+    }
+}
+
+impl FromStr for Compounding {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "annual" => Ok(Compounding::Annual),
+            "continuous" => Ok(Compounding::Continuous),
+            "simple" => Ok(Compounding::Simple),
+            _ => Err("compounding must be one of: 'annual', 'continuous', 'simple' or an integer"),
+        }
+    }
+}
+
+impl Compounding {
+    /// The discount factor of `rate` over the year fraction `t`.
+    pub fn discount(self, rate: f64, t: f64) -> f64 {
+        match self {
+            Compounding::Annual => fast_pow(1.0 + rate, -t),
+            Compounding::Continuous => (-rate * t).exp(),
+            Compounding::Simple => 1.0 / (1.0 + rate * t),
+            Compounding::Periodic(m) => {
+                let m = m as f64;
+                fast_pow(1.0 + rate / m, -m * t)
+            }
+        }
+    }
+
+    /// The discount factor and its derivative with respect to `rate`.
+    pub fn discount_with_deriv(self, rate: f64, t: f64) -> (f64, f64) {
+        let d = self.discount(rate, t);
+        let deriv = match self {
+            Compounding::Annual => d * -t / (1.0 + rate),
+            Compounding::Continuous => d * -t,
+            Compounding::Simple => d * d * -t,
+            Compounding::Periodic(m) => d * -t / (1.0 + rate / m as f64),
+        };
+        (d, deriv)
+    }
+
+    /// The growth factor of `rate` over the year fraction `t`, i.e. `1 / discount`.
+    pub fn growth(self, rate: f64, t: f64) -> f64 {
+        match self {
+            Compounding::Annual => f64::powf(1.0 + rate, t),
+            Compounding::Continuous => (rate * t).exp(),
+            Compounding::Simple => 1.0 + rate * t,
+            Compounding::Periodic(m) => {
+                let m = m as f64;
+                f64::powf(1.0 + rate / m, m * t)
+            }
+        }
+    }
+
+    /// Rates at or below this bound do not discount the year fractions `deltas`.
+    pub(crate) fn min_rate(self, deltas: &[f64]) -> f64 {
+        match self {
+            Compounding::Annual => -1.0,
+            Compounding::Continuous => f64::NEG_INFINITY,
+            Compounding::Simple => match deltas.iter().copied().fold(0.0, f64::max) {
+                t if t > 0.0 => -1.0 / t,
+                _ => f64::NEG_INFINITY,
+            },
+            Compounding::Periodic(m) => -(m as f64),
+        }
+    }
+
+    /// The rate at which two payments have a zero net present value.
+    pub(crate) fn rate_2(self, amounts: &[f64], deltas: &[f64]) -> f64 {
+        let growth = -amounts[1] / amounts[0];
+        let t = deltas[1] - deltas[0];
+        match self {
+            // solve analytically:
+            // cf[0]/(1+r)^d[0] + cf[1]/(1+r)^d[1] = 0  =>
+            // cf[1]/(1+r)^d[1] = -cf[0]/(1+r)^d[0]  => rearrange
+            // cf[1]/cf[0] = -(1+r)^d[1]/(1+r)^d[0]  => simplify
+            // cf[1]/cf[0] = -(1+r)^(d[1] - d[0])  => take the root
+            // (cf[1]/cf[0])^(1/(d[1] - d[0])) = -(1 + r) => multiply by -1 and subtract 1
+            // r = -(cf[1]/cf[0])^(1/(d[1] - d[0])) - 1
+            Compounding::Annual => growth.powf(1. / t) - 1.0,
+            Compounding::Continuous => growth.ln() / t,
+            // cf[0]/(1+r*d[0]) + cf[1]/(1+r*d[1]) = 0  =>
+            // cf[0] + cf[1] + r*(cf[0]*d[1] + cf[1]*d[0]) = 0
+            Compounding::Simple => {
+                -(amounts[0] + amounts[1]) / (amounts[0] * deltas[1] + amounts[1] * deltas[0])
+            }
+            Compounding::Periodic(m) => {
+                let m = m as f64;
+                m * (growth.powf(1. / (m * t)) - 1.0)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_approx_eq::assert_approx_eq;
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case(Compounding::Annual)]
+    #[case(Compounding::Continuous)]
+    #[case(Compounding::Simple)]
+    #[case(Compounding::Periodic(12))]
+    fn test_discount_with_deriv(#[case] compounding: Compounding) {
+        let (rate, t, h) = (0.07, 1.7, 1e-6);
+        let (d, deriv) = compounding.discount_with_deriv(rate, t);
+        let numeric =
+            (compounding.discount(rate + h, t) - compounding.discount(rate - h, t)) / 2.0 / h;
+
+        assert_approx_eq!(d, 1.0 / compounding.growth(rate, t));
+        assert_approx_eq!(deriv, numeric, 1e-6);
+    }
+
+    #[rstest]
+    #[case(Compounding::Annual)]
+    #[case(Compounding::Continuous)]
+    #[case(Compounding::Simple)]
+    #[case(Compounding::Periodic(4))]
+    fn test_rate_2(#[case] compounding: Compounding) {
+        let (amounts, deltas) = ([-100.0, 115.0], [0.0, 1.5]);
+        let rate = compounding.rate_2(&amounts, &deltas);
+        let npv = amounts[0] + amounts[1] * compounding.discount(rate, deltas[1]);
+
+        assert_approx_eq!(npv, 0.0);
+    }
+
+    #[rstest]
+    fn test_periodic_converges_to_continuous() {
+        let (continuous, periodic) = (
+            Compounding::Continuous.growth(0.05, 2.0),
+            Compounding::Periodic(100_000).growth(0.05, 2.0),
+        );
+
+        assert_approx_eq!(continuous, periodic, 1e-5);
+    }
+}
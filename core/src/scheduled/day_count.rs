@@ -1,12 +1,13 @@
-use std::{cmp::min, fmt, str::FromStr};
+use std::{borrow::Borrow, cmp::min, fmt, str::FromStr};
 
 use time::{
     util::{days_in_year_month, is_leap_year},
     Date, Month,
 };
 
-#[pyo3::pyclass]
-#[pyo3(frozen)]
+use crate::DateLike;
+
+#[cfg_attr(feature = "pyo3", pyo3::pyclass(frozen))]
 #[derive(Debug, Clone, Copy)]
 #[allow(non_camel_case_types)]
 #[derive(Default)]
@@ -74,19 +75,25 @@ impl FromStr for DayCount {
     }
 }
 
-pub fn year_fraction<T: AsRef<Date>>(d1: T, d2: T, day_count: DayCount) -> f64 {
+/// Year fraction between two dates. The ACT conventions count the time of day
+/// in fractional days, after normalising both dates to UTC.
+pub fn year_fraction<T: Borrow<DateLike>>(d1: T, d2: T, day_count: DayCount) -> f64 {
+    let (d1, d2) = (d1.borrow().to_utc(), d2.borrow().to_utc());
+    let (f1, f2) = (d1.day_fraction(), d2.day_fraction());
     let (d1, d2) = (d1.as_ref(), d2.as_ref());
+    let act_days = || days_between_act(d1, d2) as f64 + f2 - f1;
 
     use DayCount::*;
     match day_count {
         ACT_ACT_ISDA => {
             let (normal_days, leap_days) = normal_leap_days(d1, d2);
-            normal_days as f64 / 365.0 + leap_days as f64 / 366.0
+            normal_days as f64 / 365.0 + leap_days as f64 / 366.0 + f2 / days_in_year(d2)
+                - f1 / days_in_year(d1)
         }
-        ACT_365F => days_between_act(d1, d2) as f64 / 365.0,
-        ACT_365_25 => days_between_act(d1, d2) as f64 / 365.25,
-        ACT_364 => days_between_act(d1, d2) as f64 / 364.0,
-        ACT_360 => days_between_act(d1, d2) as f64 / 360.0,
+        ACT_365F => act_days() / 365.0,
+        ACT_365_25 => act_days() / 365.25,
+        ACT_364 => act_days() / 364.0,
+        ACT_360 => act_days() / 360.0,
         THIRTY_360_ISDA => days_between_30_360_isda(d1, d2) as f64 / 360.0,
         THIRTY_E_360 => days_between_30_e_360(d1, d2) as f64 / 360.0,
         THIRTY_E_PLUS_360 => days_between_30_e_plus_360(d1, d2) as f64 / 360.0,
@@ -97,6 +104,14 @@ pub fn year_fraction<T: AsRef<Date>>(d1: T, d2: T, day_count: DayCount) -> f64 {
     }
 }
 
+fn days_in_year(d: &Date) -> f64 {
+    if is_leap_year(d.year()) {
+        366.0
+    } else {
+        365.0
+    }
+}
+
 pub fn days_between<T: AsRef<Date>>(d1: T, d2: T, day_count: DayCount) -> i32 {
     let (d1, d2) = (d1.as_ref(), d2.as_ref());
 
@@ -236,10 +251,25 @@ pub fn is_last_day_of_feb(date: &Date) -> bool {
 
 #[cfg(test)]
 mod tests {
+    use assert_approx_eq::assert_approx_eq;
     use rstest::rstest;
 
     use super::*;
-    use crate::core::DateLike;
+
+    #[rstest]
+    #[case("Act/365F", "2020-01-01T06:00", "2020-01-02T18:00", 1.5 / 365.)]
+    #[case("Act/360", "2020-01-01T23:00-02:00", "2020-01-02T13:00Z", 0.5 / 360.)]
+    #[case("Act/Act", "2019-12-31T12:00", "2020-01-01T12:00", 0.5 / 365. + 0.5 / 366.)]
+    // the time of day is ignored by the 30/360 conventions
+    #[case("30/360", "2020-01-01T23:00", "2020-01-02T01:00", 1. / 360.)]
+    fn test_intraday(
+        #[case] day_count: DayCount,
+        #[case] d1: DateLike,
+        #[case] d2: DateLike,
+        #[case] expected: f64,
+    ) {
+        assert_approx_eq!(year_fraction(d1, d2, day_count), expected, 1e-12);
+    }
 
     // test cases from http://www.deltaquants.com/day-count-conventions
     #[rstest]
@@ -0,0 +1,228 @@
+//! The inverse problems of [`xirr`](super::xirr): the amount, date or scale factor
+//! for which dated cash flows have a given internal rate of return.
+
+use time::{Date, Duration};
+
+use super::{xirr::day_count_factor, year_fraction, Compounding, DayCount};
+use crate::{
+    models::{validate, validate_length, DateLike, InvalidPaymentsError},
+    optimize::{brentq, newton_raphson_with_default_deriv},
+    utils::sum_compensated,
+};
+
+/// A date search is bounded by a century before and after the earliest known date.
+const MAX_DAYS: f64 = 36_525.0;
+const MAX_AMOUNT: f64 = 1e15;
+
+/// The amount at `index` for which the cash flows have the internal rate of return `rate`,
+/// e.g. the terminal payment that yields a target XIRR. The amount at `index` is ignored.
+pub fn xgoal_seek_amount(
+    rate: f64,
+    dates: &[DateLike],
+    amounts: &[f64],
+    index: usize,
+    day_count: Option<DayCount>,
+    compounding: Compounding,
+) -> Result<f64, InvalidPaymentsError> {
+    validate_unknown(dates, amounts, index)?;
+
+    let deltas = day_count_factor(dates, day_count);
+    let known = known_xnpv(rate, &deltas, amounts, index, compounding);
+    let discount = compounding.discount(rate, deltas[index]);
+
+    Ok(seek(|amount| known + amount * discount, -known / discount, (-MAX_AMOUNT, MAX_AMOUNT)))
+}
+
+/// The date at `index` for which the cash flows have the internal rate of return `rate`,
+/// e.g. the date by which a final payment must arrive to hit a target return.
+/// The date at `index` is ignored. The solution is rounded down to a whole day.
+pub fn xgoal_seek_date(
+    rate: f64,
+    dates: &[DateLike],
+    amounts: &[f64],
+    index: usize,
+    day_count: Option<DayCount>,
+    compounding: Compounding,
+) -> Result<Option<DateLike>, InvalidPaymentsError> {
+    validate_unknown(dates, amounts, index)?;
+    if amounts[index].is_nan() {
+        return Err(InvalidPaymentsError::NaN {
+            index,
+        });
+    }
+
+    let dc = day_count.unwrap_or_default();
+    let start = others(dates, index).min().expect("the dates are validated");
+    let deltas: Vec<_> = dates.iter().map(|d| year_fraction(start, d, dc)).collect();
+    let known = known_xnpv(rate, &deltas, amounts, index, compounding);
+
+    let start_date = Date::from(*start);
+    let date_at = |days: i64| start_date.checked_add(Duration::days(days));
+    let year_fraction_at = |days: i64| date_at(days).map(|d| year_fraction(*start, d.into(), dc));
+
+    // the year fraction is interpolated between whole days, so that the search is continuous
+    let f = |days: f64| {
+        let whole = days.floor();
+        match (year_fraction_at(whole as i64), year_fraction_at(whole as i64 + 1)) {
+            (Some(t0), Some(t1)) => {
+                let t = t0 + (days - whole) * (t1 - t0);
+                known + amounts[index] * compounding.discount(rate, t)
+            }
+            _ => f64::NAN,
+        }
+    };
+
+    let last = deltas.iter().copied().fold(0.0, f64::max) * 365.0;
+    let days = seek(f, last, (-MAX_DAYS, MAX_DAYS));
+
+    Ok(days.is_finite().then(|| date_at(days.floor() as i64)).flatten().map(DateLike::from))
+}
+
+/// The factor on the distributions (positive amounts) for which the cash flows have the
+/// internal rate of return `rate`. This is the idea of [`pme_plus_lambda`](crate::pme_plus_lambda)
+/// applied to an IRR target.
+pub fn xgoal_seek_scale(
+    rate: f64,
+    dates: &[DateLike],
+    amounts: &[f64],
+    day_count: Option<DayCount>,
+    compounding: Compounding,
+) -> Result<f64, InvalidPaymentsError> {
+    validate(amounts, Some(dates))?;
+
+    let deltas = day_count_factor(dates, day_count);
+    let discounted = |positive: bool| {
+        sum_compensated(
+            amounts
+                .iter()
+                .zip(&deltas)
+                .filter(|(&a, _)| (a > 0.0) == positive)
+                .map(|(a, &t)| a * compounding.discount(rate, t)),
+        )
+    };
+    let (contributions, distributions) = (discounted(false), discounted(true));
+
+    let guess = -contributions / distributions;
+    Ok(seek(|scale| contributions + scale * distributions, guess, (-MAX_AMOUNT, MAX_AMOUNT)))
+}
+
+/// A root of `f`, by Newton's method from `guess`, or else by Brent's method within `bracket`.
+fn seek<F>(f: F, guess: f64, bracket: (f64, f64)) -> f64
+where
+    F: Fn(f64) -> f64,
+{
+    let x = newton_raphson_with_default_deriv(guess, &f);
+    if x.is_finite() {
+        return x;
+    }
+    brentq(&f, bracket.0, bracket.1, 100)
+}
+
+fn validate_unknown(
+    dates: &[DateLike],
+    amounts: &[f64],
+    index: usize,
+) -> Result<(), InvalidPaymentsError> {
+    validate_length(amounts, dates)?;
+    if index >= amounts.len() {
+        return Err(InvalidPaymentsError::new(format!(
+            "index {index} is out of range for {} payments",
+            amounts.len()
+        )));
+    }
+    if amounts.len() < 2 {
+        return Err(InvalidPaymentsError::Empty);
+    }
+    match amounts.iter().enumerate().position(|(i, a)| i != index && a.is_nan()) {
+        Some(index) => Err(InvalidPaymentsError::NaN {
+            index,
+        }),
+        None => Ok(()),
+    }
+}
+
+fn others<T>(values: &[T], index: usize) -> impl Iterator<Item = &T> {
+    values.iter().enumerate().filter(move |(i, _)| *i != index).map(|(_, v)| v)
+}
+
+fn known_xnpv(
+    rate: f64,
+    deltas: &[f64],
+    amounts: &[f64],
+    index: usize,
+    compounding: Compounding,
+) -> f64 {
+    sum_compensated(
+        others(amounts, index)
+            .zip(others(deltas, index))
+            .map(|(a, &t)| a * compounding.discount(rate, t)),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_approx_eq::assert_approx_eq;
+    use rstest::rstest;
+    use time::macros::date;
+
+    use super::*;
+    use crate::{xirr_compounded, xnpv_compounded};
+
+    fn dates() -> Vec<DateLike> {
+        vec![
+            date!(2020 - 01 - 01).into(),
+            date!(2020 - 07 - 01).into(),
+            date!(2022 - 01 - 01).into(),
+        ]
+    }
+
+    #[rstest]
+    #[case(Compounding::Annual)]
+    #[case(Compounding::Continuous)]
+    #[case(Compounding::Periodic(4))]
+    fn test_xgoal_seek_amount(#[case] compounding: Compounding) {
+        let (dates, mut amounts) = (dates(), vec![-1000.0, 100.0, f64::NAN]);
+        amounts[2] = xgoal_seek_amount(0.12, &dates, &amounts, 2, None, compounding).unwrap();
+
+        let rate = xirr_compounded(&dates, &amounts, None, None, compounding).unwrap();
+        assert_approx_eq!(rate, 0.12);
+    }
+
+    #[rstest]
+    fn test_xgoal_seek_date() {
+        let (mut dates, amounts) = (dates(), [-1000.0, 100.0, 1100.0]);
+        let date = xgoal_seek_date(0.08, &dates, &amounts, 2, None, Compounding::Annual).unwrap();
+        dates[2] = date.unwrap();
+
+        // the next day is below the target
+        let npv = |dates: &[DateLike]| {
+            xnpv_compounded(0.08, dates, &amounts, None, Compounding::Annual).unwrap()
+        };
+        assert!(npv(&dates) >= 0.0);
+        dates[2] = Date::from(dates[2]).next_day().unwrap().into();
+        assert!(npv(&dates) < 0.0);
+    }
+
+    #[rstest]
+    fn test_xgoal_seek_scale() {
+        let (dates, amounts) = (dates(), [-1000.0, 100.0, 900.0]);
+        let scale = xgoal_seek_scale(0.1, &dates, &amounts, None, Compounding::Annual).unwrap();
+        let scaled = amounts.map(|a| {
+            if a > 0.0 {
+                a * scale
+            } else {
+                a
+            }
+        });
+
+        let rate = xirr_compounded(&dates, &scaled, None, None, Compounding::Annual).unwrap();
+        assert_approx_eq!(rate, 0.1);
+    }
+
+    #[rstest]
+    fn test_xgoal_seek_invalid_index() {
+        let result =
+            xgoal_seek_amount(0.1, &dates(), &[-1.0, 1.0, 1.0], 3, None, Compounding::Annual);
+        assert!(matches!(result, Err(InvalidPaymentsError::Invalid(_))));
+    }
+}
@@ -0,0 +1,17 @@
+mod compounding;
+mod day_count;
+mod goal_seek;
+mod payback;
+mod profile;
+mod sensitivity;
+mod xirr;
+mod xnfv;
+
+pub use compounding::Compounding;
+pub use day_count::{days_between, year_fraction, DayCount};
+pub use goal_seek::*;
+pub use payback::*;
+pub use profile::*;
+pub use sensitivity::*;
+pub use xirr::*;
+pub use xnfv::*;
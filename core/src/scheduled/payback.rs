@@ -0,0 +1,86 @@
+use time::{Date, Duration};
+
+use super::{xirr::day_count_factor, Compounding, DayCount};
+use crate::{
+    models::{validate_length, validate_not_nan, DateLike, InvalidPaymentsError},
+    utils::payback_point,
+};
+
+/// The date when the cumulative cash flows (discounted at `rate`, if any) turn non-negative.
+/// The crossing flow is spread evenly between the previous date and its own date,
+/// and the first whole day when the total reaches zero is returned.
+/// `None` if the cash flows never pay back.
+pub fn xpayback(
+    dates: &[DateLike],
+    amounts: &[f64],
+    rate: Option<f64>,
+    day_count: Option<DayCount>,
+    compounding: Compounding,
+) -> Result<Option<DateLike>, InvalidPaymentsError> {
+    validate_length(amounts, dates)?;
+    validate_not_nan(amounts)?;
+    if amounts.is_empty() {
+        return Err(InvalidPaymentsError::Empty);
+    }
+
+    let deltas = day_count_factor(dates, day_count);
+    let mut flows: Vec<(Date, f64)> = dates
+        .iter()
+        .zip(amounts)
+        .zip(&deltas)
+        .map(|((&date, &amount), &t)| match rate {
+            Some(rate) => (date.into(), amount * compounding.discount(rate, t)),
+            None => (date.into(), amount),
+        })
+        .collect();
+    flows.sort_by_key(|&(date, _)| date);
+
+    let Some((i, fraction)) = payback_point(flows.iter().map(|&(_, amount)| amount)) else {
+        return Ok(None);
+    };
+
+    let (start, end) = (flows[i - 1].0, flows[i].0);
+    // the tolerance keeps an exact crossing at a whole day on that day
+    let days = (fraction * (end - start).whole_days() as f64 - 1e-9).ceil().max(0.0);
+
+    Ok(start.checked_add(Duration::days(days as i64)).map(DateLike::from))
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+    use time::macros::date;
+
+    use super::*;
+
+    #[rstest]
+    #[case(&[-1000.0, 500.0, 500.0], None, Some(date!(2021 - 01 - 01)))]
+    #[case(&[-1000.0, 500.0, 1000.0], None, Some(date!(2020 - 07 - 02)))]
+    #[case(&[-1000.0, 500.0, 400.0], None, None)]
+    #[case(&[-1000.0, 500.0, 600.0], Some(0.1), None)]
+    #[case(&[-1000.0, 600.0, 600.0], Some(0.1), Some(date!(2020 - 12 - 02)))]
+    fn test_xpayback(
+        #[case] amounts: &[f64],
+        #[case] rate: Option<f64>,
+        #[case] expected: Option<Date>,
+    ) {
+        let dates: Vec<DateLike> = vec![
+            date!(2019 - 01 - 01).into(),
+            date!(2020 - 01 - 01).into(),
+            date!(2021 - 01 - 01).into(),
+        ];
+        let result = xpayback(&dates, amounts, rate, None, Compounding::Annual).unwrap();
+        assert_eq!(result, expected.map(DateLike::from));
+    }
+
+    #[rstest]
+    fn test_xpayback_unsorted() {
+        let dates: Vec<DateLike> = vec![
+            date!(2021 - 01 - 01).into(),
+            date!(2019 - 01 - 01).into(),
+            date!(2020 - 01 - 01).into(),
+        ];
+        let result = xpayback(&dates, &[500.0, -1000.0, 500.0], None, None, Compounding::Annual);
+        assert_eq!(result.unwrap(), Some(date!(2021 - 01 - 01).into()));
+    }
+}
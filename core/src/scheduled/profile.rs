@@ -0,0 +1,117 @@
+use super::{
+    xirr::{day_count_factor, xirr_result, xnpv_result},
+    Compounding, DayCount,
+};
+use crate::models::{validate, validate_length, validate_not_nan, DateLike, InvalidPaymentsError};
+
+/// [`xnpv`](super::xnpv) at every rate, the year fractions are computed once.
+pub fn xnpv_profile(
+    rates: &[f64],
+    dates: &[DateLike],
+    amounts: &[f64],
+    day_count: Option<DayCount>,
+    compounding: Compounding,
+) -> Result<Vec<f64>, InvalidPaymentsError> {
+    validate_length(amounts, dates)?;
+    validate_not_nan(amounts)?;
+    if amounts.is_empty() {
+        return Err(InvalidPaymentsError::Empty);
+    }
+
+    let deltas = day_count_factor(dates, day_count);
+    Ok(rates.iter().map(|&rate| xnpv_result(amounts, &deltas, rate, compounding)).collect())
+}
+
+/// The rate at which two dated cash flows have the same [`xnpv_profile`]: each cash flow is
+/// discounted to its own earliest date, as in the profile, so the start dates may differ.
+pub fn xcrossover_rate(
+    (dates, amounts): (&[DateLike], &[f64]),
+    (other_dates, other_amounts): (&[DateLike], &[f64]),
+    guess: Option<f64>,
+    day_count: Option<DayCount>,
+    compounding: Compounding,
+) -> Result<f64, InvalidPaymentsError> {
+    validate_length(amounts, dates)?;
+    validate_length(other_amounts, other_dates)?;
+    if amounts.is_empty() || other_amounts.is_empty() {
+        return Err(InvalidPaymentsError::Empty);
+    }
+
+    // the root of the difference of the two profiles
+    let amounts: Vec<f64> =
+        amounts.iter().copied().chain(other_amounts.iter().map(|a| -a)).collect();
+    validate(&amounts, None)?;
+    let deltas =
+        [day_count_factor(dates, day_count), day_count_factor(other_dates, day_count)].concat();
+
+    Ok(xirr_result(&amounts, &deltas, guess, compounding))
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_approx_eq::assert_approx_eq;
+    use rstest::rstest;
+    use time::macros::date;
+
+    use super::*;
+    use crate::xnpv_compounded;
+
+    #[rstest]
+    fn test_xnpv_profile() {
+        let dates: Vec<DateLike> = vec![
+            date!(2020 - 01 - 01).into(),
+            date!(2020 - 03 - 01).into(),
+            date!(2021 - 02 - 15).into(),
+        ];
+        let amounts = [-1000.0, 300.0, 900.0];
+        let rates = [0.0, 0.1, 0.2];
+
+        let profile = xnpv_profile(&rates, &dates, &amounts, None, Compounding::Annual).unwrap();
+
+        for (&npv, rate) in profile.iter().zip(rates) {
+            let expected =
+                xnpv_compounded(rate, &dates, &amounts, None, Compounding::Annual).unwrap();
+            assert_approx_eq!(npv, expected);
+        }
+    }
+
+    #[rstest]
+    fn test_xcrossover_rate() {
+        let dates: Vec<DateLike> = vec![
+            date!(2020 - 01 - 01).into(),
+            date!(2021 - 01 - 01).into(),
+            date!(2023 - 01 - 01).into(),
+        ];
+        let (a, b) = ([-1000.0, 800.0, 400.0], [-1000.0, 100.0, 1300.0]);
+        let rate =
+            xcrossover_rate((&dates, &a), (&dates, &b), None, None, Compounding::Annual).unwrap();
+
+        let npv = |amounts: &[f64]| {
+            xnpv_compounded(rate, &dates, amounts, None, Compounding::Annual).unwrap()
+        };
+        assert_approx_eq!(npv(&a), npv(&b));
+    }
+
+    #[rstest]
+    fn test_xcrossover_rate_different_start_dates() {
+        let dates: Vec<DateLike> = vec![
+            date!(2020 - 01 - 01).into(),
+            date!(2021 - 01 - 01).into(),
+            date!(2023 - 01 - 01).into(),
+        ];
+        let other_dates: Vec<DateLike> = vec![
+            date!(2020 - 07 - 01).into(),
+            date!(2021 - 07 - 01).into(),
+            date!(2024 - 01 - 01).into(),
+        ];
+        let (a, b) = ([-1000.0, 800.0, 400.0], [-1000.0, 100.0, 1300.0]);
+        let rate =
+            xcrossover_rate((&dates, &a), (&other_dates, &b), None, None, Compounding::Annual)
+                .unwrap();
+
+        let profile = |dates: &[DateLike], amounts: &[f64]| {
+            xnpv_profile(&[rate], dates, amounts, None, Compounding::Annual).unwrap()[0]
+        };
+        assert_approx_eq!(profile(&dates, &a), profile(&other_dates, &b));
+    }
+}
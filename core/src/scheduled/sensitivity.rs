@@ -0,0 +1,216 @@
+//! Data tables of XNPV over discount rates and amount shocks, and tornado analysis of
+//! XIRR (or XNPV) when each flow is perturbed. The year fractions are computed once
+//! and shared by every cell.
+
+use super::{
+    xirr::{day_count_factor, xirr_result, xnpv_result},
+    Compounding, DayCount,
+};
+use crate::{
+    models::{validate, validate_length, validate_not_nan, DateLike, InvalidPaymentsError},
+    utils::sum_compensated,
+};
+
+/// A dated cash flow with its year fractions, ready to be evaluated many times.
+pub struct Sensitivity {
+    amounts: Vec<f64>,
+    deltas: Vec<f64>,
+    compounding: Compounding,
+    /// XIRR of the unperturbed flows, the starting point of the perturbed ones.
+    irr: f64,
+}
+
+/// The metric with each flow scaled by `1 - shock` (`low`) and `1 + shock` (`high`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tornado {
+    pub base: f64,
+    pub low: Vec<f64>,
+    pub high: Vec<f64>,
+    /// Indexes of the flows by descending swing `|high - low|`, NaN swings last.
+    pub order: Vec<usize>,
+}
+
+impl Sensitivity {
+    pub fn new(
+        dates: &[DateLike],
+        amounts: &[f64],
+        day_count: Option<DayCount>,
+        compounding: Compounding,
+    ) -> Result<Self, InvalidPaymentsError> {
+        validate_length(amounts, dates)?;
+        validate_not_nan(amounts)?;
+        if amounts.is_empty() {
+            return Err(InvalidPaymentsError::Empty);
+        }
+
+        let deltas = day_count_factor(dates, day_count);
+        let irr = match validate(amounts, None) {
+            Ok(()) => xirr_result(amounts, &deltas, None, compounding),
+            Err(_) => f64::NAN,
+        };
+
+        Ok(Self {
+            amounts: amounts.to_vec(),
+            deltas,
+            compounding,
+            irr,
+        })
+    }
+
+    /// XNPV at `rate` for every shock, a shock scales the `shocked` flows by `1 + shock`.
+    pub fn xnpv_row(&self, rate: f64, shocks: &[f64], shocked: &[bool]) -> Vec<f64> {
+        let discounted = |selected: bool| {
+            let flows = self.amounts.iter().zip(&self.deltas).zip(shocked);
+            sum_compensated(
+                flows
+                    .filter(|(_, &s)| s == selected)
+                    .map(|((a, &t), _)| a * self.compounding.discount(rate, t)),
+            )
+        };
+        let (fixed, variable) = (discounted(false), discounted(true));
+
+        shocks.iter().map(|shock| fixed + (1.0 + shock) * variable).collect()
+    }
+
+    /// XNPV with one row per rate and one column per shock, see [`Sensitivity::xnpv_row`].
+    pub fn xnpv_table(&self, rates: &[f64], shocks: &[f64], shocked: &[bool]) -> Vec<Vec<f64>> {
+        rates.iter().map(|&rate| self.xnpv_row(rate, shocks, shocked)).collect()
+    }
+
+    /// The XIRR, or the XNPV at `rate`, of the unperturbed flows.
+    pub fn base(&self, rate: Option<f64>) -> f64 {
+        match rate {
+            Some(rate) => xnpv_result(&self.amounts, &self.deltas, rate, self.compounding),
+            None => self.irr,
+        }
+    }
+
+    /// The XIRR, or the XNPV at `rate`, with the flow `index` scaled by `1 - shock`
+    /// and `1 + shock`. NaN where the XIRR is undefined.
+    pub fn swing(&self, index: usize, shock: f64, rate: Option<f64>) -> (f64, f64) {
+        let evaluate = |factor: f64| {
+            let mut amounts = self.amounts.clone();
+            amounts[index] *= factor;
+            match rate {
+                Some(rate) => xnpv_result(&amounts, &self.deltas, rate, self.compounding),
+                None if validate(&amounts, None).is_ok() => {
+                    let guess = self.irr.is_finite().then_some(self.irr);
+                    xirr_result(&amounts, &self.deltas, guess, self.compounding)
+                }
+                None => f64::NAN,
+            }
+        };
+
+        (evaluate(1.0 - shock), evaluate(1.0 + shock))
+    }
+
+    /// [`Sensitivity::swing`] of every flow.
+    pub fn tornado(&self, shock: f64, rate: Option<f64>) -> Tornado {
+        let swings = (0..self.amounts.len()).map(|i| self.swing(i, shock, rate)).collect();
+        self.tornado_of(swings, rate)
+    }
+
+    /// Assembles the swings of every flow, e.g. computed in parallel.
+    pub fn tornado_of(&self, swings: Vec<(f64, f64)>, rate: Option<f64>) -> Tornado {
+        let (low, high): (Vec<f64>, Vec<f64>) = swings.into_iter().unzip();
+
+        let width = |i: usize| match (high[i] - low[i]).abs() {
+            w if w.is_nan() => f64::NEG_INFINITY,
+            w => w,
+        };
+        let mut order: Vec<usize> = (0..low.len()).collect();
+        order.sort_by(|&a, &b| width(b).total_cmp(&width(a)));
+
+        Tornado {
+            base: self.base(rate),
+            low,
+            high,
+            order,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_approx_eq::assert_approx_eq;
+    use rstest::{fixture, rstest};
+    use time::macros::date;
+
+    use super::*;
+    use crate::{xirr_compounded, xnpv_compounded};
+
+    #[fixture]
+    fn flows() -> (Vec<DateLike>, Vec<f64>) {
+        let dates = vec![
+            date!(2020 - 01 - 01).into(),
+            date!(2020 - 09 - 15).into(),
+            date!(2021 - 06 - 30).into(),
+            date!(2023 - 01 - 01).into(),
+        ];
+        (dates, vec![-1000.0, 100.0, 300.0, 900.0])
+    }
+
+    #[rstest]
+    fn test_xnpv_table(flows: (Vec<DateLike>, Vec<f64>)) {
+        let (dates, amounts) = flows;
+        let sensitivity = Sensitivity::new(&dates, &amounts, None, Compounding::Annual).unwrap();
+        let shocked: Vec<bool> = amounts.iter().map(|&a| a > 0.0).collect();
+        let (rates, shocks) = ([0.05, 0.1], [-0.2, 0.0, 0.1]);
+
+        let table = sensitivity.xnpv_table(&rates, &shocks, &shocked);
+
+        for (row, &rate) in table.iter().zip(&rates) {
+            for (&cell, shock) in row.iter().zip(shocks) {
+                let amounts: Vec<f64> = amounts
+                    .iter()
+                    .map(|&a| {
+                        a * if a > 0.0 {
+                            1.0 + shock
+                        } else {
+                            1.0
+                        }
+                    })
+                    .collect();
+                let expected =
+                    xnpv_compounded(rate, &dates, &amounts, None, Compounding::Annual).unwrap();
+                assert_approx_eq!(cell, expected);
+            }
+        }
+    }
+
+    #[rstest]
+    fn test_tornado(flows: (Vec<DateLike>, Vec<f64>)) {
+        let (dates, amounts) = flows;
+        let sensitivity = Sensitivity::new(&dates, &amounts, None, Compounding::Annual).unwrap();
+
+        let tornado = sensitivity.tornado(0.1, None);
+
+        let base = xirr_compounded(&dates, &amounts, None, None, Compounding::Annual).unwrap();
+        assert_approx_eq!(tornado.base, base);
+
+        let mut amounts = amounts;
+        amounts[3] *= 1.1;
+        let high = xirr_compounded(&dates, &amounts, None, None, Compounding::Annual).unwrap();
+        assert_approx_eq!(tornado.high[3], high);
+
+        // the initial investment, then the exit, move the IRR the most
+        assert_eq!(tornado.order[..2], [0, 3]);
+        assert!(tornado.low[0] > base && tornado.high[0] < base);
+    }
+
+    #[rstest]
+    fn test_tornado_undefined_irr() {
+        let dates: Vec<DateLike> = vec![date!(2020 - 01 - 01).into(), date!(2021 - 01 - 01).into()];
+        let sensitivity = Sensitivity::new(&dates, &[-100.0, 110.0], None, Compounding::Annual);
+
+        // a 100% shock removes the only distribution
+        let tornado = sensitivity.unwrap().tornado(1.0, None);
+        assert!(tornado.low[1].is_nan());
+        assert_eq!(tornado.order, vec![0, 1]);
+
+        let tornado = Sensitivity::new(&dates, &[-100.0, 110.0], None, Compounding::Annual)
+            .unwrap()
+            .tornado(1.0, Some(0.1));
+        assert_approx_eq!(tornado.low[1], -100.0);
+    }
+}
@@ -1,8 +1,8 @@
-use super::{year_fraction, DayCount};
-use crate::core::{
+use super::{year_fraction, Compounding, DayCount};
+use crate::{
     models::{validate, validate_length, DateLike, InvalidPaymentsError},
     optimize::{brentq, newton_raphson_2},
-    utils::{fast_pow, initial_guess},
+    utils::{initial_guess, sum_compensated},
 };
 
 pub fn xirr(
@@ -10,29 +10,49 @@ pub fn xirr(
     amounts: &[f64],
     guess: Option<f64>,
     day_count: Option<DayCount>,
+) -> Result<f64, InvalidPaymentsError> {
+    xirr_compounded(dates, amounts, guess, day_count, Compounding::Annual)
+}
+
+/// [`xirr`] with the rate compounded as `compounding`.
+pub fn xirr_compounded(
+    dates: &[DateLike],
+    amounts: &[f64],
+    guess: Option<f64>,
+    day_count: Option<DayCount>,
+    compounding: Compounding,
 ) -> Result<f64, InvalidPaymentsError> {
     validate(amounts, Some(dates))?;
 
     let deltas = &day_count_factor(dates, day_count);
+    Ok(xirr_result(amounts, deltas, guess, compounding))
+}
 
+/// XIRR of validated amounts at the cached year fractions `deltas`, NaN if it does not converge.
+pub(super) fn xirr_result(
+    amounts: &[f64],
+    deltas: &[f64],
+    guess: Option<f64>,
+    compounding: Compounding,
+) -> f64 {
     if amounts.len() == 2 {
-        return Ok(xirr_analytical_2(amounts, deltas));
+        return compounding.rate_2(amounts, deltas);
     }
 
-    let f = |rate| xnpv_result(amounts, deltas, rate);
-    let fd = |rate| xnpv_result_with_deriv(amounts, deltas, rate);
+    let f = |rate| xnpv_result(amounts, deltas, rate, compounding);
+    let fd = |rate| xnpv_result_with_deriv(amounts, deltas, rate, compounding);
 
     let guess = guess.unwrap_or_else(|| initial_guess(amounts));
     let rate = newton_raphson_2(guess, &fd);
 
     if rate.is_finite() {
-        return Ok(rate);
+        return rate;
     }
 
     let rate = brentq(&f, -0.999999999999999, 100., 100);
 
     if rate.is_finite() {
-        return Ok(rate);
+        return rate;
     }
 
     let mut step = 0.01;
@@ -40,24 +60,13 @@ pub fn xirr(
     while guess < 1.0 {
         let rate = newton_raphson_2(guess, &fd);
         if rate.is_finite() {
-            return Ok(rate);
+            return rate;
         }
         guess += step;
         step = (step * 1.1).min(0.1);
     }
 
-    Ok(f64::NAN)
-}
-
-fn xirr_analytical_2(amounts: &[f64], deltas: &[f64]) -> f64 {
-    // solve analytically:
-    // cf[0]/(1+r)^d[0] + cf[1]/(1+r)^d[1] = 0  =>
-    // cf[1]/(1+r)^d[1] = -cf[0]/(1+r)^d[0]  => rearrange
-    // cf[1]/cf[0] = -(1+r)^d[1]/(1+r)^d[0]  => simplify
-    // cf[1]/cf[0] = -(1+r)^(d[1] - d[0])  => take the root
-    // (cf[1]/cf[0])^(1/(d[1] - d[0])) = -(1 + r) => multiply by -1 and subtract 1
-    // r = -(cf[1]/cf[0])^(1/(d[1] - d[0])) - 1
-    (-amounts[1] / amounts[0]).powf(1. / (deltas[1] - deltas[0])) - 1.0
+    f64::NAN
 }
 
 /// Calculate the net present value of a series of payments at irregular intervals.
@@ -66,11 +75,22 @@ pub fn xnpv(
     dates: &[DateLike],
     amounts: &[f64],
     day_count: Option<DayCount>,
+) -> Result<f64, InvalidPaymentsError> {
+    xnpv_compounded(rate, dates, amounts, day_count, Compounding::Annual)
+}
+
+/// [`xnpv`] with the rate compounded as `compounding`.
+pub fn xnpv_compounded(
+    rate: f64,
+    dates: &[DateLike],
+    amounts: &[f64],
+    day_count: Option<DayCount>,
+    compounding: Compounding,
 ) -> Result<f64, InvalidPaymentsError> {
     validate_length(amounts, dates)?;
 
     let deltas = &day_count_factor(dates, day_count);
-    Ok(xnpv_result(amounts, deltas, rate))
+    Ok(xnpv_result(amounts, deltas, rate, compounding))
 }
 
 pub fn sign_changes(v: &[f64]) -> i32 {
@@ -88,19 +108,24 @@ pub fn zero_crossing_points(v: &[f64]) -> Vec<usize> {
         .collect()
 }
 
-fn day_count_factor(dates: &[DateLike], day_count: Option<DayCount>) -> Vec<f64> {
+pub(super) fn day_count_factor(dates: &[DateLike], day_count: Option<DayCount>) -> Vec<f64> {
     let min_date = dates.iter().min().unwrap();
     let dc = day_count.unwrap_or_default();
-    dates.iter().map(|d| year_fraction(&min_date, &d, dc)).collect()
+    dates.iter().map(|d| year_fraction(min_date, d, dc)).collect()
 }
 
 // \sum_{i=1}^n \frac{P_i}{(1 + rate)^{(d_i - d_0)/365}}
-fn xnpv_result(payments: &[f64], deltas: &[f64], rate: f64) -> f64 {
-    if rate <= -1.0 {
+pub(super) fn xnpv_result(
+    payments: &[f64],
+    deltas: &[f64],
+    rate: f64,
+    compounding: Compounding,
+) -> f64 {
+    if rate <= compounding.min_rate(deltas) {
         // bound newton_raphson
         return f64::INFINITY;
     }
-    payments.iter().zip(deltas).map(|(p, &e)| p * fast_pow(1.0 + rate, -e)).sum()
+    sum_compensated(payments.iter().zip(deltas).map(|(p, &e)| p * compounding.discount(rate, e)))
 }
 
 // XNPV first derivative
@@ -111,16 +136,20 @@ fn xnpv_result(payments: &[f64], deltas: &[f64], rate: f64) -> f64 {
 //     payments.iter().zip(deltas).map(|(p, e)| p * -e * fast_pow(1.0 + rate, -e - 1.0)).sum()
 // }
 
-fn xnpv_result_with_deriv(payments: &[f64], deltas: &[f64], rate: f64) -> (f64, f64) {
-    if rate <= -1.0 {
+fn xnpv_result_with_deriv(
+    payments: &[f64],
+    deltas: &[f64],
+    rate: f64,
+    compounding: Compounding,
+) -> (f64, f64) {
+    if rate <= compounding.min_rate(deltas) {
         return (f64::INFINITY, f64::INFINITY);
     }
     // pow is an expensive function.
-    // we can re-use the result of pow for derivative calculation
-    payments.iter().zip(deltas).fold((0.0, 0.0), |acc, (p, e)| {
-        let y0 = p * fast_pow(1.0 + rate, -e);
-        let y1 = y0 * -e / (1.0 + rate);
-        (acc.0 + y0, acc.1 + y1)
+    // the discount factor is re-used for the derivative calculation
+    payments.iter().zip(deltas).fold((0.0, 0.0), |acc, (p, &e)| {
+        let (d, dd) = compounding.discount_with_deriv(rate, e);
+        (acc.0 + p * d, acc.1 + p * dd)
     })
 }
 
@@ -0,0 +1,70 @@
+use super::{year_fraction, Compounding, DayCount};
+use crate::models::{validate, DateLike, InvalidPaymentsError};
+
+// http://westclintech.com/SQL-Server-Financial-Functions/SQL-Server-XFV-function
+pub fn xfv(
+    start_date: &DateLike,
+    cash_flow_date: &DateLike,
+    end_date: &DateLike,
+    cash_flow_rate: f64,
+    end_rate: f64,
+    cash_flow: f64,
+    day_count: Option<DayCount>,
+) -> f64 {
+    xfv_compounded(
+        start_date,
+        cash_flow_date,
+        end_date,
+        cash_flow_rate,
+        end_rate,
+        cash_flow,
+        day_count,
+        Compounding::Annual,
+    )
+}
+
+/// [`xfv`] with the rates compounded as `compounding`.
+#[allow(clippy::too_many_arguments)]
+pub fn xfv_compounded(
+    start_date: &DateLike,
+    cash_flow_date: &DateLike,
+    end_date: &DateLike,
+    cash_flow_rate: f64,
+    end_rate: f64,
+    cash_flow: f64,
+    day_count: Option<DayCount>,
+    compounding: Compounding,
+) -> f64 {
+    let dc = day_count.unwrap_or_default();
+    let yf1 = year_fraction(start_date, end_date, dc);
+    let yf2 = year_fraction(start_date, cash_flow_date, dc);
+    let fv1 = compounding.growth(end_rate, yf1);
+    let fv2 = compounding.growth(cash_flow_rate, yf2);
+    fv1 / fv2 * cash_flow
+}
+
+// http://westclintech.com/SQL-Server-Financial-Functions/SQL-Server-XNFV-function
+pub fn xnfv(
+    rate: f64,
+    dates: &[DateLike],
+    amounts: &[f64],
+    day_count: Option<DayCount>,
+) -> Result<f64, InvalidPaymentsError> {
+    xnfv_compounded(rate, dates, amounts, day_count, Compounding::Annual)
+}
+
+/// [`xnfv`] with the rate compounded as `compounding`.
+pub fn xnfv_compounded(
+    rate: f64,
+    dates: &[DateLike],
+    amounts: &[f64],
+    day_count: Option<DayCount>,
+    compounding: Compounding,
+) -> Result<f64, InvalidPaymentsError> {
+    validate(amounts, Some(dates))?;
+    let d1 = dates.iter().min().unwrap();
+    let d2 = dates.iter().max().unwrap();
+    let periods = year_fraction(d1, d2, day_count.unwrap_or_default());
+    let pv = super::xnpv_compounded(rate, dates, amounts, None, compounding)?;
+    Ok(pv * compounding.growth(rate, periods))
+}
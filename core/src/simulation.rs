@@ -0,0 +1,461 @@
+//! Monte Carlo simulation of dated cash flows: every scenario perturbs the amounts and the
+//! timing of a base cash flow and evaluates XIRR, XNPV, TVPI and DPI.
+//!
+//! Each scenario draws from its own random stream, derived from the seed and the scenario
+//! index, so the results do not depend on the order (or the threads) the scenarios run in.
+
+use std::{fmt, str::FromStr};
+
+use time::{Duration, OffsetDateTime};
+
+use crate::{
+    models::{validate_length, DateLike, InvalidPaymentsError},
+    private_equity::{dpi, quantile, tvpi, RankMethod},
+    utils::sum_compensated,
+    xirr_compounded, xnpv_compounded, Compounding, DayCount,
+};
+
+type Result<T> = std::result::Result<T, InvalidPaymentsError>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Shock {
+    /// `amount * (1 + volatility * z)`
+    #[default]
+    Normal,
+    /// `amount * exp(volatility * z - volatility^2 / 2)`, keeps the sign and the mean.
+    LogNormal,
+}
+
+impl fmt::Display for Shock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let repr = match self {
+            Shock::Normal => "normal",
+            Shock::LogNormal => "lognormal",
+        };
+        write!(f, "{}", repr)
+    }
+}
+
+impl FromStr for Shock {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "normal" => Ok(Shock::Normal),
+            "lognormal" | "log-normal" | "log_normal" => Ok(Shock::LogNormal),
+            _ => Err("shock must be one of: 'normal', 'lognormal'"),
+        }
+    }
+}
+
+/// The stochastic specification of a single flow.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct FlowSpec {
+    pub shock: Shock,
+    /// Standard deviation of the relative amount shock.
+    pub volatility: f64,
+    /// Standard deviation of the timing jitter, in days. Dates move by whole days.
+    pub jitter_days: f64,
+    /// Probability that the flow does not happen at all (its amount is zero).
+    pub default_probability: f64,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SimulationTerms {
+    /// Discount rate of XNPV.
+    pub rate: f64,
+    /// Residual value at the latest date of a scenario: counts in TVPI,
+    /// and as a final distribution in XIRR and XNPV.
+    pub nav: f64,
+    pub day_count: Option<DayCount>,
+    pub compounding: Compounding,
+}
+
+/// The metrics of a single scenario, NaN where the metric is undefined
+/// (e.g. XIRR of a scenario where every contribution has defaulted).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Outcome {
+    pub xirr: f64,
+    pub xnpv: f64,
+    pub tvpi: f64,
+    pub dpi: f64,
+}
+
+/// The distribution of a metric over the scenarios that have it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Summary {
+    pub mean: f64,
+    /// Population standard deviation.
+    pub std: f64,
+    pub min: f64,
+    pub max: f64,
+    /// Number of scenarios where the metric is undefined.
+    pub failed: usize,
+    /// Aligned with the requested percentiles.
+    pub percentiles: Vec<f64>,
+}
+
+pub struct Simulation {
+    dates: Vec<DateLike>,
+    amounts: Vec<f64>,
+    specs: Vec<FlowSpec>,
+    terms: SimulationTerms,
+    guess: Option<f64>,
+}
+
+impl Simulation {
+    pub fn new(
+        dates: Vec<DateLike>,
+        amounts: Vec<f64>,
+        specs: Vec<FlowSpec>,
+        terms: SimulationTerms,
+    ) -> Result<Self> {
+        validate_length(&amounts, &dates)?;
+        if specs.len() != amounts.len() {
+            return Err(InvalidPaymentsError::new(
+                "the specs and amounts arrays are of different lengths",
+            ));
+        }
+        if let Some(index) = amounts.iter().position(|a| !a.is_finite()) {
+            return Err(InvalidPaymentsError::NaN {
+                index,
+            });
+        }
+        for spec in &specs {
+            if !(spec.volatility >= 0.0 && spec.volatility.is_finite()) {
+                return Err(InvalidPaymentsError::new("volatility must be non-negative"));
+            }
+            if !(spec.jitter_days >= 0.0 && spec.jitter_days.is_finite()) {
+                return Err(InvalidPaymentsError::new("jitter_days must be non-negative"));
+            }
+            if !(0.0..=1.0).contains(&spec.default_probability) {
+                return Err(InvalidPaymentsError::new(
+                    "default_probability must be between 0 and 1",
+                ));
+            }
+        }
+
+        // the base cash flow is a good starting point for every scenario
+        let mut simulation = Self {
+            dates,
+            amounts,
+            specs,
+            terms,
+            guess: None,
+        };
+        let (dates, amounts) =
+            simulation.with_nav(simulation.dates.clone(), simulation.amounts.clone());
+        simulation.guess =
+            xirr_compounded(&dates, &amounts, None, terms.day_count, terms.compounding).ok();
+
+        Ok(simulation)
+    }
+
+    /// The dates and amounts of the scenario `index`, without the NAV.
+    pub fn scenario(&self, seed: u64, index: u64) -> (Vec<DateLike>, Vec<f64>) {
+        let mut rng = Rng::new(seed, index);
+
+        self.dates
+            .iter()
+            .zip(&self.amounts)
+            .zip(&self.specs)
+            .map(|((&date, &amount), spec)| {
+                // every flow takes the same number of draws, whatever its spec,
+                // so that changing one flow does not reshuffle the others
+                let (z_amount, z_days, u_default) = (rng.normal(), rng.normal(), rng.uniform());
+
+                let amount = if u_default < spec.default_probability {
+                    0.0
+                } else {
+                    let v = spec.volatility;
+                    match spec.shock {
+                        Shock::Normal => amount * (1.0 + v * z_amount),
+                        Shock::LogNormal => amount * (v * z_amount - v * v / 2.0).exp(),
+                    }
+                };
+
+                let days = (spec.jitter_days * z_days).round() as i64;
+                (shift_days(date, days), amount)
+            })
+            .unzip()
+    }
+
+    /// The metrics of the scenario `index`.
+    pub fn outcome(&self, seed: u64, index: u64) -> Outcome {
+        let (dates, amounts) = self.scenario(seed, index);
+        let SimulationTerms {
+            rate,
+            nav,
+            day_count,
+            compounding,
+        } = self.terms;
+
+        let tvpi = tvpi(&amounts, nav).unwrap_or(f64::NAN);
+        let dpi = dpi(&amounts).unwrap_or(f64::NAN);
+
+        let (dates, amounts) = self.with_nav(dates, amounts);
+        let xirr = xirr_compounded(&dates, &amounts, self.guess, day_count, compounding);
+        let xnpv = xnpv_compounded(rate, &dates, &amounts, day_count, compounding);
+
+        Outcome {
+            xirr: xirr.unwrap_or(f64::NAN),
+            xnpv: xnpv.unwrap_or(f64::NAN),
+            tvpi,
+            dpi,
+        }
+    }
+
+    /// The metrics of the scenarios `0..scenarios`, one after another.
+    pub fn run(&self, seed: u64, scenarios: u64) -> Vec<Outcome> {
+        (0..scenarios).map(|index| self.outcome(seed, index)).collect()
+    }
+
+    fn with_nav(
+        &self,
+        mut dates: Vec<DateLike>,
+        mut amounts: Vec<f64>,
+    ) -> (Vec<DateLike>, Vec<f64>) {
+        if self.terms.nav != 0.0 {
+            if let Some(&last) = dates.iter().max() {
+                dates.push(last);
+                amounts.push(self.terms.nav);
+            }
+        }
+        (dates, amounts)
+    }
+}
+
+/// Mean, standard deviation, extremes and `percentiles` (between 0 and 1,
+/// linearly interpolated) of the finite `values`.
+pub fn summarize(values: &[f64], percentiles: &[f64]) -> Result<Summary> {
+    if let Some(p) = percentiles.iter().find(|p| !(0.0..=1.0).contains(*p)) {
+        return Err(InvalidPaymentsError::new(format!("percentile {p} is not between 0 and 1")));
+    }
+
+    let finite: Vec<f64> = values.iter().copied().filter(|v| v.is_finite()).collect();
+    let failed = values.len() - finite.len();
+    if finite.is_empty() {
+        return Ok(Summary {
+            mean: f64::NAN,
+            std: f64::NAN,
+            min: f64::NAN,
+            max: f64::NAN,
+            failed,
+            percentiles: vec![f64::NAN; percentiles.len()],
+        });
+    }
+
+    let n = finite.len() as f64;
+    let mean = sum_compensated(finite.iter().copied()) / n;
+    let variance = sum_compensated(finite.iter().map(|v| (v - mean).powi(2))) / n;
+
+    Ok(Summary {
+        mean,
+        std: variance.sqrt(),
+        min: finite.iter().copied().fold(f64::INFINITY, f64::min),
+        max: finite.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+        failed,
+        percentiles: percentiles
+            .iter()
+            .map(|&p| quantile(&finite, p, RankMethod::Inclusive))
+            .collect::<Result<_>>()?,
+    })
+}
+
+fn shift_days(date: DateLike, days: i64) -> DateLike {
+    if days == 0 {
+        return date;
+    }
+    OffsetDateTime::from(date).checked_add(Duration::days(days)).map_or(date, DateLike::from)
+}
+
+/// xoshiro256++ seeded by SplitMix64, see https://prng.di.unimi.it
+struct Rng {
+    state: [u64; 4],
+    spare: Option<f64>,
+}
+
+impl Rng {
+    fn new(seed: u64, stream: u64) -> Self {
+        let mut seed = seed;
+        let mut x = splitmix64(&mut seed) ^ stream;
+        Self {
+            state: [splitmix64(&mut x), splitmix64(&mut x), splitmix64(&mut x), splitmix64(&mut x)],
+            spare: None,
+        }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let s = &mut self.state;
+        let result = s[0].wrapping_add(s[3]).rotate_left(23).wrapping_add(s[0]);
+        let t = s[1] << 17;
+
+        s[2] ^= s[0];
+        s[3] ^= s[1];
+        s[1] ^= s[2];
+        s[0] ^= s[3];
+        s[2] ^= t;
+        s[3] = s[3].rotate_left(45);
+
+        result
+    }
+
+    /// Uniform in `[0, 1)`.
+    fn uniform(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 * (1.0 / (1u64 << 53) as f64)
+    }
+
+    /// Standard normal, by the Marsaglia polar method.
+    fn normal(&mut self) -> f64 {
+        if let Some(z) = self.spare.take() {
+            return z;
+        }
+        loop {
+            let u = 2.0 * self.uniform() - 1.0;
+            let v = 2.0 * self.uniform() - 1.0;
+            let s = u * u + v * v;
+            if s > 0.0 && s < 1.0 {
+                let factor = (-2.0 * s.ln() / s).sqrt();
+                self.spare = Some(v * factor);
+                return u * factor;
+            }
+        }
+    }
+}
+
+fn splitmix64(x: &mut u64) -> u64 {
+    *x = x.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut z = *x;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_approx_eq::assert_approx_eq;
+    use rstest::{fixture, rstest};
+    use time::macros::date;
+
+    use super::*;
+
+    #[fixture]
+    fn base() -> (Vec<DateLike>, Vec<f64>) {
+        let dates = vec![
+            date!(2020 - 01 - 01).into(),
+            date!(2021 - 01 - 01).into(),
+            date!(2022 - 01 - 01).into(),
+            date!(2023 - 01 - 01).into(),
+        ];
+        (dates, vec![-1000.0, 300.0, 400.0, 500.0])
+    }
+
+    fn simulation(base: (Vec<DateLike>, Vec<f64>), spec: FlowSpec) -> Simulation {
+        let specs = vec![spec; base.1.len()];
+        let terms = SimulationTerms {
+            rate: 0.05,
+            ..Default::default()
+        };
+        Simulation::new(base.0, base.1, specs, terms).unwrap()
+    }
+
+    #[rstest]
+    fn test_rng_moments() {
+        let mut rng = Rng::new(42, 0);
+        let n = 100_000;
+        let (uniform, normal): (Vec<f64>, Vec<f64>) =
+            (0..n).map(|_| (rng.uniform(), rng.normal())).unzip();
+
+        assert!(uniform.iter().all(|u| (0.0..1.0).contains(u)));
+        assert_approx_eq!(uniform.iter().sum::<f64>() / n as f64, 0.5, 0.01);
+
+        let summary = summarize(&normal, &[0.5]).unwrap();
+        assert_approx_eq!(summary.mean, 0.0, 0.01);
+        assert_approx_eq!(summary.std, 1.0, 0.01);
+    }
+
+    #[rstest]
+    fn test_without_randomness_every_scenario_is_the_base(base: (Vec<DateLike>, Vec<f64>)) {
+        let expected = xirr_compounded(&base.0, &base.1, None, None, Compounding::Annual).unwrap();
+        let outcomes = simulation(base, FlowSpec::default()).run(1, 10);
+
+        assert!(outcomes.iter().all(|o| (o.xirr - expected).abs() < 1e-9));
+        assert!(outcomes.iter().all(|o| (o.tvpi - 1.2).abs() < 1e-9 && o.tvpi == o.dpi));
+    }
+
+    #[rstest]
+    fn test_scenarios_are_reproducible(base: (Vec<DateLike>, Vec<f64>)) {
+        let spec = FlowSpec {
+            shock: Shock::LogNormal,
+            volatility: 0.2,
+            jitter_days: 30.0,
+            default_probability: 0.1,
+        };
+        let simulation = simulation(base, spec);
+
+        assert_eq!(simulation.scenario(7, 3), simulation.scenario(7, 3));
+        assert_ne!(simulation.scenario(7, 3), simulation.scenario(7, 4));
+        assert_ne!(simulation.scenario(7, 3), simulation.scenario(8, 3));
+    }
+
+    #[rstest]
+    fn test_lognormal_shock_keeps_the_mean(base: (Vec<DateLike>, Vec<f64>)) {
+        let spec = FlowSpec {
+            shock: Shock::LogNormal,
+            volatility: 0.3,
+            ..Default::default()
+        };
+        let simulation = simulation(base, spec);
+        let last: Vec<f64> = (0..20_000).map(|i| simulation.scenario(0, i).1[3]).collect();
+
+        assert!(last.iter().all(|&a| a > 0.0));
+        assert_approx_eq!(summarize(&last, &[]).unwrap().mean, 500.0, 5.0);
+    }
+
+    #[rstest]
+    fn test_default_probability(base: (Vec<DateLike>, Vec<f64>)) {
+        let spec = FlowSpec {
+            default_probability: 1.0,
+            ..Default::default()
+        };
+        let outcome = simulation(base, spec).outcome(0, 0);
+
+        assert!(outcome.xirr.is_nan() && outcome.dpi.is_nan());
+        assert_eq!(outcome.xnpv, 0.0);
+    }
+
+    #[rstest]
+    fn test_jitter_moves_whole_days(base: (Vec<DateLike>, Vec<f64>)) {
+        let spec = FlowSpec {
+            jitter_days: 10.0,
+            ..Default::default()
+        };
+        let (dates, _) = simulation(base.clone(), spec).scenario(0, 0);
+
+        assert_ne!(dates, base.0);
+        assert!(dates.iter().all(|d| d.day_fraction() == 0.0));
+    }
+
+    #[rstest]
+    fn test_summarize() {
+        let summary = summarize(&[4.0, f64::NAN, 1.0, 3.0, 2.0], &[0.0, 0.5, 0.9]).unwrap();
+
+        assert_eq!(summary.failed, 1);
+        assert_approx_eq!(summary.mean, 2.5);
+        assert_approx_eq!(summary.std, 1.25f64.sqrt());
+        assert_eq!((summary.min, summary.max), (1.0, 4.0));
+        assert_eq!(summary.percentiles, vec![1.0, 2.5, 3.7]);
+
+        assert!(summarize(&[1.0], &[50.0]).is_err());
+    }
+
+    #[rstest]
+    #[case(FlowSpec { volatility: -0.1, ..Default::default() })]
+    #[case(FlowSpec { jitter_days: f64::NAN, ..Default::default() })]
+    #[case(FlowSpec { default_probability: 1.5, ..Default::default() })]
+    fn test_invalid_specs(base: (Vec<DateLike>, Vec<f64>), #[case] spec: FlowSpec) {
+        let specs = vec![spec; base.1.len()];
+        let result = Simulation::new(base.0, base.1, specs, SimulationTerms::default());
+        assert!(matches!(result, Err(InvalidPaymentsError::Invalid(_))));
+    }
+}
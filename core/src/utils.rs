@@ -0,0 +1,113 @@
+use std::ops::Range;
+
+pub(crate) fn non_zero_range(p: &[f64]) -> Range<usize> {
+    let n = p.len();
+    let first_non_zero_index = p.iter().position(|&x| x != 0.0).unwrap_or(n);
+    let last_non_zero_index = n - p.iter().rev().position(|&x| x != 0.0).unwrap_or(0);
+    first_non_zero_index..last_non_zero_index
+}
+
+pub(crate) fn trim_zeros(p: &[f64]) -> &[f64] {
+    &p[non_zero_range(p)]
+}
+
+pub(crate) fn fast_pow(a: f64, b: f64) -> f64 {
+    // works only if a is positive
+    (a.log2() * b).exp2()
+}
+
+pub(crate) fn scale(values: &[f64], factor: f64) -> Vec<f64> {
+    values.iter().map(|v| v * factor).collect()
+}
+
+pub(crate) fn sum_pairwise_mul(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+pub(crate) fn pairwise_mul(a: &[f64], b: &[f64]) -> Vec<f64> {
+    a.iter().zip(b).map(|(x, y)| x * y).collect()
+}
+
+pub(crate) fn series_signum(a: &[f64]) -> f64 {
+    // returns -1. if any item is negative, otherwise +1.
+    if a.iter().any(|x| x.is_sign_negative()) {
+        -1.
+    } else {
+        1.
+    }
+}
+
+/// Neumaier's variant of Kahan summation, the rounding error does not grow
+/// with the number of values.
+pub(crate) fn sum_compensated<I: IntoIterator<Item = f64>>(values: I) -> f64 {
+    let (sum, compensation) = values.into_iter().fold((0.0, 0.0), |(sum, c), x: f64| {
+        let t = sum + x;
+        if f64::abs(sum) >= x.abs() {
+            (t, c + ((sum - t) + x))
+        } else {
+            (t, c + ((x - t) + sum))
+        }
+    });
+    // the compensation is NaN if the sum overflows
+    if sum.is_finite() {
+        sum + compensation
+    } else {
+        sum
+    }
+}
+
+pub(crate) fn sum_negatives_positives(values: &[f64]) -> (f64, f64) {
+    (
+        sum_compensated(values.iter().copied().filter(|x| x.is_sign_negative())),
+        sum_compensated(values.iter().copied().filter(|x| !x.is_sign_negative())),
+    )
+}
+
+/// The first point where the running total of `values` turns from negative to non-negative:
+/// the index of the crossing value and the share of it that brings the total to zero.
+pub(crate) fn payback_point<I: IntoIterator<Item = f64>>(values: I) -> Option<(usize, f64)> {
+    let mut total = 0.0;
+    for (i, value) in values.into_iter().enumerate() {
+        let next = total + value;
+        if total < 0.0 && next >= 0.0 {
+            return Some((i, -total / value));
+        }
+        total = next;
+    }
+    None
+}
+
+pub(crate) fn initial_guess(values: &[f64]) -> f64 {
+    let (outflows, inflows) = sum_negatives_positives(values);
+    let guess = inflows / -outflows - 1.0;
+    guess.clamp(-0.9, 0.1)
+}
+
+pub(crate) fn is_a_good_rate<F>(rate: f64, f: F) -> bool
+where
+    F: Fn(f64) -> f64,
+{
+    rate.is_finite() && f(rate).abs() < 1e-3
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    fn test_sum_compensated() {
+        assert_eq!(sum_compensated([1e100, 1.0, -1e100]), 1.0);
+        assert_eq!(sum_compensated([0.1; 10]), 1.0);
+        assert_ne!([0.1; 10].iter().sum::<f64>(), 1.0);
+        assert_eq!(sum_compensated([f64::MAX, f64::MAX]), f64::INFINITY);
+        assert!(sum_compensated([1.0, f64::NAN]).is_nan());
+    }
+
+    #[rstest]
+    fn test_sum_negatives_positives() {
+        let values = [-0.1; 10].into_iter().chain([0.1; 10]).collect::<Vec<_>>();
+        assert_eq!(sum_negatives_positives(&values), (-1.0, 1.0));
+    }
+}
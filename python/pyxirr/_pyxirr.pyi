@@ -111,6 +111,7 @@ _Period = Union[int, float, Decimal]
 _Guess = Optional[_Rate]
 _Amount = Union[int, float, Decimal]
 _DayCount = Union["DayCount" | str]
+_Compounding = Union[Literal["annual", "continuous", "simple"], int]  # int: periods per year
 
 _DateLike = Union[str, date, datetime, _datetime64, _Timestamp]
 _Payment = Tuple[_DateLike, _Amount]
@@ -121,6 +122,15 @@ _CashFlow = Union[_CashFlowTable, _CashFlowDict, _Series]
 _DateLikeArray = Iterable[_DateLike]
 _AmountArray = Iterable[_Amount]
 
+# a DataFrame or a dict of columns, used with date_col, amount_col and by
+_ColumnTable = Union[_DataFrame, Dict[Hashable, Iterable[Any]]]
+# per group (or per column) results, a pandas Series if pandas is installed
+_Grouped = Union[_Series, Dict[Any, Optional[float]]]
+# a cash flow per row (axis=1) or per column (axis=0), used with axis
+_AmountMatrix = Union[_ndarray, Sequence[Sequence[_Amount]]]
+# a numpy array for a numpy input, a list otherwise
+_RowResults = Union[_ndarray, List[Optional[float]]]
+
 _T = TypeVar("_T")
 _ArrayLike = Union[
     _ndarray,
@@ -144,10 +154,40 @@ class InvalidPaymentsError(Exception):
     pass
 
 
+class LengthMismatchError(InvalidPaymentsError):
+    amounts_len: int
+    dates_len: int  # not set for the index of the private equity functions
+    index_len: int  # only set for the index of the private equity functions
+
+
+class NoSignChangeError(InvalidPaymentsError):
+    pass
+
+
+class ZeroContributionsError(InvalidPaymentsError):
+    pass
+
+
+class EmptyInputError(InvalidPaymentsError):
+    pass
+
+
+class NaNAmountError(InvalidPaymentsError):
+    index: int
+
+
+class MissingDateError(InvalidPaymentsError):
+    index: int
+
+
 class BroadcastingError(Exception):
     pass
 
 
+class AmbiguousDateError(ValueError):
+    pass
+
+
 class DayCount:
     ACT_ACT_ISDA: "DayCount"
     ACT_365F: "DayCount"
@@ -167,7 +207,15 @@ class DayCount:
         ...
 
 
-def year_fraction(d1: _DateLike, d2: _DateLike, day_count: _DayCount) -> float:
+def year_fraction(
+    d1: _DateLike,
+    d2: _DateLike,
+    day_count: _DayCount,
+    *,
+    intraday: bool = False,
+    date_format: Optional[str] = None,
+    excel_serial: Union[bool, int, None] = None,  # True (1900), 1900 or 1904
+) -> float:
     ...
 
 
@@ -183,6 +231,7 @@ def fv(  # type: ignore[misc]
     pv: _Amount,
     *,
     pmt_at_beginning: bool = False,
+    keep_dtype: bool = False,
 ) -> Optional[float]:
     ...
 
@@ -195,6 +244,7 @@ def fv(
     pv: _ScalarOrArrayLike[_Amount],
     *,
     pmt_at_beginning: _ScalarOrArrayLike[bool] = False,
+    keep_dtype: bool = False,
 ) -> List[Optional[float]]:
     ...
 
@@ -203,6 +253,8 @@ def nfv(
     rate: _Rate,  # Rate of interest per period
     nper: _Period,  # Number of compounding periods
     amounts: _AmountArray,
+    *,
+    nan_policy: str = "raise",  # "raise" or "zero"
 ) -> Optional[float]:
     ...
 
@@ -214,6 +266,9 @@ def xfv(
     cash_flow_rate: _Rate,  # annual rate
     end_rate: _Rate,  # annual rate
     cash_flow: _Amount,
+    *,
+    day_count: Optional[_DayCount] = None,
+    compounding: _Compounding = "annual",
 ) -> Optional[float]:
     ...
 
@@ -225,10 +280,36 @@ def xnfv(
     *,
     silent: bool = False,
     day_count: _DayCount = DayCount.ACT_365F,
+    nan_policy: str = "raise",  # "raise", "omit" or "zero"
+    date_col: _Label = None,
+    amount_col: _Label = None,
+    intraday: bool = False,
+    date_format: Optional[str] = None,
+    excel_serial: Union[bool, int, None] = None,  # True (1900), 1900 or 1904
+    compounding: _Compounding = "annual",
 ) -> Optional[float]:
     ...
 
 
+@overload
+def xnfv(
+    rate: _Rate,  # annual rate
+    dates: _ColumnTable,
+    *,
+    silent: bool = False,
+    day_count: _DayCount = DayCount.ACT_365F,
+    nan_policy: str = "raise",  # "raise", "omit" or "zero"
+    date_col: _Label = None,
+    amount_col: _Label = None,
+    by: Hashable,
+    intraday: bool = False,
+    date_format: Optional[str] = None,
+    excel_serial: Union[bool, int, None] = None,  # True (1900), 1900 or 1904
+    compounding: _Compounding = "annual",
+) -> _Grouped:
+    ...
+
+
 @overload
 def xnfv(
     rate: _Rate,  # annual rate
@@ -237,6 +318,11 @@ def xnfv(
     *,
     silent: bool = False,
     day_count: _DayCount = DayCount.ACT_365F,
+    nan_policy: str = "raise",  # "raise", "omit" or "zero"
+    intraday: bool = False,
+    date_format: Optional[str] = None,
+    excel_serial: Union[bool, int, None] = None,  # True (1900), 1900 or 1904
+    compounding: _Compounding = "annual",
 ) -> Optional[float]:
     ...
 
@@ -249,6 +335,7 @@ def pv(  # type: ignore[misc]
     fv: _Amount,
     *,
     pmt_at_beginning: bool = False,
+    keep_dtype: bool = False,
 ) -> Optional[float]:
     ...
 
@@ -261,6 +348,7 @@ def pv(
     fv: _ScalarOrArrayLike[_Amount],
     *,
     pmt_at_beginning: _ScalarOrArrayLike[bool] = False,
+    keep_dtype: bool = False,
 ) -> List[Optional[float]]:
     ...
 
@@ -271,20 +359,128 @@ def npv(
     amounts: _AmountArray,
     *,
     start_from_zero: bool = True,
+    nan_policy: str = "raise",  # "raise" or "zero"
 ) -> Optional[float]:
     ...
 
 
+@overload
+def npv(
+    rate: _Rate,
+    amounts: _AmountArray,
+    *,
+    start_from_zero: bool = True,
+    nan_policy: str = "raise",  # "raise" or "zero"
+    decimal: Literal[True],
+) -> Decimal:
+    ...
+
+
 @overload
 def npv(
     rate: Iterable[_Rate],
     amounts: _AmountArray,
     *,
     start_from_zero: bool = True,
+    nan_policy: str = "raise",  # "raise" or "zero"
 ) -> List[Optional[float]]:
     ...
 
 
+@overload
+def npv(
+    rate: _Rate,
+    amounts: _AmountMatrix,
+    *,
+    start_from_zero: bool = True,
+    nan_policy: str = "raise",  # "raise" or "zero"
+    axis: int,
+) -> _RowResults:
+    ...
+
+
+def payback(
+    amounts: _AmountArray,
+    *,
+    nan_policy: str = "raise",  # "raise" or "zero"
+) -> Optional[float]:
+    ...
+
+
+def discounted_payback(
+    rate: _Rate,
+    amounts: _AmountArray,
+    *,
+    nan_policy: str = "raise",  # "raise" or "zero"
+) -> Optional[float]:
+    ...
+
+
+def profitability_index(
+    rate: _Rate,
+    amounts: _AmountArray,
+    *,
+    start_from_zero: bool = True,
+    nan_policy: str = "raise",  # "raise" or "zero"
+) -> float:
+    ...
+
+
+def eaa(
+    rate: _Rate,
+    amounts: _AmountArray,
+    *,
+    start_from_zero: bool = True,
+    nan_policy: str = "raise",  # "raise" or "zero"
+) -> Optional[float]:
+    ...
+
+
+def npv_profile(
+    rates: _AmountArray,
+    amounts: _AmountArray,
+    *,
+    other: Optional[_AmountArray] = None,  # a second project to compare with
+    start_from_zero: bool = True,
+    guess: _Guess = None,
+    nan_policy: str = "raise",  # "raise" or "zero"
+) -> Dict[str, Any]:  # {"npv": [...]}, plus "other": [...] and "crossover_rate" if other is given
+    ...
+
+
+def xnpv_profile(
+    rates: _AmountArray,
+    dates: Union[_CashFlow, _DateLikeArray],
+    amounts: Optional[_AmountArray] = None,
+    *,
+    other: Optional[_CashFlow] = None,  # a second project to compare with
+    guess: _Guess = None,
+    day_count: _DayCount = DayCount.ACT_365F,
+    nan_policy: str = "raise",  # "raise", "omit" or "zero"
+    compounding: _Compounding = "annual",
+    intraday: bool = False,
+    date_format: Optional[str] = None,
+    excel_serial: Union[bool, int, None] = None,  # True (1900), 1900 or 1904
+) -> Dict[str, Any]:  # {"npv": [...]}, plus "other": [...] and "crossover_rate" if other is given
+    ...
+
+
+def xpayback(
+    dates: Union[_CashFlow, _DateLikeArray],
+    amounts: Optional[_AmountArray] = None,
+    *,
+    rate: Optional[_Rate] = None,  # discount the cash flows at this rate
+    silent: bool = False,
+    day_count: _DayCount = DayCount.ACT_365F,
+    nan_policy: str = "raise",  # "raise", "omit" or "zero"
+    compounding: _Compounding = "annual",
+    intraday: bool = False,
+    date_format: Optional[str] = None,
+    excel_serial: Union[bool, int, None] = None,  # True (1900), 1900 or 1904
+) -> Optional[date]:
+    ...
+
+
 @overload
 def xnpv(
     rate: _Rate,
@@ -292,10 +488,36 @@ def xnpv(
     *,
     silent: bool = False,
     day_count: _DayCount = DayCount.ACT_365F,
+    nan_policy: str = "raise",  # "raise", "omit" or "zero"
+    date_col: _Label = None,
+    amount_col: _Label = None,
+    intraday: bool = False,
+    date_format: Optional[str] = None,
+    excel_serial: Union[bool, int, None] = None,  # True (1900), 1900 or 1904
+    compounding: _Compounding = "annual",
 ) -> Optional[float]:
     ...
 
 
+@overload
+def xnpv(
+    rate: _Rate,
+    dates: _ColumnTable,
+    *,
+    silent: bool = False,
+    day_count: _DayCount = DayCount.ACT_365F,
+    nan_policy: str = "raise",  # "raise", "omit" or "zero"
+    date_col: _Label = None,
+    amount_col: _Label = None,
+    by: Hashable,
+    intraday: bool = False,
+    date_format: Optional[str] = None,
+    excel_serial: Union[bool, int, None] = None,  # True (1900), 1900 or 1904
+    compounding: _Compounding = "annual",
+) -> _Grouped:
+    ...
+
+
 @overload
 def xnpv(
     rate: _Rate,
@@ -304,10 +526,32 @@ def xnpv(
     *,
     silent: bool = False,
     day_count: _DayCount = DayCount.ACT_365F,
+    nan_policy: str = "raise",  # "raise", "omit" or "zero"
+    intraday: bool = False,
+    date_format: Optional[str] = None,
+    excel_serial: Union[bool, int, None] = None,  # True (1900), 1900 or 1904
+    compounding: _Compounding = "annual",
 ) -> Optional[float]:
     ...
 
 
+@overload
+def xnpv(
+    rate: _Rate,
+    dates: Union[_CashFlow, _DateLikeArray],
+    amounts: Optional[_AmountArray] = None,
+    *,
+    silent: bool = False,
+    day_count: _DayCount = DayCount.ACT_365F,
+    nan_policy: str = "raise",  # "raise", "omit" or "zero"
+    intraday: bool = False,
+    date_format: Optional[str] = None,
+    excel_serial: Union[bool, int, None] = None,  # True (1900), 1900 or 1904
+    decimal: Literal[True],
+) -> Optional[Decimal]:
+    ...
+
+
 @overload
 def xnpv(
     rate: Iterable[_Rate],
@@ -315,10 +559,33 @@ def xnpv(
     *,
     silent: bool = False,
     day_count: _DayCount = DayCount.ACT_365F,
+    nan_policy: str = "raise",  # "raise", "omit" or "zero"
+    intraday: bool = False,
+    date_format: Optional[str] = None,
+    excel_serial: Union[bool, int, None] = None,  # True (1900), 1900 or 1904
+    compounding: _Compounding = "annual",
 ) -> List[Optional[float]]:
     ...
 
 
+@overload
+def xnpv(
+    rate: _Rate,
+    dates: _DateLikeArray,
+    amounts: _AmountMatrix,
+    *,
+    silent: bool = False,
+    day_count: _DayCount = DayCount.ACT_365F,
+    nan_policy: str = "raise",  # "raise", "omit" or "zero"
+    intraday: bool = False,
+    date_format: Optional[str] = None,
+    excel_serial: Union[bool, int, None] = None,  # True (1900), 1900 or 1904
+    axis: int,
+    compounding: _Compounding = "annual",
+) -> _RowResults:
+    ...
+
+
 @overload
 def rate(  # type: ignore[misc]
     nper: _Period,
@@ -328,6 +595,7 @@ def rate(  # type: ignore[misc]
     *,
     pmt_at_beginning: bool = False,
     guess: _Guess = None,
+    keep_dtype: bool = False,
 ) -> Optional[float]:
     ...
 
@@ -341,6 +609,7 @@ def rate(
     *,
     pmt_at_beginning: _ScalarOrArrayLike[bool] = False,
     guess: _Guess = None,
+    keep_dtype: bool = False,
 ) -> List[Optional[float]]:
     ...
 
@@ -353,6 +622,7 @@ def nper(  # type: ignore[misc]
     fv: _Amount = 0,
     *,
     pmt_at_beginning: bool = False,
+    keep_dtype: bool = False,
 ) -> Optional[float]:
     ...
 
@@ -365,6 +635,7 @@ def nper(
     fv: _ScalarOrArrayLike[_Amount] = 0,
     *,
     pmt_at_beginning: _ScalarOrArrayLike[bool] = False,
+    keep_dtype: bool = False,
 ) -> List[Optional[float]]:
     ...
 
@@ -377,6 +648,7 @@ def pmt(  # type: ignore[misc]
     fv: _Amount = 0,
     *,
     pmt_at_beginning: bool = False,
+    keep_dtype: bool = False,
 ) -> Optional[float]:
     ...
 
@@ -389,6 +661,7 @@ def pmt(
     fv: _ScalarOrArrayLike[_Amount] = 0,
     *,
     pmt_at_beginning: _ScalarOrArrayLike[bool] = False,
+    keep_dtype: bool = False,
 ) -> List[Optional[float]]:
     ...
 
@@ -402,6 +675,7 @@ def ipmt(  # type: ignore[misc]
     fv: _Amount = 0,
     *,
     pmt_at_beginning: bool = False,
+    keep_dtype: bool = False,
 ) -> Optional[float]:
     ...
 
@@ -415,6 +689,7 @@ def ipmt(
     fv: _ScalarOrArrayLike[_Amount] = 0,
     *,
     pmt_at_beginning: _ScalarOrArrayLike[bool] = False,
+    keep_dtype: bool = False,
 ) -> List[Optional[float]]:
     ...
 
@@ -440,6 +715,7 @@ def ppmt(  # type: ignore[misc]
     fv: _Amount = 0,
     *,
     pmt_at_beginning: bool = False,
+    keep_dtype: bool = False,
 ) -> Optional[float]:
     ...
 
@@ -453,6 +729,7 @@ def ppmt(
     fv: _ScalarOrArrayLike[_Amount] = 0,
     *,
     pmt_at_beginning: _ScalarOrArrayLike[bool] = False,
+    keep_dtype: bool = False,
 ) -> List[Optional[float]]:
     ...
 
@@ -469,25 +746,54 @@ def cumprinc(
     ...
 
 
+@overload
 def irr(
     amounts: _AmountArray,
     *,
     guess: _Guess = None,
     silent: bool = False,
+    nan_policy: str = "raise",  # "raise" or "zero"
 ) -> Optional[float]:
     ...
 
 
+@overload
+def irr(
+    amounts: _AmountMatrix,
+    *,
+    guess: _Guess = None,
+    silent: bool = False,
+    nan_policy: str = "raise",  # "raise" or "zero"
+    axis: int,
+) -> _RowResults:
+    ...
+
+
+@overload
 def mirr(
     amounts: _AmountArray,
     finance_rate: _Rate,
     reinvest_rate: _Rate,
     *,
     silent: bool = False,
+    nan_policy: str = "raise",  # "raise" or "zero"
 ) -> Optional[float]:
     ...
 
 
+@overload
+def mirr(
+    amounts: _AmountMatrix,
+    finance_rate: _Rate,
+    reinvest_rate: _Rate,
+    *,
+    silent: bool = False,
+    nan_policy: str = "raise",  # "raise" or "zero"
+    axis: int,
+) -> _RowResults:
+    ...
+
+
 @overload
 def xirr(
     dates: _DateLikeArray,
@@ -496,6 +802,12 @@ def xirr(
     guess: _Guess = None,
     silent: bool = False,
     day_count: _DayCount = DayCount.ACT_365F,
+    nan_policy: str = "raise",  # "raise", "omit" or "zero"
+    intraday: bool = False,
+    date_format: Optional[str] = None,
+    excel_serial: Union[bool, int, None] = None,  # True (1900), 1900 or 1904
+    compounding: _Compounding = "annual",
+    money_market: bool = False,  # simple interest, ACT/360 by default
 ) -> Optional[float]:
     ...
 
@@ -507,10 +819,151 @@ def xirr(
     guess: _Guess = None,
     silent: bool = False,
     day_count: _DayCount = DayCount.ACT_365F,
+    nan_policy: str = "raise",  # "raise", "omit" or "zero"
+    date_col: _Label = None,
+    amount_col: _Label = None,
+    intraday: bool = False,
+    date_format: Optional[str] = None,
+    excel_serial: Union[bool, int, None] = None,  # True (1900), 1900 or 1904
+    compounding: _Compounding = "annual",
+    money_market: bool = False,  # simple interest, ACT/360 by default
 ) -> Optional[float]:
     ...
 
 
+@overload
+def xirr(
+    dates: _ColumnTable,
+    *,
+    guess: _Guess = None,
+    silent: bool = False,
+    day_count: _DayCount = DayCount.ACT_365F,
+    nan_policy: str = "raise",  # "raise", "omit" or "zero"
+    date_col: _Label = None,
+    amount_col: _Label = None,
+    by: Hashable,
+    intraday: bool = False,
+    date_format: Optional[str] = None,
+    excel_serial: Union[bool, int, None] = None,  # True (1900), 1900 or 1904
+    compounding: _Compounding = "annual",
+    money_market: bool = False,  # simple interest, ACT/360 by default
+) -> _Grouped:
+    ...
+
+
+@overload
+def xirr(
+    dates: _DateLikeArray,
+    amounts: _AmountMatrix,
+    *,
+    guess: _Guess = None,
+    silent: bool = False,
+    day_count: _DayCount = DayCount.ACT_365F,
+    nan_policy: str = "raise",  # "raise", "omit" or "zero"
+    intraday: bool = False,
+    date_format: Optional[str] = None,
+    excel_serial: Union[bool, int, None] = None,  # True (1900), 1900 or 1904
+    axis: int,
+    compounding: _Compounding = "annual",
+    money_market: bool = False,  # simple interest, ACT/360 by default
+) -> _RowResults:
+    ...
+
+
+@overload
+def xgoal_seek(
+    rate: _Rate,  # target annual rate
+    dates: Union[_CashFlow, Sequence[Optional[_DateLike]]],
+    amounts: Optional[Sequence[Optional[_Amount]]] = None,
+    *,
+    solve_for: Literal["amount", "scale"] = "amount",
+    index: int = -1,  # position of the unknown amount
+    silent: bool = False,
+    day_count: _DayCount = DayCount.ACT_365F,
+    nan_policy: str = "raise",  # "raise", "omit" or "zero"
+    compounding: _Compounding = "annual",
+    intraday: bool = False,
+    date_format: Optional[str] = None,
+    excel_serial: Union[bool, int, None] = None,  # True (1900), 1900 or 1904
+) -> Optional[float]:
+    ...
+
+
+@overload
+def xgoal_seek(
+    rate: _Rate,  # target annual rate
+    dates: Union[_CashFlow, Sequence[Optional[_DateLike]]],
+    amounts: Optional[Sequence[Optional[_Amount]]] = None,
+    *,
+    solve_for: Literal["date"],
+    index: int = -1,  # position of the unknown date
+    silent: bool = False,
+    day_count: _DayCount = DayCount.ACT_365F,
+    nan_policy: str = "raise",  # "raise", "omit" or "zero"
+    compounding: _Compounding = "annual",
+    intraday: bool = False,
+    date_format: Optional[str] = None,
+    excel_serial: Union[bool, int, None] = None,  # True (1900), 1900 or 1904
+) -> Optional[date]:
+    ...
+
+
+def monte_carlo(
+    rate: _Rate,  # xnpv discount rate
+    dates: Union[_CashFlow, _DateLikeArray],
+    amounts: Optional[_AmountArray] = None,
+    *,
+    scenarios: int = 10_000,
+    seed: Optional[int] = None,
+    shock: Union[Literal["normal", "lognormal"], Sequence[str], None] = None,  # per flow, "normal" by default
+    volatility: Union[float, Sequence[float], None] = None,  # per flow, 0 by default
+    jitter_days: Union[float, Sequence[float], None] = None,  # per flow, 0 by default
+    default_probability: Union[float, Sequence[float], None] = None,  # per flow, 0 by default
+    nav: float = 0.0,
+    percentiles: Sequence[float] = (5, 25, 50, 75, 95),
+    day_count: _DayCount = DayCount.ACT_365F,
+    nan_policy: str = "raise",  # "raise", "omit" or "zero"
+    compounding: _Compounding = "annual",
+    intraday: bool = False,
+    date_format: Optional[str] = None,
+    excel_serial: Union[bool, int, None] = None,  # True (1900), 1900 or 1904
+) -> Dict[str, Any]:  # {"seed": ..., "xirr": {"mean", "std", "min", "max", "failed", "percentiles", "values"}, "xnpv": ..., "tvpi": ..., "dpi": ...}
+    ...
+
+
+def sensitivity_table(
+    rates: _AmountArray,
+    shocks: _AmountArray,  # relative, 0.1 scales the shocked flows by 1.1
+    dates: Union[_CashFlow, _DateLikeArray],
+    amounts: Optional[_AmountArray] = None,
+    *,
+    flows: Optional[Sequence[int]] = None,  # indexes of the shocked flows, the distributions by default
+    day_count: _DayCount = DayCount.ACT_365F,
+    nan_policy: str = "raise",  # "raise", "omit" or "zero"
+    compounding: _Compounding = "annual",
+    intraday: bool = False,
+    date_format: Optional[str] = None,
+    excel_serial: Union[bool, int, None] = None,  # True (1900), 1900 or 1904
+) -> Union[_DataFrame, List[List[float]]]:  # a DataFrame if pandas is installed
+    ...
+
+
+def tornado(
+    dates: Union[_CashFlow, _DateLikeArray],
+    amounts: Optional[_AmountArray] = None,
+    *,
+    shock: float = 0.1,
+    rate: Optional[_Rate] = None,  # xnpv at this rate instead of xirr
+    day_count: _DayCount = DayCount.ACT_365F,
+    nan_policy: str = "raise",  # "raise", "omit" or "zero"
+    compounding: _Compounding = "annual",
+    intraday: bool = False,
+    date_format: Optional[str] = None,
+    excel_serial: Union[bool, int, None] = None,  # True (1900), 1900 or 1904
+) -> Dict[str, Any]:  # {"base": float, "low": [...], "high": [...], "order": [...]}
+    ...
+
+
 def is_conventional_cash_flow(cf: _AmountArray) -> bool:
     ...
 
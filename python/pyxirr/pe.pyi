@@ -1,79 +1,297 @@
-from collections.abc import Iterable
+from collections.abc import Hashable, Iterable
 from decimal import Decimal
-from typing import List, Optional, Tuple, Union
+from typing import Any, Dict, List, Literal, Optional, Tuple, Union, overload
+
+from ._pyxirr import _CashFlow, _DateLike, _DateLikeArray, _DayCount, _ndarray
 
 _Amount = Union[int, float, Decimal]
 _AmountArray = Iterable[_Amount]
 
+# series along the last axis, broadcast over the leading dimensions
+_SeriesArray = Union[_ndarray, Iterable[Iterable[Any]]]
+_ScalarArray = Union[_ndarray, Iterable[Any]]
+# a numpy array if any input was a numpy array, otherwise a (nested) list
+_Broadcast = Union[_ndarray, List[Any]]
+
+
+@overload
+def dpi(
+    amounts: _AmountArray,
+    *,
+    nan_policy: str = "raise",  # "raise" or "zero"
+    decimal: Literal[False] = False,
+) -> float:
+    ...
+
+
+@overload
+def dpi(
+    amounts: _AmountArray,
+    *,
+    nan_policy: str = "raise",  # "raise" or "zero"
+    decimal: Literal[True],
+) -> Decimal:
+    ...
+
 
-def dpi(amounts: _AmountArray) -> float:
+@overload
+def dpi(
+    amounts: Union[_AmountArray, _SeriesArray],
+    *,
+    nan_policy: str = "raise",  # "raise" or "zero"
+) -> _Broadcast:
     ...
 
 
+@overload
 def dpi_2(
     contributions: _AmountArray,
     distributions: _AmountArray,
+    *,
+    nan_policy: str = "raise",  # "raise" or "zero"
+    decimal: Literal[False] = False,
 ) -> float:
     ...
 
 
+@overload
+def dpi_2(
+    contributions: _AmountArray,
+    distributions: _AmountArray,
+    *,
+    nan_policy: str = "raise",  # "raise" or "zero"
+    decimal: Literal[True],
+) -> Decimal:
+    ...
+
+
+@overload
+def dpi_2(
+    contributions: Union[_AmountArray, _SeriesArray],
+    distributions: Union[_AmountArray, _SeriesArray],
+    *,
+    nan_policy: str = "raise",  # "raise" or "zero"
+) -> _Broadcast:
+    ...
+
+
+@overload
 def rvpi(
     contributions: _AmountArray,
     nav: _Amount,
+    *,
+    nan_policy: str = "raise",  # "raise" or "zero"
+    decimal: Literal[False] = False,
 ) -> float:
     ...
 
 
+@overload
+def rvpi(
+    contributions: _AmountArray,
+    nav: _Amount,
+    *,
+    nan_policy: str = "raise",  # "raise" or "zero"
+    decimal: Literal[True],
+) -> Decimal:
+    ...
+
+
+@overload
+def rvpi(
+    contributions: Union[_AmountArray, _SeriesArray],
+    nav: Union[_Amount, _ScalarArray],
+    *,
+    nan_policy: str = "raise",  # "raise" or "zero"
+) -> _Broadcast:
+    ...
+
+
+@overload
 def tvpi(
     amounts: _AmountArray,
     nav: _Amount = 0,
+    *,
+    nan_policy: str = "raise",  # "raise" or "zero"
+    decimal: Literal[False] = False,
 ) -> float:
     ...
 
 
+@overload
+def tvpi(
+    amounts: _AmountArray,
+    nav: _Amount = 0,
+    *,
+    nan_policy: str = "raise",  # "raise" or "zero"
+    decimal: Literal[True],
+) -> Decimal:
+    ...
+
+
+@overload
+def tvpi(
+    amounts: Union[_AmountArray, _SeriesArray],
+    nav: Union[_Amount, _ScalarArray] = 0,
+    *,
+    nan_policy: str = "raise",  # "raise" or "zero"
+) -> _Broadcast:
+    ...
+
+
+@overload
 def tvpi_2(
     contributions: _AmountArray,
     distributions: _AmountArray,
     nav: _Amount = 0,
+    *,
+    nan_policy: str = "raise",  # "raise" or "zero"
+    decimal: Literal[False] = False,
 ) -> float:
     ...
 
 
+@overload
+def tvpi_2(
+    contributions: _AmountArray,
+    distributions: _AmountArray,
+    nav: _Amount = 0,
+    *,
+    nan_policy: str = "raise",  # "raise" or "zero"
+    decimal: Literal[True],
+) -> Decimal:
+    ...
+
+
+@overload
+def tvpi_2(
+    contributions: Union[_AmountArray, _SeriesArray],
+    distributions: Union[_AmountArray, _SeriesArray],
+    nav: Union[_Amount, _ScalarArray] = 0,
+    *,
+    nan_policy: str = "raise",  # "raise" or "zero"
+) -> _Broadcast:
+    ...
+
+
+@overload
 def moic(
     amounts: _AmountArray,
     nav: _Amount = 0,
+    *,
+    nan_policy: str = "raise",  # "raise" or "zero"
+    decimal: Literal[False] = False,
 ) -> float:
     ...
 
 
+@overload
+def moic(
+    amounts: _AmountArray,
+    nav: _Amount = 0,
+    *,
+    nan_policy: str = "raise",  # "raise" or "zero"
+    decimal: Literal[True],
+) -> Decimal:
+    ...
+
+
+@overload
+def moic(
+    amounts: Union[_AmountArray, _SeriesArray],
+    nav: Union[_Amount, _ScalarArray] = 0,
+    *,
+    nan_policy: str = "raise",  # "raise" or "zero"
+) -> _Broadcast:
+    ...
+
+
+@overload
 def moic_2(
     contributions: _AmountArray,
     distributions: _AmountArray,
     nav: _Amount = 0,
+    *,
+    nan_policy: str = "raise",  # "raise" or "zero"
+    decimal: Literal[False] = False,
 ) -> float:
     ...
 
 
+@overload
+def moic_2(
+    contributions: _AmountArray,
+    distributions: _AmountArray,
+    nav: _Amount = 0,
+    *,
+    nan_policy: str = "raise",  # "raise" or "zero"
+    decimal: Literal[True],
+) -> Decimal:
+    ...
+
+
+@overload
+def moic_2(
+    contributions: Union[_AmountArray, _SeriesArray],
+    distributions: Union[_AmountArray, _SeriesArray],
+    nav: Union[_Amount, _ScalarArray] = 0,
+    *,
+    nan_policy: str = "raise",  # "raise" or "zero"
+) -> _Broadcast:
+    ...
+
+
+@overload
 def ks_pme(
     amounts: _AmountArray,
     index: _AmountArray,
     nav: _Amount = 0,
+    *,
+    nan_policy: str = "raise",  # "raise" or "zero"
 ) -> Optional[float]:
     ...
 
 
+@overload
+def ks_pme(
+    amounts: Union[_AmountArray, _SeriesArray],
+    index: Union[_AmountArray, _SeriesArray],
+    nav: Union[_Amount, _ScalarArray] = 0,
+    *,
+    nan_policy: str = "raise",  # "raise" or "zero"
+) -> _Broadcast:
+    ...
+
+
+@overload
 def ks_pme_2(
     contributions: _AmountArray,
     distributions: _AmountArray,
     index: _AmountArray,
     nav: _Amount = 0,
+    *,
+    nan_policy: str = "raise",  # "raise" or "zero"
 ) -> Optional[float]:
     ...
 
 
+@overload
+def ks_pme_2(
+    contributions: Union[_AmountArray, _SeriesArray],
+    distributions: Union[_AmountArray, _SeriesArray],
+    index: Union[_AmountArray, _SeriesArray],
+    nav: Union[_Amount, _ScalarArray] = 0,
+    *,
+    nan_policy: str = "raise",  # "raise" or "zero"
+) -> _Broadcast:
+    ...
+
+
 def ks_pme_flows(
     amounts: _AmountArray,
     index: _AmountArray,
+    *,
+    nan_policy: str = "raise",  # "raise" or "zero"
 ) -> List[float]:
     ...
 
@@ -82,48 +300,110 @@ def ks_pme_flows_2(
     contributions: _AmountArray,
     distributions: _AmountArray,
     index: _AmountArray,
+    *,
+    nan_policy: str = "raise",  # "raise" or "zero"
 ) -> Tuple[List[float], List[float]]:
     ...
 
 
+@overload
 def m_pme(
     amounts: _AmountArray,
     index: _AmountArray,
     nav: _AmountArray,
+    *,
+    nan_policy: str = "raise",  # "raise" or "zero"
 ) -> float:
     ...
 
 
+@overload
+def m_pme(
+    amounts: Union[_AmountArray, _SeriesArray],
+    index: Union[_AmountArray, _SeriesArray],
+    nav: Union[_AmountArray, _SeriesArray],
+    *,
+    nan_policy: str = "raise",  # "raise" or "zero"
+) -> _Broadcast:
+    ...
+
+
+@overload
 def m_pme_2(
     contributions: _AmountArray,
     distributions: _AmountArray,
     index: _AmountArray,
     nav: _AmountArray,
+    *,
+    nan_policy: str = "raise",  # "raise" or "zero"
 ) -> float:
     ...
 
 
+@overload
+def m_pme_2(
+    contributions: Union[_AmountArray, _SeriesArray],
+    distributions: Union[_AmountArray, _SeriesArray],
+    index: Union[_AmountArray, _SeriesArray],
+    nav: Union[_AmountArray, _SeriesArray],
+    *,
+    nan_policy: str = "raise",  # "raise" or "zero"
+) -> _Broadcast:
+    ...
+
+
+@overload
 def pme_plus(
     amounts: _AmountArray,
     index: _AmountArray,
     nav: _Amount = 0,
+    *,
+    nan_policy: str = "raise",  # "raise" or "zero"
 ) -> Optional[float]:
     ...
 
 
+@overload
+def pme_plus(
+    amounts: Union[_AmountArray, _SeriesArray],
+    index: Union[_AmountArray, _SeriesArray],
+    nav: Union[_Amount, _ScalarArray] = 0,
+    *,
+    nan_policy: str = "raise",  # "raise" or "zero"
+) -> _Broadcast:
+    ...
+
+
+@overload
 def pme_plus_2(
     contributions: _AmountArray,
     distributions: _AmountArray,
     index: _AmountArray,
     nav: _Amount = 0,
+    *,
+    nan_policy: str = "raise",  # "raise" or "zero"
 ) -> Optional[float]:
     ...
 
 
+@overload
+def pme_plus_2(
+    contributions: Union[_AmountArray, _SeriesArray],
+    distributions: Union[_AmountArray, _SeriesArray],
+    index: Union[_AmountArray, _SeriesArray],
+    nav: Union[_Amount, _ScalarArray] = 0,
+    *,
+    nan_policy: str = "raise",  # "raise" or "zero"
+) -> _Broadcast:
+    ...
+
+
 def pme_plus_flows(
     amounts: _AmountArray,
     index: _AmountArray,
     nav: _Amount = 0,
+    *,
+    nan_policy: str = "raise",  # "raise" or "zero"
 ) -> List[float]:
     ...
 
@@ -133,69 +413,333 @@ def pme_plus_flows_2(
     distributions: _AmountArray,
     index: _AmountArray,
     nav: _Amount = 0,
+    *,
+    nan_policy: str = "raise",  # "raise" or "zero"
 ) -> Tuple[List[float], List[float]]:
     ...
 
 
+@overload
 def pme_plus_lambda(
     amounts: _AmountArray,
     index: _AmountArray,
     nav: _Amount = 0,
+    *,
+    nan_policy: str = "raise",  # "raise" or "zero"
 ) -> float:
     ...
 
 
+@overload
+def pme_plus_lambda(
+    amounts: Union[_AmountArray, _SeriesArray],
+    index: Union[_AmountArray, _SeriesArray],
+    nav: Union[_Amount, _ScalarArray] = 0,
+    *,
+    nan_policy: str = "raise",  # "raise" or "zero"
+) -> _Broadcast:
+    ...
+
+
+@overload
 def pme_plus_lambda_2(
     contributions: _AmountArray,
     distributions: _AmountArray,
     index: _AmountArray,
     nav: _Amount = 0,
+    *,
+    nan_policy: str = "raise",  # "raise" or "zero"
 ) -> float:
     ...
 
 
+@overload
+def pme_plus_lambda_2(
+    contributions: Union[_AmountArray, _SeriesArray],
+    distributions: Union[_AmountArray, _SeriesArray],
+    index: Union[_AmountArray, _SeriesArray],
+    nav: Union[_Amount, _ScalarArray] = 0,
+    *,
+    nan_policy: str = "raise",  # "raise" or "zero"
+) -> _Broadcast:
+    ...
+
+
+@overload
 def ln_pme_nav(
     amounts: _AmountArray,
     index: _AmountArray,
+    *,
+    nan_policy: str = "raise",  # "raise" or "zero"
 ) -> float:
     ...
 
 
+@overload
+def ln_pme_nav(
+    amounts: Union[_AmountArray, _SeriesArray],
+    index: Union[_AmountArray, _SeriesArray],
+    *,
+    nan_policy: str = "raise",  # "raise" or "zero"
+) -> _Broadcast:
+    ...
+
+
+@overload
 def ln_pme_nav_2(
     contributions: _AmountArray,
     distributions: _AmountArray,
     index: _AmountArray,
+    *,
+    nan_policy: str = "raise",  # "raise" or "zero"
 ) -> float:
     ...
 
 
+@overload
+def ln_pme_nav_2(
+    contributions: Union[_AmountArray, _SeriesArray],
+    distributions: Union[_AmountArray, _SeriesArray],
+    index: Union[_AmountArray, _SeriesArray],
+    *,
+    nan_policy: str = "raise",  # "raise" or "zero"
+) -> _Broadcast:
+    ...
+
+
+@overload
 def ln_pme(
     amounts: _AmountArray,
     index: _AmountArray,
+    *,
+    nan_policy: str = "raise",  # "raise" or "zero"
 ) -> Optional[float]:
     ...
 
 
+@overload
+def ln_pme(
+    amounts: Union[_AmountArray, _SeriesArray],
+    index: Union[_AmountArray, _SeriesArray],
+    *,
+    nan_policy: str = "raise",  # "raise" or "zero"
+) -> _Broadcast:
+    ...
+
+
+@overload
 def ln_pme_2(
     contributions: _AmountArray,
     distributions: _AmountArray,
     index: _AmountArray,
+    *,
+    nan_policy: str = "raise",  # "raise" or "zero"
 ) -> Optional[float]:
     ...
 
 
+@overload
+def ln_pme_2(
+    contributions: Union[_AmountArray, _SeriesArray],
+    distributions: Union[_AmountArray, _SeriesArray],
+    index: Union[_AmountArray, _SeriesArray],
+    *,
+    nan_policy: str = "raise",  # "raise" or "zero"
+) -> _Broadcast:
+    ...
+
+
+@overload
 def direct_alpha(
     amounts: _AmountArray,
     index: _AmountArray,
     nav: _Amount = 0,
+    *,
+    nan_policy: str = "raise",  # "raise" or "zero"
 ) -> Optional[float]:
     ...
 
 
+@overload
+def direct_alpha(
+    amounts: Union[_AmountArray, _SeriesArray],
+    index: Union[_AmountArray, _SeriesArray],
+    nav: Union[_Amount, _ScalarArray] = 0,
+    *,
+    nan_policy: str = "raise",  # "raise" or "zero"
+) -> _Broadcast:
+    ...
+
+
+@overload
 def direct_alpha_2(
     contributions: _AmountArray,
     distributions: _AmountArray,
     index: _AmountArray,
     nav: _Amount = 0,
+    *,
+    nan_policy: str = "raise",  # "raise" or "zero"
 ) -> Optional[float]:
     ...
+
+
+@overload
+def direct_alpha_2(
+    contributions: Union[_AmountArray, _SeriesArray],
+    distributions: Union[_AmountArray, _SeriesArray],
+    index: Union[_AmountArray, _SeriesArray],
+    nav: Union[_Amount, _ScalarArray] = 0,
+    *,
+    nan_policy: str = "raise",  # "raise" or "zero"
+) -> _Broadcast:
+    ...
+
+
+def takahashi_alexander(
+    commitment: _Amount,
+    fund_life: float,
+    rate_of_contribution: float,
+    bow: float,
+    growth: float,
+    yield_: float,
+    periods: int,
+) -> Dict[str, List[float]]:  # contributions, distributions, nav, amounts
+    ...
+
+
+def takahashi_alexander_calibrate(
+    commitment: _Amount,
+    fund_life: float,
+    contributions: _AmountArray,
+    distributions: _AmountArray,
+    nav: _AmountArray,
+    *,
+    nan_policy: str = "raise",  # "raise" or "zero"
+) -> Dict[str, float]:
+    ...
+
+
+def waterfall(
+    dates: _DateLikeArray,
+    contributions: _AmountArray,
+    distributions: _AmountArray,
+    *,
+    hurdle: float = 0.08,
+    carry: float = 0.2,
+    catch_up: float = 1.0,
+    kind: str = "european",  # "european" or "american"
+    deals: Optional[Iterable[Hashable]] = None,
+    clawback: bool = True,
+    day_count: Optional[_DayCount] = None,
+    nan_policy: str = "raise",  # "raise" or "zero"
+    date_format: Optional[str] = None,
+    excel_serial: Union[bool, int, None] = None,  # True (1900), 1900 or 1904
+) -> Dict[str, List[float]]:
+    ...
+
+
+def management_fees(
+    commitment: _Amount,
+    rate: float,
+    start_date: _DateLike,
+    investment_period_end: _DateLike,
+    end_date: _DateLike,
+    *,
+    post_rate: Optional[float] = None,
+    step_down: float = 0.0,
+    frequency: int = 4,
+    invested: Optional[_CashFlow] = None,
+    fee_income: Optional[_CashFlow] = None,
+    offset: float = 1.0,
+    expenses: _Amount = 0,
+    day_count: Optional[_DayCount] = None,
+    nan_policy: str = "raise",  # "raise", "omit" or "zero"
+) -> Dict[str, List[Any]]:
+    ...
+
+
+def pooled_irr(
+    funds: Iterable[_CashFlow],
+    navs: Optional[_AmountArray] = None,
+    *,
+    day_count: Optional[_DayCount] = None,
+    nan_policy: str = "raise",  # "raise", "omit" or "zero"
+) -> Optional[float]:
+    ...
+
+
+def pooled_dpi(
+    funds: Iterable[_CashFlow],
+    *,
+    nan_policy: str = "raise",  # "raise", "omit" or "zero"
+) -> float:
+    ...
+
+
+def pooled_rvpi(
+    funds: Iterable[_CashFlow],
+    navs: Optional[_AmountArray] = None,
+    *,
+    nan_policy: str = "raise",  # "raise", "omit" or "zero"
+) -> float:
+    ...
+
+
+def pooled_tvpi(
+    funds: Iterable[_CashFlow],
+    navs: Optional[_AmountArray] = None,
+    *,
+    nan_policy: str = "raise",  # "raise", "omit" or "zero"
+) -> float:
+    ...
+
+
+def pooled_metrics(
+    funds: Iterable[_CashFlow],
+    navs: Optional[_AmountArray] = None,
+    *,
+    weights: str = "paid_in",  # "equal", "commitment" or "paid_in"
+    commitments: Optional[_AmountArray] = None,
+    day_count: Optional[_DayCount] = None,
+    nan_policy: str = "raise",  # "raise", "omit" or "zero"
+) -> Dict[str, Any]:
+    ...
+
+
+def percentile_rank(
+    value: float,
+    peers: _AmountArray,
+    *,
+    method: str = "inclusive",  # "inclusive", "exclusive", "weak", "strict" or "mean"
+) -> float:
+    ...
+
+
+def quartile(
+    value: float,
+    peers: _AmountArray,
+    *,
+    method: str = "inclusive",
+) -> int:
+    ...
+
+
+def quartile_breakpoints(
+    peers: _AmountArray,
+    *,
+    method: str = "inclusive",
+) -> Tuple[float, float, float]:
+    ...
+
+
+def benchmark(
+    flows: _CashFlow,
+    peers: Dict[str, _AmountArray],  # "tvpi", "dpi", "xirr" or "ks_pme"
+    *,
+    nav: _Amount = 0,
+    index: Optional[_AmountArray] = None,
+    method: str = "inclusive",
+    day_count: Optional[_DayCount] = None,
+    nan_policy: str = "raise",  # "raise", "omit" or "zero"
+) -> Dict[str, Dict[str, Any]]:
+    ...
@@ -0,0 +1,15 @@
+from typing import Any, List
+
+# numpy.ufunc objects (numpy is an optional dependency), created on first access.
+# Every input is positional, pmt_at_beginning is any number (non-zero is true).
+_ufunc = Any
+
+__all__: List[str]
+
+fv: _ufunc  # fv(rate, nper, pmt, pv, pmt_at_beginning)
+pv: _ufunc  # pv(rate, nper, pmt, fv, pmt_at_beginning)
+pmt: _ufunc  # pmt(rate, nper, pv, fv, pmt_at_beginning)
+ipmt: _ufunc  # ipmt(rate, per, nper, pv, fv, pmt_at_beginning)
+ppmt: _ufunc  # ppmt(rate, per, nper, pv, fv, pmt_at_beginning)
+nper: _ufunc  # nper(rate, pmt, pv, fv, pmt_at_beginning)
+rate: _ufunc  # rate(nper, pmt, pv, fv, pmt_at_beginning)
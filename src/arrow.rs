@@ -0,0 +1,566 @@
+//! Zero-copy reading of Arrow arrays exported through the Arrow PyCapsule
+//! interface (`__arrow_c_array__`, `__arrow_c_stream__`), which is implemented
+//! by pyarrow, polars and other dataframe libraries.
+//!
+//! https://arrow.apache.org/docs/format/CDataInterface.html
+//! https://arrow.apache.org/docs/format/CDataInterface/PyCapsuleInterface.html
+
+use std::{
+    ffi::{c_char, c_int, c_void, CStr},
+    mem, ptr,
+};
+
+use pyo3::{
+    exceptions::PyValueError,
+    ffi::c_str,
+    intern,
+    prelude::*,
+    types::{PyCapsule, PyTuple},
+};
+
+use crate::core::DateLike;
+
+#[repr(C)]
+struct ArrowSchema {
+    format: *const c_char,
+    name: *const c_char,
+    metadata: *const c_char,
+    flags: i64,
+    n_children: i64,
+    children: *mut *mut ArrowSchema,
+    dictionary: *mut ArrowSchema,
+    release: Option<unsafe extern "C" fn(*mut ArrowSchema)>,
+    private_data: *mut c_void,
+}
+
+#[repr(C)]
+struct ArrowArray {
+    length: i64,
+    null_count: i64,
+    offset: i64,
+    n_buffers: i64,
+    n_children: i64,
+    buffers: *mut *const c_void,
+    children: *mut *mut ArrowArray,
+    dictionary: *mut ArrowArray,
+    release: Option<unsafe extern "C" fn(*mut ArrowArray)>,
+    private_data: *mut c_void,
+}
+
+#[repr(C)]
+struct ArrowArrayStream {
+    get_schema: Option<unsafe extern "C" fn(*mut ArrowArrayStream, *mut ArrowSchema) -> c_int>,
+    get_next: Option<unsafe extern "C" fn(*mut ArrowArrayStream, *mut ArrowArray) -> c_int>,
+    get_last_error: Option<unsafe extern "C" fn(*mut ArrowArrayStream) -> *const c_char>,
+    release: Option<unsafe extern "C" fn(*mut ArrowArrayStream)>,
+    private_data: *mut c_void,
+}
+
+impl Drop for ArrowSchema {
+    fn drop(&mut self) {
+        if let Some(release) = self.release {
+            unsafe { release(self) }
+        }
+    }
+}
+
+impl Drop for ArrowArray {
+    fn drop(&mut self) {
+        if let Some(release) = self.release {
+            unsafe { release(self) }
+        }
+    }
+}
+
+impl Drop for ArrowArrayStream {
+    fn drop(&mut self) {
+        if let Some(release) = self.release {
+            unsafe { release(self) }
+        }
+    }
+}
+
+impl ArrowSchema {
+    fn data_type(&self) -> Option<DataType> {
+        // dictionary encoded arrays store indices in place of values
+        if self.format.is_null() || !self.dictionary.is_null() {
+            return None;
+        }
+        let format = unsafe { CStr::from_ptr(self.format) };
+        DataType::parse(format.to_str().ok()?)
+    }
+
+    fn child(&self, index: usize) -> Option<&ArrowSchema> {
+        if index >= self.n_children as usize {
+            return None;
+        }
+        unsafe { (*self.children.add(index)).as_ref() }
+    }
+}
+
+impl ArrowArray {
+    fn child(&self, index: usize) -> Option<&ArrowArray> {
+        if index >= self.n_children as usize {
+            return None;
+        }
+        unsafe { (*self.children.add(index)).as_ref() }
+    }
+}
+
+impl ArrowArrayStream {
+    fn get_schema(&mut self) -> PyResult<ArrowSchema> {
+        let get_schema = self.get_schema.ok_or_else(|| released("arrow_array_stream"))?;
+        // all the C structures are valid when zeroed: null pointers and no release callback
+        let mut schema: ArrowSchema = unsafe { mem::zeroed() };
+        match unsafe { get_schema(self, &mut schema) } {
+            0 => Ok(schema),
+            code => Err(self.error(code)),
+        }
+    }
+
+    fn get_next(&mut self) -> PyResult<Option<ArrowArray>> {
+        let get_next = self.get_next.ok_or_else(|| released("arrow_array_stream"))?;
+        let mut array: ArrowArray = unsafe { mem::zeroed() };
+        match unsafe { get_next(self, &mut array) } {
+            // a released array marks the end of the stream
+            0 => Ok(array.release.is_some().then_some(array)),
+            code => Err(self.error(code)),
+        }
+    }
+
+    fn error(&mut self, code: c_int) -> PyErr {
+        let message = self
+            .get_last_error
+            .map(|get_last_error| unsafe { get_last_error(self) })
+            .filter(|message| !message.is_null())
+            .map(|message| unsafe { CStr::from_ptr(message) }.to_string_lossy().into_owned())
+            .unwrap_or_else(|| format!("error code {code}"));
+
+        PyValueError::new_err(format!("Could not read Arrow stream: {message}"))
+    }
+}
+
+fn released(name: &str) -> PyErr {
+    PyValueError::new_err(format!("{name} PyCapsule has already been consumed"))
+}
+
+/// Moves the C structure out of the capsule, so the capsule destructor does not release it.
+///
+/// Safety: `T` must be one of the Arrow C structures.
+unsafe fn take<T>(capsule: &Bound<PyCapsule>, name: &CStr) -> PyResult<T> {
+    if capsule.name()? != Some(name) {
+        return Err(PyValueError::new_err(format!(
+            "Expected {} PyCapsule",
+            name.to_string_lossy()
+        )));
+    }
+    Ok(ptr::replace(capsule.pointer() as *mut T, mem::zeroed()))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum DataType {
+    Int8,
+    Int16,
+    Int32,
+    Int64,
+    UInt8,
+    UInt16,
+    UInt32,
+    UInt64,
+    Float32,
+    Float64,
+    Date32,
+    Date64,
+    /// Timestamp without a time zone, the value is the number of nanoseconds per unit.
+    Timestamp(i128),
+    Struct,
+}
+
+impl DataType {
+    fn parse(format: &str) -> Option<Self> {
+        use DataType::*;
+
+        Some(match format {
+            "c" => Int8,
+            "s" => Int16,
+            "i" => Int32,
+            "l" => Int64,
+            "C" => UInt8,
+            "S" => UInt16,
+            "I" => UInt32,
+            "L" => UInt64,
+            "f" => Float32,
+            "g" => Float64,
+            "tdD" => Date32,
+            "tdm" => Date64,
+            // timestamps with a time zone are stored in UTC, the local date is
+            // left to the slow path
+            "tss:" => Timestamp(1_000_000_000),
+            "tsm:" => Timestamp(1_000_000),
+            "tsu:" => Timestamp(1_000),
+            "tsn:" => Timestamp(1),
+            "+s" => Struct,
+            _ => return None,
+        })
+    }
+}
+
+/// A primitive array or a child of a struct array.
+struct Column<'a> {
+    array: &'a ArrowArray,
+    data_type: DataType,
+    offset: usize,
+    len: usize,
+}
+
+impl<'a> Column<'a> {
+    fn new(
+        array: &'a ArrowArray,
+        data_type: DataType,
+        parent: Option<&ArrowArray>,
+    ) -> Option<Self> {
+        if data_type == DataType::Struct || array.n_buffers < 2 || array.buffers.is_null() {
+            return None;
+        }
+
+        // the offset of a struct array applies to its children too
+        let (offset, len) = match parent {
+            Some(parent) => (array.offset + parent.offset, parent.length),
+            None => (array.offset, array.length),
+        };
+
+        Some(Self {
+            array,
+            data_type,
+            offset: offset as usize,
+            len: len as usize,
+        })
+    }
+
+    fn is_valid(&self, index: usize) -> bool {
+        let validity = unsafe { *self.array.buffers } as *const u8;
+        if self.array.null_count == 0 || validity.is_null() {
+            return true;
+        }
+        let index = self.offset + index;
+        unsafe { *validity.add(index / 8) & (1 << (index % 8)) != 0 }
+    }
+
+    fn read<T: Copy, U: Copy>(&self, out: &mut Vec<U>, missing: U, f: impl Fn(T) -> U) {
+        let values = unsafe { *self.array.buffers.add(1) } as *const T;
+        out.extend((0..self.len).map(|i| {
+            if self.is_valid(i) {
+                // buffers are not guaranteed to be aligned
+                f(unsafe { values.add(self.offset + i).read_unaligned() })
+            } else {
+                missing
+            }
+        }));
+    }
+
+    fn read_dates(&self, out: &mut Vec<Option<DateLike>>) -> bool {
+        match self.data_type {
+            DataType::Date32 => self.read(out, None, |days: i32| Some((days as i64).into())),
+            DataType::Date64 => {
+                self.read(out, None, |ms: i64| Some(ms.div_euclid(86_400_000).into()))
+            }
+            DataType::Timestamp(nanos) => {
+                self.read(out, None, |t: i64| Some(DateLike::from_unix_nanos(t as i128 * nanos)))
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    fn read_amounts(&self, out: &mut Vec<f64>) -> bool {
+        match self.data_type {
+            DataType::Float64 => self.read(out, f64::NAN, |x: f64| x),
+            DataType::Float32 => self.read(out, f64::NAN, |x: f32| x as f64),
+            DataType::Int8 => self.read(out, f64::NAN, |x: i8| x as f64),
+            DataType::Int16 => self.read(out, f64::NAN, |x: i16| x as f64),
+            DataType::Int32 => self.read(out, f64::NAN, |x: i32| x as f64),
+            DataType::Int64 => self.read(out, f64::NAN, |x: i64| x as f64),
+            DataType::UInt8 => self.read(out, f64::NAN, |x: u8| x as f64),
+            DataType::UInt16 => self.read(out, f64::NAN, |x: u16| x as f64),
+            DataType::UInt32 => self.read(out, f64::NAN, |x: u32| x as f64),
+            DataType::UInt64 => self.read(out, f64::NAN, |x: u64| x as f64),
+            _ => return false,
+        }
+        true
+    }
+}
+
+/// Arrays exported by an object, all of them share the same schema.
+struct Exported {
+    schema: ArrowSchema,
+    chunks: Vec<ArrowArray>,
+}
+
+impl Exported {
+    fn from_object(obj: &Bound<PyAny>) -> PyResult<Option<Self>> {
+        let py = obj.py();
+
+        if obj.hasattr(intern!(py, "__arrow_c_array__"))? {
+            let capsules = obj.call_method0(intern!(py, "__arrow_c_array__"))?;
+            let capsules = capsules.downcast::<PyTuple>()?;
+            let schema = capsules.get_item(0)?;
+            let array = capsules.get_item(1)?;
+            let (schema, array) = unsafe {
+                (
+                    take::<ArrowSchema>(schema.downcast()?, c_str!("arrow_schema"))?,
+                    take::<ArrowArray>(array.downcast()?, c_str!("arrow_array"))?,
+                )
+            };
+            if schema.release.is_none() || array.release.is_none() {
+                return Err(released("arrow_array"));
+            }
+
+            return Ok(Some(Self {
+                schema,
+                chunks: vec![array],
+            }));
+        }
+
+        if obj.hasattr(intern!(py, "__arrow_c_stream__"))? {
+            let capsule = obj.call_method0(intern!(py, "__arrow_c_stream__"))?;
+            let mut stream = unsafe {
+                take::<ArrowArrayStream>(capsule.downcast()?, c_str!("arrow_array_stream"))?
+            };
+            let schema = stream.get_schema()?;
+            let mut chunks = Vec::new();
+            while let Some(chunk) = stream.get_next()? {
+                chunks.push(chunk);
+            }
+
+            return Ok(Some(Self {
+                schema,
+                chunks,
+            }));
+        }
+
+        Ok(None)
+    }
+
+    fn len(&self) -> usize {
+        self.chunks.iter().map(|c| c.length as usize).sum()
+    }
+
+    /// Reads the whole array (`field` is None) or a field of a struct array.
+    /// Returns None if the data type is not supported.
+    fn read_column<T>(
+        &self,
+        field: Option<usize>,
+        read: impl Fn(&Column, &mut Vec<T>) -> bool,
+    ) -> Option<Vec<T>> {
+        let schema = match field {
+            Some(index) => self.schema.child(index)?,
+            None => &self.schema,
+        };
+        let data_type = schema.data_type()?;
+        let mut result = Vec::with_capacity(self.len());
+
+        for chunk in &self.chunks {
+            let column = match field {
+                // null rows of a struct array are not supported
+                Some(index) if chunk.null_count == 0 => {
+                    Column::new(chunk.child(index)?, data_type, Some(chunk))?
+                }
+                Some(_) => return None,
+                None => Column::new(chunk, data_type, None)?,
+            };
+            if !read(&column, &mut result) {
+                return None;
+            }
+        }
+
+        Some(result)
+    }
+}
+
+/// Reads a date array, None if the object does not implement the Arrow PyCapsule
+/// interface or its data type is not supported.
+pub fn extract_dates(obj: &Bound<PyAny>) -> PyResult<Option<Vec<Option<DateLike>>>> {
+    Ok(Exported::from_object(obj)?.and_then(|e| e.read_column(None, |c, out| c.read_dates(out))))
+}
+
+/// Reads a numeric array, missing values are NaN.
+pub fn extract_amounts(obj: &Bound<PyAny>) -> PyResult<Option<Vec<f64>>> {
+    Ok(Exported::from_object(obj)?.and_then(|e| e.read_column(None, |c, out| c.read_amounts(out))))
+}
+
+type Payments = (Vec<Option<DateLike>>, Vec<f64>);
+
+/// Reads the first two columns of a table (a struct array) as dates and amounts.
+pub fn extract_table(obj: &Bound<PyAny>) -> PyResult<Option<Payments>> {
+    let Some(exported) = Exported::from_object(obj)? else {
+        return Ok(None);
+    };
+    if exported.schema.data_type() != Some(DataType::Struct) {
+        return Ok(None);
+    }
+
+    Ok(exported
+        .read_column(Some(0), |c, out| c.read_dates(out))
+        .zip(exported.read_column(Some(1), |c, out| c.read_amounts(out))))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::CString;
+
+    use pyo3::types::PyDict;
+    use rstest::rstest;
+    use time::macros::date;
+
+    use super::*;
+
+    // the producer side: exported structures point to memory owned by the test
+    unsafe extern "C" fn release_schema(schema: *mut ArrowSchema) {
+        (*schema).release = None;
+    }
+
+    unsafe extern "C" fn release_array(array: *mut ArrowArray) {
+        (*array).release = None;
+    }
+
+    struct Send<T>(T);
+    unsafe impl<T> std::marker::Send for Send<T> {}
+
+    fn schema(format: &CStr, children: &mut [*mut ArrowSchema]) -> ArrowSchema {
+        ArrowSchema {
+            format: format.as_ptr(),
+            n_children: children.len() as i64,
+            children: children.as_mut_ptr(),
+            release: Some(release_schema),
+            ..unsafe { mem::zeroed() }
+        }
+    }
+
+    fn array(
+        length: i64,
+        offset: i64,
+        buffers: &mut [*const c_void],
+        children: &mut [*mut ArrowArray],
+    ) -> ArrowArray {
+        // the null count is unknown (-1) if there is a validity bitmap
+        let has_validity = buffers.first().is_some_and(|b| !b.is_null());
+        ArrowArray {
+            length,
+            null_count: if has_validity {
+                -1
+            } else {
+                0
+            },
+            offset,
+            n_buffers: buffers.len() as i64,
+            n_children: children.len() as i64,
+            buffers: buffers.as_mut_ptr(),
+            children: children.as_mut_ptr(),
+            release: Some(release_array),
+            ..unsafe { mem::zeroed() }
+        }
+    }
+
+    fn exporter<'py>(py: Python<'py>, schema: ArrowSchema, array: ArrowArray) -> Bound<'py, PyAny> {
+        let capsules = (
+            PyCapsule::new(py, Send(schema), Some(CString::from(c_str!("arrow_schema")))).unwrap(),
+            PyCapsule::new(py, Send(array), Some(CString::from(c_str!("arrow_array")))).unwrap(),
+        );
+        let globals = PyDict::new(py);
+        globals.set_item("capsules", capsules).unwrap();
+        py.eval(
+            c_str!("type('Array', (), {'__arrow_c_array__': lambda self, requested_schema=None: capsules})()"),
+            Some(&globals),
+            None,
+        )
+        .unwrap()
+    }
+
+    #[rstest]
+    fn test_extract_amounts() {
+        Python::with_gil(|py| {
+            let values = [0.0, -100.0, 0.0, 110.0];
+            let validity = [0b1011u8];
+            let mut buffers = [validity.as_ptr() as *const c_void, values.as_ptr() as _];
+            let array = array(3, 1, &mut buffers, &mut []);
+            let obj = exporter(py, schema(c_str!("g"), &mut []), array);
+
+            let amounts = extract_amounts(&obj).unwrap().unwrap();
+            assert_eq!(amounts[0], -100.0);
+            assert!(amounts[1].is_nan());
+            assert_eq!(amounts[2], 110.0);
+        })
+    }
+
+    #[rstest]
+    fn test_extract_dates() {
+        Python::with_gil(|py| {
+            // 2020-01-01, 2021-01-01 in days and microseconds since the epoch
+            let days = [18262i32, 18628];
+            let mut buffers = [ptr::null(), days.as_ptr() as _];
+            let obj =
+                exporter(py, schema(c_str!("tdD"), &mut []), array(2, 0, &mut buffers, &mut []));
+            let expected =
+                vec![Some(date!(2020 - 01 - 01).into()), Some(date!(2021 - 01 - 01).into())];
+            assert_eq!(extract_dates(&obj).unwrap(), Some(expected.clone()));
+
+            // the time of day is kept
+            let micros = [18262i64 * 86_400_000_000 + 43_200_000_000, 18628 * 86_400_000_000];
+            let mut buffers = [ptr::null(), micros.as_ptr() as _];
+            let obj =
+                exporter(py, schema(c_str!("tsu:"), &mut []), array(2, 0, &mut buffers, &mut []));
+            let dates = extract_dates(&obj).unwrap().unwrap();
+            assert_eq!(dates[0].unwrap().day_fraction(), 0.5);
+            assert_eq!(
+                dates.into_iter().map(|d| d.map(DateLike::to_date)).collect::<Vec<_>>(),
+                expected
+            );
+
+            // time zone aware timestamps are not supported
+            let mut buffers = [ptr::null(), micros.as_ptr() as _];
+            let obj = exporter(
+                py,
+                schema(c_str!("tsu:UTC"), &mut []),
+                array(2, 0, &mut buffers, &mut []),
+            );
+            assert_eq!(extract_dates(&obj).unwrap(), None);
+        })
+    }
+
+    #[rstest]
+    fn test_extract_table() {
+        Python::with_gil(|py| {
+            let days = [0i32, 18262, 18628];
+            let values = [0i64, -100, 110];
+            let mut date_buffers = [ptr::null(), days.as_ptr() as _];
+            let mut amount_buffers = [ptr::null(), values.as_ptr() as _];
+            let mut date_array = array(3, 0, &mut date_buffers, &mut []);
+            let mut amount_array = array(3, 0, &mut amount_buffers, &mut []);
+            let mut date_schema = schema(c_str!("tdD"), &mut []);
+            let mut amount_schema = schema(c_str!("l"), &mut []);
+            let mut schema_children = [&mut date_schema as *mut _, &mut amount_schema as *mut _];
+            let mut array_children = [&mut date_array as *mut _, &mut amount_array as *mut _];
+            let mut buffers = [ptr::null()];
+            let obj = exporter(
+                py,
+                schema(c_str!("+s"), &mut schema_children),
+                array(2, 1, &mut buffers, &mut array_children),
+            );
+
+            let (dates, amounts) = extract_table(&obj).unwrap().unwrap();
+            assert_eq!(
+                dates,
+                vec![Some(date!(2020 - 01 - 01).into()), Some(date!(2021 - 01 - 01).into())]
+            );
+            assert_eq!(amounts, vec![-100.0, 110.0]);
+        })
+    }
+
+    #[rstest]
+    fn test_not_exported() {
+        Python::with_gil(|py| {
+            let obj = PyTuple::new(py, [1.0, 2.0]).unwrap();
+            assert_eq!(extract_amounts(obj.as_any()).unwrap(), None);
+        })
+    }
+}
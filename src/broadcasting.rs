@@ -1,75 +1,21 @@
-use std::{error::Error, fmt};
-
 use ndarray::{ArrayD, ArrayViewD, Axis, CowArray, IxDyn};
-use numpy::{npyffi, Element, PyArrayDescrMethods, PyArrayDyn, PyArrayMethods, PY_ARRAY_API};
+use numpy::{
+    npyffi, Element, PyArrayDescrMethods, PyArrayDyn, PyArrayMethods, PyUntypedArray,
+    PyUntypedArrayMethods, PY_ARRAY_API,
+};
 use pyo3::{
     exceptions::{PyTypeError, PyValueError},
+    intern,
     prelude::*,
-    types::{PyIterator, PyList, PySequence, PyTuple},
+    types::{PyDict, PyIterator, PyList, PySequence, PyTuple},
     IntoPyObjectExt,
 };
 
-use crate::conversions::float_or_none;
-
-/// An error returned when the payments do not contain both negative and positive payments.
-#[derive(Debug)]
-pub struct BroadcastingError(String);
-
-impl BroadcastingError {
-    pub fn new(shapes: &[&[usize]]) -> Self {
-        Self(format!("{:?}", shapes))
-    }
-}
-
-impl fmt::Display for BroadcastingError {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        self.0.fmt(f)
-    }
-}
-
-impl Error for BroadcastingError {}
-
-pub fn broadcast_shapes(shapes: &[&[usize]]) -> Option<Vec<usize>> {
-    /* Discover the broadcast number of dimensions */
-    let ndim = shapes.iter().map(|s| s.len()).max()?;
-    let mut result = vec![0; ndim];
-
-    /* Discover the broadcast shape in each dimension */
-    for (i, cur) in result.iter_mut().enumerate() {
-        *cur = 1;
-        for s in shapes.iter() {
-            /* This prepends 1 to shapes not already equal to ndim */
-            if i + s.len() >= ndim {
-                let k = i + s.len() - ndim;
-                let tmp = s[k];
-                if tmp == 1 {
-                    continue;
-                }
-                if cur == &1 {
-                    *cur = tmp;
-                } else if cur != &tmp {
-                    return None;
-                }
-            }
-        }
-    }
-
-    Some(result)
-}
-
-#[macro_export]
-macro_rules! broadcast_together {
-    ($($a:expr),*) => {
-        {
-            let _a = &[$($a.shape(),)*];
-
-            match $crate::broadcasting::broadcast_shapes(_a) {
-                Some(shape) => Ok(( $($a.broadcast(shape.clone()).unwrap(),)*)),
-                None => Err(BroadcastingError::new(_a))
-            }
-        }
-    };
-}
+use crate::{
+    conversions::{extract_amount_series, float_or_none, is_pandas, NanPolicy},
+    core::InvalidPaymentsError,
+    rows::par_map,
+};
 
 pub fn pyiter_to_arrayd<'py, T>(pyiter: Bound<'py, PyIterator>) -> PyResult<ArrayD<T>>
 where
@@ -109,10 +55,15 @@ fn flatten_pyiter<'p, T>(
 where
     T: FromPyObject<'p>,
 {
-    let mut max_i = 0;
-    for (i, item) in pyiter.enumerate() {
+    // the outer dimension goes first, before the nested ones are known
+    if shape.len() == depth {
+        shape.push(0);
+    }
+
+    let mut len = 0;
+    for item in pyiter {
         let item = item?;
-        max_i = i;
+        len += 1;
         match item.extract::<T>() {
             Ok(val) => flat_list.push(val),
             Err(_) => {
@@ -122,12 +73,7 @@ where
         }
     }
 
-    max_i += 1;
-    if let Some(current) = shape.get(depth) {
-        shape[depth] = (*current).max(max_i);
-    } else {
-        shape.push(max_i);
-    }
+    shape[depth] = shape[depth].max(len);
 
     Ok(())
 }
@@ -222,6 +168,192 @@ impl<'p> FromPyObject<'p> for Arg<'p, bool> {
     }
 }
 
+/// A pandas Series or DataFrame, or an xarray DataArray.
+fn is_labeled_container(ob: &Bound<PyAny>) -> PyResult<bool> {
+    let name = ob.get_type().name()?;
+    Ok((is_pandas(ob)? && (name.eq("Series") || name.eq("DataFrame")))
+        || (ob.get_type().module()?.to_cow()?.starts_with("xarray") && name.eq("DataArray")))
+}
+
+/// A `numpy.ma.MaskedArray`.
+fn is_masked_array(ob: &Bound<PyAny>) -> PyResult<bool> {
+    let py = ob.py();
+    // checked first, so that scalars and lists do not import numpy
+    if !ob.hasattr(intern!(py, "mask"))? {
+        return Ok(false);
+    }
+    ob.is_instance(&py.import(intern!(py, "numpy.ma"))?.getattr(intern!(py, "MaskedArray"))?)
+}
+
+/// The mask of a masked array, `None` if nothing is masked.
+fn extract_mask(ob: &Bound<PyAny>) -> PyResult<Option<ArrayD<bool>>> {
+    let py = ob.py();
+    let ma = py.import(intern!(py, "numpy.ma"))?;
+    if ob.getattr(intern!(py, "mask"))?.is(&ma.getattr(intern!(py, "nomask"))?) {
+        return Ok(None);
+    }
+    let mask = ma.call_method1(intern!(py, "getmaskarray"), (ob,))?;
+    Ok(Some(mask.downcast_into::<PyArrayDyn<bool>>()?.to_owned_array()))
+}
+
+/// The precision of a floating point numpy array.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Precision {
+    Single,
+    Double,
+}
+
+impl Precision {
+    fn of(array: &Bound<PyAny>) -> PyResult<Option<Self>> {
+        let dtype = array.downcast::<PyUntypedArray>()?.dtype();
+        Ok(match dtype.kind() {
+            b'f' if dtype.itemsize() <= 4 => Some(Precision::Single),
+            b'f' => Some(Precision::Double),
+            _ => None,
+        })
+    }
+}
+
+/// What a vectorized result keeps from an argument: the pandas or xarray container,
+/// the mask of a masked array and the precision of a float array.
+#[derive(Default)]
+pub struct Origin<'p> {
+    container: Option<Bound<'p, PyAny>>,
+    mask: Option<ArrayD<bool>>,
+    precision: Option<Precision>,
+}
+
+/// An argument of a vectorized function that remembers where it was extracted from,
+/// so that the result gets the same index, columns or coordinates, mask and dtype.
+pub struct Labeled<'p, T> {
+    pub arg: Arg<'p, T>,
+    origin: Origin<'p>,
+}
+
+impl<'p, T> From<Arg<'p, T>> for Labeled<'p, T> {
+    fn from(arg: Arg<'p, T>) -> Self {
+        Self {
+            arg,
+            origin: Origin::default(),
+        }
+    }
+}
+
+impl<'p, T> FromPyObject<'p> for Labeled<'p, T>
+where
+    Arg<'p, T>: FromPyObject<'p>,
+{
+    fn extract_bound(ob: &Bound<'p, PyAny>) -> PyResult<Self> {
+        let py = ob.py();
+        let mut origin = Origin::default();
+
+        let values = if is_labeled_container(ob)? {
+            origin.container = Some(ob.clone());
+            ob.getattr(intern!(py, "values"))?
+        } else if is_masked_array(ob)? {
+            origin.mask = extract_mask(ob)?;
+            ob.getattr(intern!(py, "data"))?
+        } else {
+            ob.clone()
+        };
+
+        let arg = values.extract::<Arg<T>>()?;
+        if matches!(arg, Arg::NumpyArray(_)) {
+            origin.precision = Precision::of(&values)?;
+        }
+
+        Ok(Self {
+            arg,
+            origin,
+        })
+    }
+}
+
+impl<'p, T> Labeled<'p, T> {
+    pub fn origin(&self) -> &Origin<'p> {
+        &self.origin
+    }
+}
+
+/// How a vectorized result is returned, from the origins of the arguments:
+/// - with `keep_dtype`, in float32 if the float arrays are float32
+/// - masked where any argument is masked
+/// - with the labels of the first pandas or xarray argument of the same shape
+pub struct Output<'p> {
+    container: Option<Bound<'p, PyAny>>,
+    masks: Vec<ArrayD<bool>>,
+    single: bool,
+}
+
+impl<'p> Output<'p> {
+    pub fn of<'a>(origins: impl IntoIterator<Item = &'a Origin<'p>>, keep_dtype: bool) -> Self
+    where
+        'p: 'a,
+    {
+        let mut container = None;
+        let mut masks = Vec::new();
+        let mut precision = None;
+        for origin in origins {
+            container = container.or_else(|| origin.container.clone());
+            masks.extend(origin.mask.clone());
+            precision = precision.max(origin.precision);
+        }
+
+        Self {
+            container,
+            masks,
+            single: keep_dtype && precision == Some(Precision::Single),
+        }
+    }
+
+    pub fn wrap(&self, py: Python<'p>, result: Arg<'p, f64>) -> PyResult<Bound<'p, PyAny>> {
+        let Arg::NumpyArray(array) = result else {
+            return result.into_pyobject(py);
+        };
+
+        let shape = array.shape().to_vec();
+        let mut result = if self.single {
+            array.cast::<f32>(false)?.into_any()
+        } else {
+            array.into_any()
+        };
+
+        if !self.masks.is_empty() {
+            let mut mask = ArrayD::from_elem(IxDyn(&shape), false);
+            for m in &self.masks {
+                // the result has the broadcast shape of all the arguments
+                mask.zip_mut_with(m, |a, &b| *a |= b);
+            }
+            let ma = py.import(intern!(py, "numpy.ma"))?;
+            result = ma
+                .getattr(intern!(py, "MaskedArray"))?
+                .call1((result, numpy::PyArray::from_owned_array(py, mask)))?;
+        }
+
+        let Some(container) = &self.container else {
+            return Ok(result);
+        };
+        if container.getattr(intern!(py, "shape"))?.extract::<Vec<usize>>()? != shape {
+            return Ok(result);
+        }
+
+        let kwargs = PyDict::new(py);
+        if is_pandas(container)? {
+            kwargs.set_item("index", container.getattr(intern!(py, "index"))?)?;
+            if container.get_type().name()?.eq("DataFrame") {
+                kwargs.set_item("columns", container.getattr(intern!(py, "columns"))?)?;
+            } else {
+                kwargs.set_item("name", container.getattr(intern!(py, "name"))?)?;
+            }
+        } else {
+            kwargs.set_item("coords", container.getattr(intern!(py, "coords"))?)?;
+            kwargs.set_item("dims", container.getattr(intern!(py, "dims"))?)?;
+            kwargs.set_item("name", container.getattr(intern!(py, "name"))?)?;
+        }
+        container.get_type().call((result,), Some(&kwargs))
+    }
+}
+
 impl<'py> IntoPyObject<'py> for Arg<'py, f64> {
     type Target = PyAny;
     type Output = Bound<'py, Self::Target>;
@@ -268,6 +400,177 @@ impl<'p, T> From<Bound<'p, PyArrayDyn<T>>> for Arg<'p, T> {
     }
 }
 
+/// A series argument (e.g. amounts, index) of the private equity functions:
+/// a single series or an array of series along the last axis.
+pub enum Series<'p> {
+    Single(Vec<f64>),
+    Nested(Arg<'p, f64>),
+}
+
+impl Series<'_> {
+    fn is_numpy(&self) -> bool {
+        matches!(self, Series::Nested(Arg::NumpyArray(_)))
+    }
+
+    /// [`NanPolicy::apply`] to every series along the last axis.
+    pub fn apply_nan_policy(self, nan_policy: NanPolicy) -> PyResult<Self> {
+        let arg = match self {
+            Series::Single(values) => return Ok(Series::Single(nan_policy.apply(values)?)),
+            Series::Nested(arg) => arg,
+        };
+
+        let numpy_py = match &arg {
+            Arg::NumpyArray(a) => Some(a.py()),
+            _ => None,
+        };
+        let mut array = arg.into_arrayd().into_owned();
+        if array.ndim() > 0 {
+            let axis = Axis(array.ndim() - 1);
+            for mut lane in array.lanes_mut(axis) {
+                let values = nan_policy.apply(lane.to_vec())?;
+                lane.iter_mut().zip(values).for_each(|(a, b)| *a = b);
+            }
+        }
+
+        Ok(Series::Nested(match numpy_py {
+            Some(py) => Arg::from(numpy::PyArray::from_owned_array(py, array)),
+            None => Arg::from(array),
+        }))
+    }
+}
+
+impl<'p> FromPyObject<'p> for Series<'p> {
+    fn extract_bound(ob: &Bound<'p, PyAny>) -> PyResult<Self> {
+        let py = ob.py();
+        let is_nested = if ob.get_type().name()?.eq("ndarray") {
+            ob.getattr(intern!(py, "ndim"))?.extract::<usize>()? > 1
+        } else if ob.downcast::<PyList>().is_ok() || ob.downcast::<PyTuple>().is_ok() {
+            ob.len()? > 0 && {
+                let first = ob.get_item(0)?;
+                first.downcast::<PyList>().is_ok()
+                    || first.downcast::<PyTuple>().is_ok()
+                    || first.get_type().name()?.eq("ndarray")
+            }
+        } else {
+            false
+        };
+
+        if is_nested {
+            Ok(Series::Nested(ob.extract()?))
+        } else {
+            Ok(Series::Single(extract_amount_series(ob)?))
+        }
+    }
+}
+
+fn broadcast_shapes<'a>(shapes: impl IntoIterator<Item = &'a [usize]>) -> PyResult<Vec<usize>> {
+    let mut result: Vec<usize> = Vec::new();
+    for shape in shapes {
+        if shape.len() > result.len() {
+            let mut padded = vec![1; shape.len() - result.len()];
+            padded.extend(&result);
+            result = padded;
+        }
+        let offset = result.len() - shape.len();
+        for (r, &s) in result[offset..].iter_mut().zip(shape) {
+            match (*r, s) {
+                (r, s) if r == s || s == 1 => {}
+                (1, s) => *r = s,
+                _ => {
+                    return Err(PyValueError::new_err(format!(
+                        "shape mismatch: objects cannot be broadcast to a single shape: {shape:?}"
+                    )))
+                }
+            }
+        }
+    }
+    Ok(result)
+}
+
+/// Evaluates a function of `S` series and `K` scalars, where series lie along the last axis
+/// and the leading dimensions of series and scalars broadcast against each other, e.g.
+/// a matrix of index paths with a vector of NAV scenarios. Returns a scalar for a single
+/// series and scalars, otherwise an array of the broadcast leading shape.
+pub fn broadcast_series<'py, const S: usize, const K: usize, F>(
+    py: Python<'py>,
+    series: [Series<'py>; S],
+    scalars: [Arg<'py, f64>; K],
+    f: F,
+) -> PyResult<Arg<'py, f64>>
+where
+    F: Fn([&[f64]; S], [f64; K]) -> Result<f64, InvalidPaymentsError> + Sync,
+{
+    let is_single = series.iter().all(|s| matches!(s, Series::Single(_)))
+        && scalars.iter().all(|s| matches!(s, Arg::Scalar(_)));
+
+    if is_single {
+        let series = series.map(|s| match s {
+            Series::Single(values) => values,
+            Series::Nested(_) => unreachable!(),
+        });
+        let scalars = scalars.map(|s| match s {
+            Arg::Scalar(value) => value,
+            _ => unreachable!(),
+        });
+        let result = py.allow_threads(|| f(series.each_ref().map(Vec::as_slice), scalars))?;
+        return Ok(Arg::Scalar(result));
+    }
+
+    let has_numpy_array = series.iter().any(Series::is_numpy)
+        || scalars.iter().any(|s| matches!(s, Arg::NumpyArray(_)));
+
+    let series = series.map(|s| match s {
+        Series::Single(values) => {
+            ArrayD::from_shape_vec(IxDyn(&[values.len()]), values).expect("a vector is a 1D array")
+        }
+        Series::Nested(arg) => arg.into_arrayd().into_owned(),
+    });
+    let scalars = scalars.map(|s| s.into_arrayd());
+
+    if let Some(s) = series.iter().find(|s| s.ndim() == 0) {
+        return Err(PyValueError::new_err(format!("expected a series, got {s}")));
+    }
+
+    let shape = broadcast_shapes(
+        series.iter().map(|s| &s.shape()[..s.ndim() - 1]).chain(scalars.iter().map(|s| s.shape())),
+    )?;
+
+    let series_lanes: Vec<Vec<Vec<f64>>> = series
+        .iter()
+        .map(|s| {
+            let mut full = shape.clone();
+            full.push(s.shape()[s.ndim() - 1]);
+            let view = s.broadcast(IxDyn(&full)).expect("leading dimensions are broadcast");
+            view.lanes(Axis(full.len() - 1)).into_iter().map(|lane| lane.to_vec()).collect()
+        })
+        .collect();
+    let scalar_values: Vec<Vec<f64>> = scalars
+        .iter()
+        .map(|s| {
+            s.broadcast(IxDyn(&shape)).expect("shapes are broadcast").iter().copied().collect()
+        })
+        .collect();
+
+    let n = shape.iter().product();
+    let results = py.allow_threads(|| {
+        par_map((0..n).collect(), |i| {
+            let series = std::array::from_fn(|k| series_lanes[k][i].as_slice());
+            let scalars = std::array::from_fn(|k| scalar_values[k][i]);
+            f(series, scalars)
+        })
+    });
+
+    let values = results.into_iter().collect::<Result<Vec<f64>, _>>()?;
+    let result = ArrayD::from_shape_vec(IxDyn(&shape), values)
+        .map_err(|e| PyValueError::new_err(e.to_string()))?;
+
+    if has_numpy_array {
+        Ok(Arg::from(numpy::PyArray::from_owned_array(py, result)))
+    } else {
+        Ok(Arg::from(result))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use pyo3::ffi::c_str;
@@ -275,16 +578,6 @@ mod tests {
 
     use super::*;
 
-    #[rstest]
-    fn test_broadcast_shapes() {
-        assert_eq!(broadcast_shapes(&[&[3_usize, 2], &[2, 1]]), None);
-        assert_eq!(broadcast_shapes(&[&[1_usize, 2], &[3, 1], &[3, 2]]), Some(vec![3_usize, 2]));
-        assert_eq!(
-            broadcast_shapes(&[&[6_usize, 7], &[5, 6, 1], &[7], &[5, 1, 7]]),
-            Some(vec![5, 6, 7])
-        );
-    }
-
     #[rstest]
     fn test_flatten_pyiter() {
         Python::with_gil(|py| {
@@ -294,4 +587,17 @@ mod tests {
             assert!(array == expected)
         });
     }
+
+    #[rstest]
+    #[case(vec![vec![3], vec![2, 1], vec![]], vec![2, 3])]
+    #[case(vec![vec![4, 1], vec![1, 5]], vec![4, 5])]
+    #[case(vec![], vec![])]
+    fn test_broadcast_shapes(#[case] shapes: Vec<Vec<usize>>, #[case] expected: Vec<usize>) {
+        assert_eq!(broadcast_shapes(shapes.iter().map(Vec::as_slice)).unwrap(), expected);
+    }
+
+    #[rstest]
+    fn test_broadcast_shapes_mismatch() {
+        assert!(broadcast_shapes([&[3][..], &[2]]).is_err());
+    }
 }
@@ -1,18 +1,16 @@
-use std::str::FromStr;
-
 use numpy::{PyArray1, PyArrayMethods};
 use pyo3::{
     exceptions::{PyTypeError, PyValueError},
     intern,
     prelude::*,
     types::*,
+    IntoPyObjectExt,
 };
-use time::Date;
-
-use crate::core::{DateLike, DayCount};
 
-// time::Date::from_ordinal_date(1970, 1).unwrap().to_julian_day();
-static UNIX_EPOCH_JULIAN_DAY: i32 = 2440588;
+use crate::{
+    arrow,
+    core::{python, DateFormat, DateLike, DayCount, ExcelDateSystem, InvalidPaymentsError},
+};
 
 pub fn float_or_none(result: f64) -> Option<f64> {
     if result.is_nan() {
@@ -38,6 +36,19 @@ where
     }
 }
 
+/// Suppresses invalid payments found during the conversion (e.g. NaN amounts),
+/// the same way as `silent` suppresses the errors of the calculation.
+pub fn silent_invalid_payments<T>(
+    py: Python,
+    result: PyResult<T>,
+    silent: bool,
+) -> PyResult<Option<T>> {
+    match result {
+        Err(e) if silent && e.is_instance_of::<python::InvalidPaymentsError>(py) => Ok(None),
+        result => result.map(Some),
+    }
+}
+
 #[derive(FromPyObject)]
 pub enum PyDayCount {
     String(String),
@@ -55,141 +66,265 @@ impl TryInto<DayCount> for PyDayCount {
     }
 }
 
-#[pymethods]
-impl DayCount {
-    #[staticmethod]
-    fn of(value: &str) -> PyResult<Self> {
-        DayCount::from_str(value).map_err(PyValueError::new_err)
-    }
+// use numpy::datetime::{units, Datetime as datetime64};
+//
+// impl From<&datetime64<units::Days>> for DateLike {
+//     fn from(value: &datetime64<units::Days>) -> Self {
+//         let days_since_unix_epoch: i32 = Into::<i64>::into(*value) as i32;
+//         let date = Date::from_julian_day(UNIX_EPOCH_JULIAN_DAY + days_since_unix_epoch).unwrap();
+//
+//         date.into()
+//     }
+// }
 
-    fn __str__(&self) -> String {
-        self.to_string()
-    }
+/// What to do with NaN amounts and missing (None, NaT) dates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NanPolicy {
+    /// Raise `NaNAmountError` or `MissingDateError`.
+    #[default]
+    Raise,
+    /// Drop the pairs of date and amount where either of them is missing.
+    /// Not supported by positional series, see [`NanPolicy::apply`].
+    Omit,
+    /// Replace NaN amounts with zero. Pairs with a missing date are dropped,
+    /// as if their amount were zero.
+    Zero,
 }
 
-struct DaysSinceUnixEpoch(i32);
-
-impl<'py> FromPyObject<'py> for DaysSinceUnixEpoch {
+impl<'py> FromPyObject<'py> for NanPolicy {
     fn extract_bound(obj: &Bound<'py, PyAny>) -> PyResult<Self> {
-        obj.extract::<i64>().map(|x| Self(x as i32))
+        match obj.downcast::<PyString>()?.to_cow()?.as_ref() {
+            "raise" => Ok(NanPolicy::Raise),
+            "omit" => Ok(NanPolicy::Omit),
+            "zero" => Ok(NanPolicy::Zero),
+            _ => Err(PyValueError::new_err("nan_policy must be one of: 'raise', 'omit', 'zero'")),
+        }
     }
 }
 
-impl From<DaysSinceUnixEpoch> for DateLike {
-    fn from(value: DaysSinceUnixEpoch) -> Self {
-        Date::from_julian_day(UNIX_EPOCH_JULIAN_DAY + value.0).unwrap().into()
+impl NanPolicy {
+    /// For positional series (periodic cash flows, private equity series), where dropping
+    /// an amount would shift the following ones by one period, so `Omit` is rejected.
+    pub fn apply(self, amounts: Vec<f64>) -> PyResult<Vec<f64>> {
+        match self {
+            NanPolicy::Raise => match amounts.iter().position(|a| a.is_nan()) {
+                Some(index) => Err(InvalidPaymentsError::NaN {
+                    index,
+                }
+                .into()),
+                None => Ok(amounts),
+            },
+            NanPolicy::Omit => Err(NanPolicy::omit_positional()),
+            NanPolicy::Zero => Ok(amounts
+                .into_iter()
+                .map(|a| {
+                    if a.is_nan() {
+                        0.0
+                    } else {
+                        a
+                    }
+                })
+                .collect()),
+        }
     }
-}
 
-impl From<i64> for DateLike {
-    fn from(value: i64) -> Self {
-        Date::from_julian_day(UNIX_EPOCH_JULIAN_DAY + (value as i32)).unwrap().into()
+    pub fn omit_positional() -> PyErr {
+        PyValueError::new_err(
+            "nan_policy='omit' would shift the following amounts by one period, use 'zero'",
+        )
     }
-}
 
-impl TryFrom<&Bound<'_, PyDate>> for DateLike {
-    type Error = PyErr;
+    pub fn apply_payments(
+        self,
+        dates: Vec<Option<DateLike>>,
+        amounts: Vec<f64>,
+    ) -> PyResult<(Vec<DateLike>, Vec<f64>)> {
+        if dates.len() != amounts.len() {
+            return Err(InvalidPaymentsError::LengthMismatch {
+                amounts: amounts.len(),
+                dates: dates.len(),
+            }
+            .into());
+        }
 
-    #[cfg(feature = "abi")]
-    fn try_from(value: &Bound<'_, PyDate>) -> Result<Self, Self::Error> {
-        let py = value.py();
-        let date = Date::from_calendar_date(
-            value.getattr(intern!(py, "year"))?.extract::<i32>()?,
-            value.getattr(intern!(py, "month"))?.extract::<u8>()?.try_into().unwrap(),
-            value.getattr(intern!(py, "day"))?.extract::<u8>()?,
-        );
+        let mut result = (Vec::with_capacity(dates.len()), Vec::with_capacity(amounts.len()));
+
+        for (index, (date, amount)) in dates.into_iter().zip(amounts).enumerate() {
+            let amount = match (self, date, amount.is_nan()) {
+                (NanPolicy::Raise, None, _) => {
+                    return Err(InvalidPaymentsError::MissingDate {
+                        index,
+                    }
+                    .into())
+                }
+                (NanPolicy::Raise, _, true) => {
+                    return Err(InvalidPaymentsError::NaN {
+                        index,
+                    }
+                    .into())
+                }
+                (_, None, _) | (NanPolicy::Omit, _, true) => continue,
+                (NanPolicy::Zero, _, true) => 0.0,
+                _ => amount,
+            };
+            result.0.push(date.unwrap());
+            result.1.push(amount);
+        }
 
-        Ok(date.unwrap().into())
+        Ok(result)
     }
+}
 
-    #[cfg(not(feature = "abi"))]
-    fn try_from(value: &Bound<'_, PyDate>) -> Result<Self, Self::Error> {
-        let date = Date::from_calendar_date(
-            value.get_year(),
-            value.get_month().try_into().unwrap(),
-            value.get_day(),
-        );
+// pandas objects may implement the Arrow PyCapsule interface too,
+// but it requires pyarrow and reading their numpy arrays is cheaper
+pub fn is_pandas(obj: &Bound<PyAny>) -> PyResult<bool> {
+    Ok(obj.get_type().module()?.to_cow()?.starts_with("pandas"))
+}
 
-        Ok(date.unwrap().into())
-    }
+// None, NaN and NaT (not equal to itself)
+fn is_missing(obj: &Bound<PyAny>) -> PyResult<bool> {
+    Ok(obj.is_none() || obj.ne(obj)?)
 }
 
-// use numpy::datetime::{units, Datetime as datetime64};
-//
-// impl From<&datetime64<units::Days>> for DateLike {
-//     fn from(value: &datetime64<units::Days>) -> Self {
-//         let days_since_unix_epoch: i32 = Into::<i64>::into(*value) as i32;
-//         let date = Date::from_julian_day(UNIX_EPOCH_JULIAN_DAY + days_since_unix_epoch).unwrap();
-//
-//         date.into()
-//     }
-// }
+/// How to read dates given as strings and numbers.
+#[derive(Debug, Clone, Default)]
+pub struct DateParser {
+    format: Option<DateFormat>,
+    excel_serial: Option<ExcelDateSystem>,
+}
 
-impl<'py> FromPyObject<'py> for DateLike {
-    fn extract_bound(obj: &Bound<'py, PyAny>) -> PyResult<Self> {
-        if let Ok(py_date) = obj.downcast::<PyDate>() {
-            return py_date.try_into();
-        }
+impl DateParser {
+    /// `excel_serial` is either a bool (the 1900 date system) or the date system, 1900 or 1904.
+    pub fn new(date_format: Option<&str>, excel_serial: Option<&Bound<PyAny>>) -> PyResult<Self> {
+        let excel_serial = match excel_serial {
+            None => None,
+            Some(obj) if obj.is_instance_of::<PyBool>() => {
+                obj.is_truthy()?.then_some(ExcelDateSystem::Excel1900)
+            }
+            Some(obj) => Some(obj.str()?.to_cow()?.parse().map_err(PyValueError::new_err)?),
+        };
 
-        if let Ok(py_string) = obj.downcast::<PyString>() {
-            return py_string
-                .to_cow()?
-                .parse::<DateLike>()
-                .map_err(|e| PyValueError::new_err(e.to_string()));
-        }
+        Ok(Self {
+            format: date_format.map(str::parse).transpose()?,
+            excel_serial,
+        })
+    }
 
-        let py = obj.py();
+    fn is_default(&self) -> bool {
+        self.format.is_none() && self.excel_serial.is_none()
+    }
 
-        match obj.get_type().name()?.to_cow()?.as_ref() {
-            "datetime64" => Ok(obj
-                .call_method1(intern!(py, "astype"), (intern!(py, "datetime64[D]"),))?
-                .call_method1(intern!(py, "astype"), (intern!(py, "int32"),))?
-                .extract::<DaysSinceUnixEpoch>()?
-                .into()),
+    pub fn extract(&self, obj: &Bound<PyAny>) -> PyResult<DateLike> {
+        if let Ok(py_string) = obj.downcast::<PyString>() {
+            if let Some(format) = &self.format {
+                return Ok(format.parse(&py_string.to_cow()?)?);
+            }
+        } else if let Some(system) = self.excel_serial {
+            if let Ok(serial) = obj.extract::<f64>() {
+                return Ok(DateLike::from_excel_serial(serial, system)?);
+            }
+        }
+        python::extract_datetime(obj)
+    }
+}
 
-            "Timestamp" => Ok(obj
-                .call_method0(intern!(py, "to_pydatetime"))?
-                .downcast::<PyDate>()?
-                .try_into()?),
+fn has_datetime_dtype(obj: &Bound<PyAny>) -> PyResult<bool> {
+    let py = obj.py();
+    if !obj.hasattr(intern!(py, "dtype"))? {
+        return Ok(false);
+    }
+    let dtype = obj.getattr(intern!(py, "dtype"))?;
+    Ok(dtype.hasattr(intern!(py, "kind"))? && dtype.getattr(intern!(py, "kind"))?.eq("M")?)
+}
 
-            other => Err(PyTypeError::new_err(format!(
-                "Type {other:?} is not understood. Expected: date"
-            ))),
-        }
+pub fn extract_optional_date(
+    obj: &Bound<PyAny>,
+    parser: &DateParser,
+) -> PyResult<Option<DateLike>> {
+    match parser.extract(obj) {
+        Ok(date) => Ok(Some(date)),
+        Err(_) if is_missing(obj)? => Ok(None),
+        Err(e) => Err(e),
     }
 }
 
-fn extract_iterable<'a, T>(values: &Bound<'a, PyAny>) -> PyResult<Vec<T>>
-where
-    T: FromPyObject<'a>,
-{
-    values.try_iter()?.map(|i| i.and_then(|j| j.extract())).collect()
+fn extract_optional_amount(obj: &Bound<PyAny>) -> PyResult<f64> {
+    match obj.extract() {
+        Ok(amount) => Ok(amount),
+        Err(_) if is_missing(obj)? => Ok(f64::NAN),
+        Err(e) => Err(e),
+    }
 }
 
-fn extract_date_series_from_numpy(series: &Bound<PyAny>) -> PyResult<Vec<DateLike>> {
+fn extract_optional_date_series_from_numpy(
+    series: &Bound<PyAny>,
+) -> PyResult<Vec<Option<DateLike>>> {
     let py = series.py();
     Ok(series
-        .call_method1(intern!(py, "astype"), (intern!(py, "datetime64[D]"),))?
-        .call_method1(intern!(py, "astype"), (intern!(py, "int32"),))?
-        .downcast::<PyArray1<i32>>()?
+        .call_method1(intern!(py, "astype"), (intern!(py, "datetime64[us]"),))?
+        .call_method1(intern!(py, "astype"), (intern!(py, "int64"),))?
+        .downcast::<PyArray1<i64>>()?
         .readonly()
         .as_slice()?
         .iter()
-        .map(|&x| DateLike::from(DaysSinceUnixEpoch(x)))
+        // NaT is the minimal int64 value
+        .map(|&x| (x != i64::MIN).then(|| DateLike::from_unix_nanos(x as i128 * 1000)))
         .collect())
 }
 
-pub fn extract_date_series(series: &Bound<PyAny>) -> PyResult<Vec<DateLike>> {
+/// Converts arrays of unsupported Arrow types (e.g. timestamps with a time zone) to a list,
+/// as pyarrow arrays are iterated as arrow scalars.
+fn to_pylist<'py>(series: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyAny>> {
+    if series.hasattr(intern!(series.py(), "to_pylist"))? {
+        series.call_method0(intern!(series.py(), "to_pylist"))
+    } else {
+        Ok(series.clone())
+    }
+}
+
+pub fn extract_optional_date_series(
+    series: &Bound<PyAny>,
+    parser: &DateParser,
+) -> PyResult<Vec<Option<DateLike>>> {
+    if !parser.is_default() && !has_datetime_dtype(series)? {
+        // strings and numbers are parsed one by one
+        return to_pylist(series)?
+            .try_iter()?
+            .map(|i| extract_optional_date(&i?, parser))
+            .collect();
+    }
+
     match series.get_type().name()?.to_cow()?.as_ref() {
-        "Series" => {
+        "Series" if is_pandas(series)? => {
             let values = series.getattr(intern!(series.py(), "values"))?;
-            extract_date_series_from_numpy(&values)
+            extract_optional_date_series_from_numpy(&values)
         }
-        "ndarray" => extract_date_series_from_numpy(series),
-        _ => extract_iterable::<DateLike>(series),
+        "ndarray" => extract_optional_date_series_from_numpy(series),
+        _ => match arrow::extract_dates(series)? {
+            Some(dates) => Ok(dates),
+            None => {
+                to_pylist(series)?.try_iter()?.map(|i| extract_optional_date(&i?, parser)).collect()
+            }
+        },
     }
 }
 
+pub fn extract_date_series(series: &Bound<PyAny>, parser: &DateParser) -> PyResult<Vec<DateLike>> {
+    extract_optional_date_series(series, parser)?
+        .into_iter()
+        .enumerate()
+        .map(|(index, date)| {
+            date.ok_or(
+                InvalidPaymentsError::MissingDate {
+                    index,
+                }
+                .into(),
+            )
+            .map(DateLike::to_date)
+        })
+        .collect()
+}
+
 fn extract_amount_series_from_numpy(series: &Bound<PyAny>) -> PyResult<Vec<f64>> {
     let py = series.py();
     Ok(series
@@ -198,9 +333,9 @@ fn extract_amount_series_from_numpy(series: &Bound<PyAny>) -> PyResult<Vec<f64>>
         .to_vec()?)
 }
 
-fn extract_records(data: &Bound<PyAny>) -> PyResult<(Vec<DateLike>, Vec<f64>)> {
+fn extract_records(data: &Bound<PyAny>, parser: &DateParser) -> PyResult<OptionalPayments> {
     let capacity = data.len().unwrap_or(12); // pre-allocate vec
-    let mut dates: Vec<DateLike> = Vec::with_capacity(capacity);
+    let mut dates: Vec<Option<DateLike>> = Vec::with_capacity(capacity);
     let mut amounts: Vec<f64> = Vec::with_capacity(capacity);
 
     for obj in data.try_iter()? {
@@ -215,8 +350,8 @@ fn extract_records(data: &Bound<PyAny>) -> PyResult<(Vec<DateLike>, Vec<f64>)> {
             (obj.get_item(0)?, obj.get_item(1)?)
         };
 
-        dates.push(tup.0.extract::<DateLike>()?);
-        amounts.push(tup.1.extract::<f64>()?);
+        dates.push(extract_optional_date(&tup.0, parser)?);
+        amounts.push(extract_optional_amount(&tup.1)?);
     }
 
     Ok((dates, amounts))
@@ -246,27 +381,313 @@ impl std::ops::Deref for AmountArray {
 
 pub fn extract_amount_series(series: &Bound<PyAny>) -> PyResult<Vec<f64>> {
     match series.get_type().name()?.to_cow()?.as_ref() {
-        "Series" => {
+        "Series" if is_pandas(series)? => {
             let values = series.getattr(intern!(series.py(), "values"))?;
             extract_amount_series_from_numpy(&values)
         }
         "ndarray" => extract_amount_series_from_numpy(series),
-        _ => extract_iterable::<f64>(series),
+        _ => match arrow::extract_amounts(series)? {
+            Some(amounts) => Ok(amounts),
+            None => to_pylist(series)?.try_iter()?.map(|i| extract_optional_amount(&i?)).collect(),
+        },
     }
 }
 
 pub fn extract_payments(
     dates: &Bound<PyAny>,
     amounts: Option<&Bound<PyAny>>,
+    nan_policy: NanPolicy,
 ) -> PyResult<(Vec<DateLike>, Vec<f64>)> {
+    let (dates, amounts) = extract_optional_payments(dates, amounts, &DateParser::default())?;
+    let (dates, amounts) = nan_policy.apply_payments(dates, amounts)?;
+    Ok((with_precision(dates, false), amounts))
+}
+
+/// Intraday dates are normalised to UTC, otherwise only the local date is kept.
+pub fn with_precision(dates: Vec<DateLike>, intraday: bool) -> Vec<DateLike> {
+    let f = if intraday {
+        DateLike::to_utc
+    } else {
+        DateLike::to_date
+    };
+    dates.into_iter().map(f).collect()
+}
+
+/// Columns of a DataFrame-like input, selected by `date_col`, `amount_col` and `by` arguments.
+pub struct Columns<'py> {
+    pub date: Option<Bound<'py, PyAny>>,
+    pub amount: Option<Bound<'py, PyAny>>,
+    pub by: Option<Bound<'py, PyAny>>,
+}
+
+impl Columns<'_> {
+    pub fn is_empty(&self) -> bool {
+        self.date.is_none() && self.amount.is_none() && self.by.is_none()
+    }
+}
+
+type OptionalPayments = (Vec<Option<DateLike>>, Vec<f64>);
+
+enum CashFlows<'py> {
+    Single(OptionalPayments),
+    /// Results are returned as a pandas Series (or dict) with `keys` as index.
+    Grouped {
+        name: Option<Bound<'py, PyAny>>,
+        keys: Vec<Bound<'py, PyAny>>,
+        payments: Vec<OptionalPayments>,
+    },
+}
+
+/// Extracts payments and calls `f` on them, or on every group of them
+/// when the input is grouped by `columns.by` or has multiple amount columns.
+#[allow(clippy::too_many_arguments)]
+pub fn map_cash_flows<'py, T, F>(
+    dates: &Bound<'py, PyAny>,
+    amounts: Option<&Bound<'py, PyAny>>,
+    columns: Columns<'py>,
+    nan_policy: NanPolicy,
+    parser: &DateParser,
+    intraday: bool,
+    silent: bool,
+    f: F,
+) -> PyResult<Bound<'py, PyAny>>
+where
+    T: IntoPyObject<'py>,
+    F: Fn(Vec<DateLike>, Vec<f64>) -> PyResult<Option<T>>,
+{
+    let py = dates.py();
+    let apply = |(dates, amounts): OptionalPayments| {
+        let payments = nan_policy.apply_payments(dates, amounts);
+        match silent_invalid_payments(py, payments, silent)? {
+            Some((dates, amounts)) => {
+                f(with_precision(dates, intraday), amounts)?.into_bound_py_any(py)
+            }
+            None => Ok(py.None().into_bound(py)),
+        }
+    };
+
+    match extract_cash_flows(dates, amounts, columns, parser)? {
+        CashFlows::Single(payments) => apply(payments),
+        CashFlows::Grouped {
+            name,
+            keys,
+            payments,
+        } => {
+            let values = payments.into_iter().map(apply).collect::<PyResult<Vec<_>>>()?;
+            grouped_result(py, name, keys, values)
+        }
+    }
+}
+
+fn extract_cash_flows<'py>(
+    dates: &Bound<'py, PyAny>,
+    amounts: Option<&Bound<'py, PyAny>>,
+    columns: Columns<'py>,
+    parser: &DateParser,
+) -> PyResult<CashFlows<'py>> {
+    let py = dates.py();
+
+    if columns.is_empty() {
+        return match amounts {
+            None if is_pandas_frame_of_amount_columns(dates)? => extract_amount_columns(dates),
+            _ => Ok(CashFlows::Single(extract_optional_payments(dates, amounts, parser)?)),
+        };
+    }
+
     if amounts.is_some() {
-        return Ok((extract_date_series(dates)?, extract_amount_series(amounts.unwrap())?));
+        return Err(PyTypeError::new_err(
+            "date_col, amount_col and by require a DataFrame as the only input",
+        ));
+    }
+
+    let frame = dates;
+    let Columns {
+        date,
+        amount,
+        by,
+    } = columns;
+
+    let (date, amount) = match (date, amount) {
+        (Some(date), Some(amount)) => (date, amount),
+        (date, amount) => {
+            // default to the columns that are not selected explicitly, in order
+            let selected = [date.clone(), amount.clone(), by.clone()];
+            let mut rest = column_names(frame)?.into_iter().filter(|name| {
+                !selected
+                    .iter()
+                    .any(|col| col.as_ref().is_some_and(|c| c.eq(name).unwrap_or(false)))
+            });
+            let date = date.or_else(|| rest.next());
+            let amount = amount.or_else(|| rest.next());
+            match (date, amount) {
+                (Some(date), Some(amount)) => (date, amount),
+                _ => return Err(PyValueError::new_err("Could not find date and amount columns")),
+            }
+        }
+    };
+
+    let payments = (
+        extract_optional_date_series(&frame.get_item(date)?, parser)?,
+        extract_amount_series(&frame.get_item(amount)?)?,
+    );
+
+    let Some(by) = by else {
+        return Ok(CashFlows::Single(payments));
+    };
+
+    let series = to_pylist(&frame.get_item(&by)?)?;
+
+    // group index by key, in order of appearance
+    let indices = PyDict::new(py);
+    let mut keys = Vec::new();
+    let mut groups: Vec<OptionalPayments> = Vec::new();
+    let mut rows = payments.0.into_iter().zip(payments.1);
+
+    for key in series.try_iter()? {
+        let key = key?;
+        let Some((date, amount)) = rows.next() else {
+            return Err(PyValueError::new_err("by column must be the same length as the data"));
+        };
+        let index = match indices.get_item(&key)? {
+            Some(index) => index.extract::<usize>()?,
+            None => {
+                indices.set_item(&key, keys.len())?;
+                keys.push(key);
+                groups.push(Default::default());
+                keys.len() - 1
+            }
+        };
+        groups[index].0.push(date);
+        groups[index].1.push(amount);
+    }
+
+    if rows.next().is_some() {
+        return Err(PyValueError::new_err("by column must be the same length as the data"));
+    }
+
+    Ok(CashFlows::Grouped {
+        name: Some(by),
+        keys,
+        payments: groups,
+    })
+}
+
+/// A DataFrame indexed by date with several columns, all numeric, e.g. a set of funds.
+/// A frame with a date column keeps its first two columns as dates and amounts,
+/// a single amount column is a single cash flow.
+fn is_pandas_frame_of_amount_columns(obj: &Bound<PyAny>) -> PyResult<bool> {
+    let py = obj.py();
+    if !(is_pandas_frame_with_datetime_index(obj)?
+        && obj.getattr(intern!(py, "columns"))?.len()? > 1)
+    {
+        return Ok(false);
+    }
+
+    // bool, int, uint and float
+    for dtype in obj.getattr(intern!(py, "dtypes"))?.try_iter()? {
+        let kind = dtype?.getattr(intern!(py, "kind"))?;
+        if !"biuf".contains(kind.downcast::<PyString>()?.to_cow()?.as_ref()) {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+fn is_pandas_frame_with_datetime_index(obj: &Bound<PyAny>) -> PyResult<bool> {
+    Ok(obj.get_type().name()?.eq("DataFrame")
+        && is_pandas(obj)?
+        && obj.getattr(intern!(obj.py(), "index"))?.get_type().name()?.eq("DatetimeIndex"))
+}
+
+/// One cash flow per column, sharing the dates of the index.
+fn extract_amount_columns<'py>(frame: &Bound<'py, PyAny>) -> PyResult<CashFlows<'py>> {
+    let py = frame.py();
+    let index = frame.getattr(intern!(py, "index"))?;
+    let dates = extract_optional_date_series(&index, &DateParser::default())?;
+    let keys = column_names(frame)?;
+    let payments = keys
+        .iter()
+        .map(|key| Ok((dates.clone(), extract_amount_series(&frame.get_item(key)?)?)))
+        .collect::<PyResult<_>>()?;
+
+    Ok(CashFlows::Grouped {
+        name: None,
+        keys,
+        payments,
+    })
+}
+
+fn column_names<'py>(frame: &Bound<'py, PyAny>) -> PyResult<Vec<Bound<'py, PyAny>>> {
+    let py = frame.py();
+    let names = if let Ok(dict) = frame.downcast::<PyDict>() {
+        dict.keys().into_any()
+    } else if frame.hasattr(intern!(py, "column_names"))? {
+        // pyarrow tables, their `columns` are arrays
+        frame.getattr(intern!(py, "column_names"))?
+    } else {
+        frame.getattr(intern!(py, "columns"))?
+    };
+    names.try_iter()?.collect()
+}
+
+/// A pandas Series if pandas is available, otherwise a dict.
+fn grouped_result<'py>(
+    py: Python<'py>,
+    name: Option<Bound<'py, PyAny>>,
+    keys: Vec<Bound<'py, PyAny>>,
+    values: Vec<Bound<'py, PyAny>>,
+) -> PyResult<Bound<'py, PyAny>> {
+    let Ok(pandas) = py.import(intern!(py, "pandas")) else {
+        let result = PyDict::new(py);
+        for (key, value) in keys.into_iter().zip(values) {
+            result.set_item(key, value)?;
+        }
+        return Ok(result.into_any());
+    };
+
+    let kwargs = PyDict::new(py);
+    kwargs.set_item(intern!(py, "name"), name)?;
+    let index = pandas.getattr(intern!(py, "Index"))?.call((keys,), Some(&kwargs))?;
+    let kwargs = PyDict::new(py);
+    kwargs.set_item(intern!(py, "index"), index)?;
+    pandas.getattr(intern!(py, "Series"))?.call((values,), Some(&kwargs))
+}
+
+/// A pandas DataFrame if pandas is available, otherwise a list of rows.
+pub fn table_result<'py>(
+    py: Python<'py>,
+    rows: Vec<Vec<f64>>,
+    (index_name, index): (&str, &[f64]),
+    (columns_name, columns): (&str, &[f64]),
+) -> PyResult<Bound<'py, PyAny>> {
+    let Ok(pandas) = py.import(intern!(py, "pandas")) else {
+        return rows.into_bound_py_any(py);
+    };
+
+    let labels = |name: &str, values: &[f64]| {
+        let kwargs = PyDict::new(py);
+        kwargs.set_item(intern!(py, "name"), name)?;
+        pandas.getattr(intern!(py, "Index"))?.call((values.to_vec(),), Some(&kwargs))
+    };
+    let kwargs = PyDict::new(py);
+    kwargs.set_item(intern!(py, "index"), labels(index_name, index)?)?;
+    kwargs.set_item(intern!(py, "columns"), labels(columns_name, columns)?)?;
+    pandas.getattr(intern!(py, "DataFrame"))?.call((rows,), Some(&kwargs))
+}
+
+pub fn extract_optional_payments(
+    dates: &Bound<PyAny>,
+    amounts: Option<&Bound<PyAny>>,
+    parser: &DateParser,
+) -> PyResult<OptionalPayments> {
+    if let Some(amounts) = amounts {
+        return Ok((extract_optional_date_series(dates, parser)?, extract_amount_series(amounts)?));
     };
 
     if let Ok(py_dict) = dates.downcast::<PyDict>() {
         return Ok((
-            extract_iterable::<DateLike>(py_dict.keys().as_any())?,
-            extract_iterable::<f64>(py_dict.values().as_any())?,
+            extract_optional_date_series(py_dict.keys().as_any(), parser)?,
+            extract_amount_series(py_dict.values().as_any())?,
         ));
     }
 
@@ -276,28 +697,39 @@ pub fn extract_payments(
         "DataFrame" => {
             let frame = dates;
             let columns = frame.getattr(intern!(py, "columns"))?;
+            // a single amount column indexed by date, as a Series
+            if columns.len()? == 1 && is_pandas_frame_with_datetime_index(frame)? {
+                return Ok((
+                    extract_optional_date_series(&frame.getattr(intern!(py, "index"))?, parser)?,
+                    extract_amount_series(&frame.get_item(columns.get_item(0)?)?)?,
+                ));
+            }
             Ok((
-                extract_date_series(&frame.get_item(columns.get_item(0)?)?)?,
+                extract_optional_date_series(&frame.get_item(columns.get_item(0)?)?, parser)?,
                 extract_amount_series(&frame.get_item(columns.get_item(1)?)?)?,
             ))
         }
-        "Series" => {
+        "Series" if is_pandas(dates)? => {
             let index = &dates.getattr(intern!(py, "index"))?;
 
             if index.get_type().name()?.ne("DatetimeIndex") {
                 return Err(PyTypeError::new_err("Expected Series with DatetimeIndex"));
             }
 
-            Ok((extract_date_series(index)?, extract_amount_series(dates)?))
+            Ok((extract_optional_date_series(index, parser)?, extract_amount_series(dates)?))
         }
         "ndarray" => {
             let array = dates;
             Ok((
-                extract_date_series(&array.get_item(0)?)?,
+                extract_optional_date_series(&array.get_item(0)?, parser)?,
                 extract_amount_series(&array.get_item(1)?)?,
             ))
         }
-        _ => extract_records(dates),
+        _ if !parser.is_default() => extract_records(dates, parser),
+        _ => match arrow::extract_table(dates)? {
+            Some(payments) => Ok(payments),
+            None => extract_records(dates, parser),
+        },
     }
 }
 
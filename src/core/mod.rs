@@ -1,12 +0,0 @@
-// TODO: move core module into a separate crate
-
-mod models;
-mod optimize;
-pub mod periodic;
-mod scheduled;
-mod utils;
-
-pub use models::{DateLike, InvalidPaymentsError};
-pub use periodic::*;
-pub use scheduled::*;
-pub mod private_equity;
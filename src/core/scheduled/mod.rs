@@ -1,7 +0,0 @@
-mod day_count;
-mod xirr;
-mod xnfv;
-
-pub use day_count::{days_between, year_fraction, DayCount};
-pub use xirr::*;
-pub use xnfv::*;
@@ -0,0 +1,298 @@
+//! Decimal arithmetic for the non-iterative functions (`decimal=True`).
+//!
+//! Amounts are converted to `decimal.Decimal` without going through `f64`
+//! (floats are converted from their shortest repr, so `0.1` stays `0.1`)
+//! and the arithmetic is done by Python's `decimal` module in the current context.
+
+use pyo3::{
+    intern,
+    prelude::*,
+    types::{PyDict, PyFloat, PyInt, PyString},
+};
+
+use crate::{
+    conversions::{extract_optional_date, extract_optional_date_series, DateParser, NanPolicy},
+    core::{year_fraction, DateLike, DayCount, InvalidPaymentsError},
+};
+
+pub struct Decimals<'py> {
+    cls: Bound<'py, PyAny>,
+}
+
+impl<'py> Decimals<'py> {
+    pub fn new(py: Python<'py>) -> PyResult<Self> {
+        Ok(Self {
+            cls: py.import(intern!(py, "decimal"))?.getattr(intern!(py, "Decimal"))?,
+        })
+    }
+
+    fn py(&self) -> Python<'py> {
+        self.cls.py()
+    }
+
+    pub fn float(&self, value: f64) -> PyResult<Bound<'py, PyAny>> {
+        // the repr of a float is the shortest string that round-trips
+        self.cls.call1((PyFloat::new(self.py(), value).repr()?,))
+    }
+
+    /// Decimals, ints and strings are converted exactly, None is NaN.
+    pub fn convert(&self, obj: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyAny>> {
+        let py = self.py();
+        if obj.is_none() {
+            self.cls.call1((intern!(py, "NaN"),))
+        } else if obj.is_instance(&self.cls)? {
+            Ok(obj.clone())
+        } else if let Ok(value) = obj.downcast::<PyFloat>() {
+            // numpy.float64 is a float subclass with a different repr
+            self.float(value.value())
+        } else if obj.is_instance_of::<PyInt>() || obj.is_instance_of::<PyString>() {
+            self.cls.call1((obj,))
+        } else if obj.hasattr(intern!(py, "item"))? {
+            // numpy scalars
+            self.convert(&obj.call_method0(intern!(py, "item"))?)
+        } else {
+            self.cls.call1((obj,))
+        }
+    }
+
+    pub fn extract(&self, obj: &Bound<'py, PyAny>) -> PyResult<Vec<Bound<'py, PyAny>>> {
+        obj.try_iter()?.map(|x| self.convert(&x?)).collect()
+    }
+
+    /// Like [`NanPolicy::apply`].
+    pub fn apply_nan_policy(
+        &self,
+        nan_policy: NanPolicy,
+        amounts: Vec<Bound<'py, PyAny>>,
+    ) -> PyResult<Vec<Bound<'py, PyAny>>> {
+        if nan_policy == NanPolicy::Omit {
+            return Err(NanPolicy::omit_positional());
+        }
+        let dates = vec![Some(()); amounts.len()];
+        Ok(self.apply_nan_policy_payments(nan_policy, dates, amounts)?.1)
+    }
+
+    /// Like `NanPolicy::apply_payments`.
+    pub fn apply_nan_policy_payments<D>(
+        &self,
+        nan_policy: NanPolicy,
+        dates: Vec<Option<D>>,
+        amounts: Vec<Bound<'py, PyAny>>,
+    ) -> PyResult<(Vec<D>, Vec<Bound<'py, PyAny>>)> {
+        if dates.len() != amounts.len() {
+            return Err(InvalidPaymentsError::LengthMismatch {
+                amounts: amounts.len(),
+                dates: dates.len(),
+            }
+            .into());
+        }
+
+        let mut result = (Vec::with_capacity(dates.len()), Vec::with_capacity(amounts.len()));
+
+        for (index, (date, amount)) in dates.into_iter().zip(amounts).enumerate() {
+            let is_nan = amount.call_method0(intern!(self.py(), "is_nan"))?.is_truthy()?;
+            let amount = match (nan_policy, date, is_nan) {
+                (NanPolicy::Raise, None, _) => {
+                    return Err(InvalidPaymentsError::MissingDate {
+                        index,
+                    }
+                    .into())
+                }
+                (NanPolicy::Raise, _, true) => {
+                    return Err(InvalidPaymentsError::NaN {
+                        index,
+                    }
+                    .into())
+                }
+                (_, None, _) | (NanPolicy::Omit, _, true) => continue,
+                (NanPolicy::Zero, Some(date), true) => (date, self.zero()?),
+                (_, Some(date), false) => (date, amount),
+            };
+            result.0.push(amount.0);
+            result.1.push(amount.1);
+        }
+
+        Ok(result)
+    }
+
+    fn zero(&self) -> PyResult<Bound<'py, PyAny>> {
+        self.cls.call1((0,))
+    }
+
+    fn one(&self) -> PyResult<Bound<'py, PyAny>> {
+        self.cls.call1((1,))
+    }
+
+    pub fn sum<'a, I>(&self, values: I) -> PyResult<Bound<'py, PyAny>>
+    where
+        'py: 'a,
+        I: IntoIterator<Item = &'a Bound<'py, PyAny>>,
+    {
+        values.into_iter().try_fold(self.zero()?, |acc, x| acc.add(x))
+    }
+
+    /// Sums of the negative and the positive values.
+    fn sum_negatives_positives(
+        &self,
+        values: &[Bound<'py, PyAny>],
+    ) -> PyResult<(Bound<'py, PyAny>, Bound<'py, PyAny>)> {
+        let zero = self.zero()?;
+        let mut negatives = Vec::new();
+        let mut positives = Vec::new();
+        for value in values {
+            if value.lt(&zero)? {
+                negatives.push(value);
+            } else {
+                positives.push(value);
+            }
+        }
+        Ok((self.sum(negatives)?, self.sum(positives)?))
+    }
+
+    fn check_zero_contributions(&self, contributions: &Bound<'py, PyAny>) -> PyResult<()> {
+        if contributions.eq(self.zero()?)? {
+            Err(InvalidPaymentsError::ZeroContributions.into())
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn npv(
+        &self,
+        rate: f64,
+        values: &[Bound<'py, PyAny>],
+        start_from_zero: bool,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let factor = self.one()?.add(self.float(rate)?)?;
+        let mut discount = if start_from_zero {
+            self.one()?
+        } else {
+            factor.clone()
+        };
+        let mut result = self.zero()?;
+        for value in values {
+            result = result.add(value.div(&discount)?)?;
+            discount = discount.mul(&factor)?;
+        }
+        Ok(result)
+    }
+
+    pub fn xnpv(
+        &self,
+        rate: f64,
+        dates: &[DateLike],
+        amounts: &[Bound<'py, PyAny>],
+        day_count: Option<DayCount>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        if dates.len() != amounts.len() {
+            return Err(InvalidPaymentsError::LengthMismatch {
+                amounts: amounts.len(),
+                dates: dates.len(),
+            }
+            .into());
+        }
+        let Some(min_date) = dates.iter().min() else {
+            return self.zero();
+        };
+
+        let py = self.py();
+        let day_count = day_count.unwrap_or_default();
+        let factor = self.one()?.add(self.float(rate)?)?;
+        let mut result = self.zero()?;
+        for (date, amount) in dates.iter().zip(amounts) {
+            let exponent = self.float(-year_fraction(min_date, date, day_count))?;
+            result = result.add(amount.mul(factor.pow(exponent, py.None())?)?)?;
+        }
+        Ok(result)
+    }
+
+    pub fn dpi(&self, amounts: &[Bound<'py, PyAny>]) -> PyResult<Bound<'py, PyAny>> {
+        let (cs, ds) = self.sum_negatives_positives(amounts)?;
+        self.check_zero_contributions(&cs)?;
+        ds.div(cs.neg()?)
+    }
+
+    pub fn dpi_2(
+        &self,
+        contributions: &[Bound<'py, PyAny>],
+        distributions: &[Bound<'py, PyAny>],
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let cs = self.sum(contributions)?;
+        self.check_zero_contributions(&cs)?;
+        self.sum(distributions)?.div(cs)
+    }
+
+    pub fn rvpi(
+        &self,
+        contributions: &[Bound<'py, PyAny>],
+        nav: f64,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let cs = self.sum(contributions)?;
+        self.check_zero_contributions(&cs)?;
+        let zero = self.zero()?;
+        // contributions are either all negative or all positive
+        for value in contributions {
+            if value.lt(&zero)? {
+                return self.float(nav)?.div(cs.neg()?);
+            }
+        }
+        self.float(nav)?.div(cs)
+    }
+
+    pub fn tvpi(&self, amounts: &[Bound<'py, PyAny>], nav: f64) -> PyResult<Bound<'py, PyAny>> {
+        let (cs, ds) = self.sum_negatives_positives(amounts)?;
+        self.check_zero_contributions(&cs)?;
+        ds.add(self.float(nav)?)?.div(cs.neg()?)
+    }
+
+    pub fn tvpi_2(
+        &self,
+        contributions: &[Bound<'py, PyAny>],
+        distributions: &[Bound<'py, PyAny>],
+        nav: f64,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let cs = self.sum(contributions)?;
+        self.check_zero_contributions(&cs)?;
+        self.sum(distributions)?.add(self.float(nav)?)?.div(cs)
+    }
+}
+
+type DecimalPayments<'py> = (Vec<Option<DateLike>>, Vec<Bound<'py, PyAny>>);
+
+/// Dates and decimal amounts of separate arrays, a dict, a DataFrame or records.
+pub fn extract_payments<'py>(
+    decimals: &Decimals<'py>,
+    dates: &Bound<'py, PyAny>,
+    amounts: Option<&Bound<'py, PyAny>>,
+    parser: &DateParser,
+) -> PyResult<DecimalPayments<'py>> {
+    let py = dates.py();
+
+    if let Some(amounts) = amounts {
+        return Ok((extract_optional_date_series(dates, parser)?, decimals.extract(amounts)?));
+    }
+
+    if let Ok(py_dict) = dates.downcast::<PyDict>() {
+        return Ok((
+            extract_optional_date_series(py_dict.keys().as_any(), parser)?,
+            decimals.extract(py_dict.values().as_any())?,
+        ));
+    }
+
+    if dates.get_type().name()?.eq("DataFrame") {
+        let columns = dates.getattr(intern!(py, "columns"))?;
+        return Ok((
+            extract_optional_date_series(&dates.get_item(columns.get_item(0)?)?, parser)?,
+            decimals.extract(&dates.get_item(columns.get_item(1)?)?)?,
+        ));
+    }
+
+    let mut result: DecimalPayments = Default::default();
+    for record in dates.try_iter()? {
+        let record = record?;
+        result.0.push(extract_optional_date(&record.get_item(0)?, parser)?);
+        result.1.push(decimals.convert(&record.get_item(1)?)?);
+    }
+
+    Ok(result)
+}
@@ -1,26 +1,32 @@
-use broadcasting::Arg;
-use conversions::{fallible_float_or_none, float_or_none, AmountArray, PyDayCount};
+use std::str::FromStr;
+
+use broadcasting::{Arg, Labeled, Output};
+use conversions::{
+    fallible_float_or_none, float_or_none, map_cash_flows, silent_invalid_payments, AmountArray,
+    Columns, DateParser, NanPolicy, PyDayCount,
+};
+use decimal::Decimals;
 use numpy::{PyArray, PyArrayMethods};
-use pyo3::{create_exception, exceptions, prelude::*, wrap_pyfunction};
-
+use pyo3::{
+    exceptions::{PyIndexError, PyTypeError, PyValueError},
+    prelude::*,
+    types::PyDict,
+    wrap_pyfunction, IntoPyObjectExt,
+};
+use pyxirr_core as core;
+
+pub use crate::core::python::{
+    AmbiguousDateError, BroadcastingError, EmptyInputError, InvalidPaymentsError,
+    LengthMismatchError, MissingDateError, NaNAmountError, NoSignChangeError,
+    ZeroContributionsError,
+};
+
+mod arrow;
 mod broadcasting;
 mod conversions;
-mod core;
-
-create_exception!(pyxirr, InvalidPaymentsError, exceptions::PyException);
-create_exception!(pyxirr, BroadcastingError, exceptions::PyException);
-
-impl From<core::InvalidPaymentsError> for PyErr {
-    fn from(value: core::InvalidPaymentsError) -> Self {
-        InvalidPaymentsError::new_err(value.to_string())
-    }
-}
-
-impl From<broadcasting::BroadcastingError> for PyErr {
-    fn from(value: broadcasting::BroadcastingError) -> Self {
-        BroadcastingError::new_err(value.to_string())
-    }
-}
+mod decimal;
+mod rows;
+mod ufunc;
 
 macro_rules! dispatch_vectorized {
     (infallible $py:ident, ($($vars:ident),*), $non_vec:expr, $vec:expr ) => {
@@ -44,9 +50,11 @@ macro_rules! dispatch_vectorized {
             }
         }
     };
-    ($py:ident, ($($vars:ident),*), $non_vec:expr, $vec:expr ) => {
+    ($py:ident, ($($vars:ident),*), $keep_dtype:expr, $non_vec:expr, $vec:expr ) => {
         {
-            match ($($vars,)*) {
+            let output = Output::of([$($vars.origin(),)*], $keep_dtype);
+            let ($($vars,)*) = ($($vars.arg,)*);
+            let result = match ($($vars,)*) {
                 ($(Arg::Scalar($vars),)*) => {
                     let result = $py.allow_threads(move || $non_vec);
                     Ok(Arg::Scalar(result))
@@ -61,70 +69,596 @@ macro_rules! dispatch_vectorized {
                     } else {
                         result.map(Arg::from)
                     };
-                    result.map_err(|e| e.into())
+                    result.map_err(PyErr::from)
                 }
-            }
+            };
+            result.and_then(|r| output.wrap($py, r))
         }
     };
 }
 
 /// Internal Rate of Return for a non-periodic cash flows.
 #[pyfunction]
-#[pyo3(signature = (dates, amounts=None, *, guess=None, silent=false, day_count=None))]
-#[pyo3(text_signature = "(dates, amounts=None, *, guess=None, silent=False, day_count=None)")]
-fn xirr(
-    py: Python,
-    dates: &Bound<PyAny>,
-    amounts: Option<&Bound<PyAny>>,
+#[pyo3(signature = (dates, amounts=None, *, guess=None, silent=false, day_count=None, nan_policy=NanPolicy::Raise, date_col=None, amount_col=None, by=None, intraday=false, date_format=None, excel_serial=None, axis=None, compounding=core::Compounding::Annual, money_market=false))]
+#[pyo3(
+    text_signature = "(dates, amounts=None, *, guess=None, silent=False, day_count=None, nan_policy='raise', date_col=None, amount_col=None, by=None, intraday=False, date_format=None, excel_serial=None, axis=None, compounding='annual', money_market=False)"
+)]
+#[allow(clippy::too_many_arguments)]
+fn xirr<'py>(
+    py: Python<'py>,
+    dates: &Bound<'py, PyAny>,
+    amounts: Option<&Bound<'py, PyAny>>,
     guess: Option<f64>,
     silent: Option<bool>,
     day_count: Option<PyDayCount>,
-) -> PyResult<Option<f64>> {
-    let (dates, amounts) = conversions::extract_payments(dates, amounts)?;
+    nan_policy: NanPolicy,
+    date_col: Option<Bound<'py, PyAny>>,
+    amount_col: Option<Bound<'py, PyAny>>,
+    by: Option<Bound<'py, PyAny>>,
+    intraday: bool,
+    date_format: Option<&str>,
+    excel_serial: Option<&Bound<'py, PyAny>>,
+    axis: Option<isize>,
+    mut compounding: core::Compounding,
+    money_market: bool,
+) -> PyResult<Bound<'py, PyAny>> {
+    let silent = silent.unwrap_or(false);
+    let mut day_count = day_count.map(|x| x.try_into()).transpose()?;
+    if money_market {
+        if compounding != core::Compounding::Annual {
+            return Err(PyValueError::new_err("money_market=True implies simple compounding"));
+        }
+        // simple interest on an ACT/360 basis, unless another day count is given
+        day_count = Some(day_count.unwrap_or(core::DayCount::ACT_360));
+        compounding = core::Compounding::Simple;
+    }
+    let columns = Columns {
+        date: date_col,
+        amount: amount_col,
+        by,
+    };
+    let parser = DateParser::new(date_format, excel_serial)?;
+
+    if let Some(axis) = axis {
+        let (dates, rows) = rows::extract_dated(dates, amounts, &columns, axis, &parser)?;
+        return rows.solve(
+            py,
+            silent,
+            |amounts| nan_policy.apply_payments(dates.clone(), amounts),
+            |(dates, amounts)| {
+                let dates = conversions::with_precision(dates, intraday);
+                core::xirr_compounded(&dates, &amounts, guess, day_count, compounding)
+            },
+        );
+    }
+
+    map_cash_flows(
+        dates,
+        amounts,
+        columns,
+        nan_policy,
+        &parser,
+        intraday,
+        silent,
+        |dates, amounts| {
+            py.allow_threads(move || {
+                let result = core::xirr_compounded(&dates, &amounts, guess, day_count, compounding);
+                fallible_float_or_none(result, silent)
+            })
+        },
+    )
+}
+
+/// The amount, date or scale factor for which the cash flows have the internal rate of return `rate`.
+#[pyfunction]
+#[pyo3(signature = (rate, dates, amounts=None, *, solve_for="amount", index=-1, silent=false, day_count=None, nan_policy=NanPolicy::Raise, compounding=core::Compounding::Annual, intraday=false, date_format=None, excel_serial=None))]
+#[pyo3(
+    text_signature = "(rate, dates, amounts=None, *, solve_for='amount', index=-1, silent=False, day_count=None, nan_policy='raise', compounding='annual', intraday=False, date_format=None, excel_serial=None)"
+)]
+#[allow(clippy::too_many_arguments)]
+fn xgoal_seek<'py>(
+    py: Python<'py>,
+    rate: f64,
+    dates: &Bound<'py, PyAny>,
+    amounts: Option<&Bound<'py, PyAny>>,
+    solve_for: &str,
+    index: isize,
+    silent: bool,
+    day_count: Option<PyDayCount>,
+    nan_policy: NanPolicy,
+    compounding: core::Compounding,
+    intraday: bool,
+    date_format: Option<&str>,
+    excel_serial: Option<&Bound<'py, PyAny>>,
+) -> PyResult<Bound<'py, PyAny>> {
+    if !matches!(solve_for, "amount" | "date" | "scale") {
+        return Err(PyValueError::new_err("solve_for must be one of: 'amount', 'date', 'scale'"));
+    }
+    if solve_for == "date" && intraday {
+        return Err(PyValueError::new_err(
+            "intraday=True is not supported with solve_for='date', the date is solved to a whole day",
+        ));
+    }
+
     let day_count = day_count.map(|x| x.try_into()).transpose()?;
+    let parser = DateParser::new(date_format, excel_serial)?;
+    let (dates, amounts) = conversions::extract_optional_payments(dates, amounts, &parser)?;
 
-    py.allow_threads(move || {
-        let result = core::xirr(&dates, &amounts, guess, day_count);
-        fallible_float_or_none(result, silent.unwrap_or(false))
-    })
+    if dates.len() != amounts.len() {
+        return Err(core::InvalidPaymentsError::LengthMismatch {
+            amounts: amounts.len(),
+            dates: dates.len(),
+        }
+        .into());
+    }
+    let index = resolve_index(index, amounts.len())?;
+
+    // the payment at `index` is the unknown one, `nan_policy` applies to the others
+    let unknown = (solve_for != "scale").then_some(index);
+    let (index, dates, amounts) = apply_nan_policy_except(nan_policy, dates, amounts, unknown)?;
+
+    // the date at `index` is unknown when solving for it, the placeholder is ignored
+    let placeholder = dates.iter().flatten().next().copied();
+    let dates = dates
+        .into_iter()
+        .enumerate()
+        .map(|(i, date)| match date {
+            None if solve_for == "date" && i == index => {
+                placeholder.ok_or(core::InvalidPaymentsError::Empty)
+            }
+            None => Err(core::InvalidPaymentsError::MissingDate {
+                index: i,
+            }),
+            Some(date) => Ok(date),
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .map(|dates| conversions::with_precision(dates, intraday));
+
+    let result = match solve_for {
+        "amount" => py.allow_threads(|| {
+            let result = dates.and_then(|dates| {
+                core::xgoal_seek_amount(rate, &dates, &amounts, index, day_count, compounding)
+            });
+            fallible_float_or_none(result, silent)
+        })?,
+        "scale" => py.allow_threads(|| {
+            let result = dates.and_then(|dates| {
+                core::xgoal_seek_scale(rate, &dates, &amounts, day_count, compounding)
+            });
+            fallible_float_or_none(result, silent)
+        })?,
+        _ => {
+            let result = py.allow_threads(|| {
+                dates.and_then(|dates| {
+                    core::xgoal_seek_date(rate, &dates, &amounts, index, day_count, compounding)
+                })
+            });
+            return match result {
+                Ok(date) => date.into_bound_py_any(py),
+                Err(_) if silent => Ok(py.None().into_bound(py)),
+                Err(e) => Err(e.into()),
+            };
+        }
+    };
+
+    result.into_bound_py_any(py)
+}
+
+/// Applies `nan_policy` to all the payments but the `unknown` one,
+/// returns the position of the unknown payment among the remaining ones.
+fn apply_nan_policy_except(
+    nan_policy: NanPolicy,
+    dates: Vec<Option<core::DateLike>>,
+    amounts: Vec<f64>,
+    unknown: Option<usize>,
+) -> PyResult<(usize, Vec<Option<core::DateLike>>, Vec<f64>)> {
+    let mut position = unknown.unwrap_or_default();
+    let mut result = (Vec::with_capacity(dates.len()), Vec::with_capacity(amounts.len()));
+
+    for (index, (date, amount)) in dates.into_iter().zip(amounts).enumerate() {
+        let amount = match (nan_policy, date, amount.is_nan()) {
+            _ if Some(index) == unknown => {
+                position = result.0.len();
+                amount
+            }
+            (NanPolicy::Raise, None, _) => {
+                return Err(core::InvalidPaymentsError::MissingDate {
+                    index,
+                }
+                .into())
+            }
+            (NanPolicy::Raise, _, true) => {
+                return Err(core::InvalidPaymentsError::NaN {
+                    index,
+                }
+                .into())
+            }
+            (_, None, _) | (NanPolicy::Omit, _, true) => continue,
+            (NanPolicy::Zero, _, true) => 0.0,
+            _ => amount,
+        };
+        result.0.push(date);
+        result.1.push(amount);
+    }
+
+    Ok((position, result.0, result.1))
+}
+
+/// Monte Carlo simulation of a dated cash flow: the distribution of XIRR, XNPV, TVPI and DPI.
+#[pyfunction]
+#[pyo3(signature = (rate, dates, amounts=None, *, scenarios=10_000, seed=None, shock=None, volatility=None, jitter_days=None, default_probability=None, nav=0.0, percentiles=vec![5.0, 25.0, 50.0, 75.0, 95.0], day_count=None, nan_policy=NanPolicy::Raise, compounding=core::Compounding::Annual, intraday=false, date_format=None, excel_serial=None))]
+#[pyo3(
+    text_signature = "(rate, dates, amounts=None, *, scenarios=10000, seed=None, shock=None, volatility=None, jitter_days=None, default_probability=None, nav=0.0, percentiles=(5, 25, 50, 75, 95), day_count=None, nan_policy='raise', compounding='annual', intraday=False, date_format=None, excel_serial=None)"
+)]
+#[allow(clippy::too_many_arguments)]
+fn monte_carlo<'py>(
+    py: Python<'py>,
+    rate: f64,
+    dates: &Bound<'py, PyAny>,
+    amounts: Option<&Bound<'py, PyAny>>,
+    scenarios: u64,
+    seed: Option<u64>,
+    shock: Option<&Bound<'py, PyAny>>,
+    volatility: Option<&Bound<'py, PyAny>>,
+    jitter_days: Option<&Bound<'py, PyAny>>,
+    default_probability: Option<&Bound<'py, PyAny>>,
+    nav: f64,
+    percentiles: Vec<f64>,
+    day_count: Option<PyDayCount>,
+    nan_policy: NanPolicy,
+    compounding: core::Compounding,
+    intraday: bool,
+    date_format: Option<&str>,
+    excel_serial: Option<&Bound<'py, PyAny>>,
+) -> PyResult<Bound<'py, PyDict>> {
+    if let Some(p) = percentiles.iter().find(|p| !(0.0..=100.0).contains(*p)) {
+        return Err(PyValueError::new_err(format!("percentile {p} is not between 0 and 100")));
+    }
+
+    let parser = DateParser::new(date_format, excel_serial)?;
+    let (dates, amounts) = conversions::extract_optional_payments(dates, amounts, &parser)?;
+    let (dates, amounts) = nan_policy.apply_payments(dates, amounts)?;
+    let dates = conversions::with_precision(dates, intraday);
+
+    let n = amounts.len();
+    let shock: Vec<String> = per_flow(shock, n, "shock", "normal".to_owned())?;
+    let shock = shock
+        .iter()
+        .map(|s| core::simulation::Shock::from_str(s).map_err(PyValueError::new_err))
+        .collect::<PyResult<Vec<_>>>()?;
+    let volatility = per_flow(volatility, n, "volatility", 0.0)?;
+    let jitter_days = per_flow(jitter_days, n, "jitter_days", 0.0)?;
+    let default_probability = per_flow(default_probability, n, "default_probability", 0.0)?;
+
+    let specs = (0..n)
+        .map(|i| core::simulation::FlowSpec {
+            shock: shock[i],
+            volatility: volatility[i],
+            jitter_days: jitter_days[i],
+            default_probability: default_probability[i],
+        })
+        .collect();
+    let terms = core::simulation::SimulationTerms {
+        rate,
+        nav,
+        day_count: day_count.map(|x| x.try_into()).transpose()?,
+        compounding,
+    };
+    let quantiles: Vec<f64> = percentiles.iter().map(|p| p / 100.0).collect();
+    let seed = seed.unwrap_or_else(|| {
+        let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH);
+        now.map_or(0, |d| d.as_nanos() as u64)
+    });
+
+    let (outcomes, summaries) = py.allow_threads(|| {
+        let simulation = core::simulation::Simulation::new(dates, amounts, specs, terms)?;
+        let outcomes = rows::par_map((0..scenarios).collect(), |i| simulation.outcome(seed, i));
+
+        let metrics: [Vec<f64>; 4] = [
+            outcomes.iter().map(|o| o.xirr).collect(),
+            outcomes.iter().map(|o| o.xnpv).collect(),
+            outcomes.iter().map(|o| o.tvpi).collect(),
+            outcomes.iter().map(|o| o.dpi).collect(),
+        ];
+        let summaries = metrics
+            .iter()
+            .map(|values| core::simulation::summarize(values, &quantiles))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok::<_, core::InvalidPaymentsError>((metrics, summaries))
+    })?;
+
+    let result = PyDict::new(py);
+    result.set_item("seed", seed)?;
+    for ((name, values), summary) in
+        ["xirr", "xnpv", "tvpi", "dpi"].iter().zip(outcomes).zip(summaries)
+    {
+        let dict = PyDict::new(py);
+        dict.set_item("mean", summary.mean)?;
+        dict.set_item("std", summary.std)?;
+        dict.set_item("min", summary.min)?;
+        dict.set_item("max", summary.max)?;
+        dict.set_item("failed", summary.failed)?;
+        let by_percentile = PyDict::new(py);
+        for (p, value) in percentiles.iter().zip(summary.percentiles) {
+            by_percentile.set_item(p, value)?;
+        }
+        dict.set_item("percentiles", by_percentile)?;
+        dict.set_item("values", values)?;
+        result.set_item(name, dict)?;
+    }
+
+    Ok(result)
+}
+
+/// A scalar (or `None` for the default) applies to every flow, a sequence gives one value per flow.
+fn per_flow<'py, T>(
+    ob: Option<&Bound<'py, PyAny>>,
+    n: usize,
+    name: &str,
+    default: T,
+) -> PyResult<Vec<T>>
+where
+    T: FromPyObject<'py> + Clone,
+{
+    let Some(ob) = ob else {
+        return Ok(vec![default; n]);
+    };
+    if let Ok(value) = ob.extract::<T>() {
+        return Ok(vec![value; n]);
+    }
+
+    let values: Vec<T> = ob.extract()?;
+    if values.len() != n {
+        return Err(PyValueError::new_err(format!(
+            "{name} has {} values for {n} payments",
+            values.len()
+        )));
+    }
+    Ok(values)
+}
+
+/// XNPV over a grid of discount rates (rows) and relative shocks of the distributions (columns).
+#[pyfunction]
+#[pyo3(signature = (rates, shocks, dates, amounts=None, *, flows=None, day_count=None, nan_policy=NanPolicy::Raise, compounding=core::Compounding::Annual, intraday=false, date_format=None, excel_serial=None))]
+#[pyo3(
+    text_signature = "(rates, shocks, dates, amounts=None, *, flows=None, day_count=None, nan_policy='raise', compounding='annual', intraday=False, date_format=None, excel_serial=None)"
+)]
+#[allow(clippy::too_many_arguments)]
+fn sensitivity_table<'py>(
+    py: Python<'py>,
+    rates: AmountArray,
+    shocks: AmountArray,
+    dates: &Bound<'py, PyAny>,
+    amounts: Option<&Bound<'py, PyAny>>,
+    flows: Option<Vec<isize>>,
+    day_count: Option<PyDayCount>,
+    nan_policy: NanPolicy,
+    compounding: core::Compounding,
+    intraday: bool,
+    date_format: Option<&str>,
+    excel_serial: Option<&Bound<'py, PyAny>>,
+) -> PyResult<Bound<'py, PyAny>> {
+    let day_count = day_count.map(|x| x.try_into()).transpose()?;
+    let parser = DateParser::new(date_format, excel_serial)?;
+    let (dates, amounts) = conversions::extract_optional_payments(dates, amounts, &parser)?;
+    let positions = kept_positions(nan_policy, &dates, &amounts);
+    let len = amounts.len();
+    let (dates, amounts) = nan_policy.apply_payments(dates, amounts)?;
+    let dates = conversions::with_precision(dates, intraday);
+
+    // the distributions are shocked by default, `flows` index the payments before omitting
+    let shocked: Vec<bool> = match flows {
+        None => amounts.iter().map(|&a| a > 0.0).collect(),
+        Some(flows) => {
+            let mut shocked = vec![false; len];
+            for index in flows {
+                shocked[resolve_index(index, len)?] = true;
+            }
+            positions.iter().map(|&i| shocked[i]).collect()
+        }
+    };
+
+    let table = py.allow_threads(|| {
+        let sensitivity = core::Sensitivity::new(&dates, &amounts, day_count, compounding)?;
+        Ok::<_, core::InvalidPaymentsError>(rows::par_map(rates.to_vec(), |rate| {
+            sensitivity.xnpv_row(rate, &shocks, &shocked)
+        }))
+    })?;
+
+    conversions::table_result(py, table, ("rate", &rates), ("shock", &shocks))
+}
+
+/// XIRR (or XNPV at `rate`) when each cash flow is scaled by `1 - shock` and `1 + shock`.
+#[pyfunction]
+#[pyo3(signature = (dates, amounts=None, *, shock=0.1, rate=None, day_count=None, nan_policy=NanPolicy::Raise, compounding=core::Compounding::Annual, intraday=false, date_format=None, excel_serial=None))]
+#[pyo3(
+    text_signature = "(dates, amounts=None, *, shock=0.1, rate=None, day_count=None, nan_policy='raise', compounding='annual', intraday=False, date_format=None, excel_serial=None)"
+)]
+#[allow(clippy::too_many_arguments)]
+fn tornado<'py>(
+    py: Python<'py>,
+    dates: &Bound<'py, PyAny>,
+    amounts: Option<&Bound<'py, PyAny>>,
+    shock: f64,
+    rate: Option<f64>,
+    day_count: Option<PyDayCount>,
+    nan_policy: NanPolicy,
+    compounding: core::Compounding,
+    intraday: bool,
+    date_format: Option<&str>,
+    excel_serial: Option<&Bound<'py, PyAny>>,
+) -> PyResult<Bound<'py, PyDict>> {
+    let day_count = day_count.map(|x| x.try_into()).transpose()?;
+    let parser = DateParser::new(date_format, excel_serial)?;
+    let (dates, amounts) = conversions::extract_optional_payments(dates, amounts, &parser)?;
+    let positions = kept_positions(nan_policy, &dates, &amounts);
+    let len = amounts.len();
+    let (dates, amounts) = nan_policy.apply_payments(dates, amounts)?;
+    let dates = conversions::with_precision(dates, intraday);
+
+    let result = py.allow_threads(|| {
+        let sensitivity = core::Sensitivity::new(&dates, &amounts, day_count, compounding)?;
+        let swings =
+            rows::par_map((0..amounts.len()).collect(), |i| sensitivity.swing(i, shock, rate));
+        Ok::<_, core::InvalidPaymentsError>(sensitivity.tornado_of(swings, rate))
+    })?;
+
+    // the omitted payments have no swing, they are NaN and last in the order
+    let (mut low, mut high) = (vec![f64::NAN; len], vec![f64::NAN; len]);
+    for (i, &position) in positions.iter().enumerate() {
+        low[position] = result.low[i];
+        high[position] = result.high[i];
+    }
+    let mut order: Vec<usize> = result.order.iter().map(|&i| positions[i]).collect();
+    order.extend((0..len).filter(|i| positions.binary_search(i).is_err()));
+
+    let dict = PyDict::new(py);
+    dict.set_item("base", result.base)?;
+    dict.set_item("low", low)?;
+    dict.set_item("high", high)?;
+    dict.set_item("order", order)?;
+    Ok(dict)
+}
+
+/// Positions of the payments that `nan_policy` keeps.
+fn kept_positions(
+    nan_policy: NanPolicy,
+    dates: &[Option<core::DateLike>],
+    amounts: &[f64],
+) -> Vec<usize> {
+    let omit = nan_policy == NanPolicy::Omit;
+    (0..dates.len().min(amounts.len()))
+        .filter(|&i| dates[i].is_some() && !(omit && amounts[i].is_nan()))
+        .collect()
+}
+
+/// A Python index (negative counts from the end) into `len` payments.
+fn resolve_index(index: isize, len: usize) -> PyResult<usize> {
+    let len = len as isize;
+    match index {
+        i if (0..len).contains(&i) => Ok(i as usize),
+        i if (-len..0).contains(&i) => Ok((len + i) as usize),
+        i => Err(PyIndexError::new_err(format!("index {i} is out of range for {len} payments"))),
+    }
 }
 
 /// Net Present Value for a non-periodic cash flows.
 #[pyfunction]
-#[pyo3(signature = (rate, dates, amounts=None, *, silent=false, day_count=None))]
-#[pyo3(text_signature = "(rate, dates, amounts=None, *, silent=False, day_count=None)")]
-fn xnpv<'a>(
-    py: Python<'a>,
-    rate: Arg<f64, 'a>,
-    dates: &Bound<PyAny>,
-    amounts: Option<&Bound<PyAny>>,
+#[pyo3(signature = (rate, dates, amounts=None, *, silent=false, day_count=None, nan_policy=NanPolicy::Raise, date_col=None, amount_col=None, by=None, intraday=false, date_format=None, excel_serial=None, decimal=false, axis=None, compounding=core::Compounding::Annual))]
+#[pyo3(
+    text_signature = "(rate, dates, amounts=None, *, silent=False, day_count=None, nan_policy='raise', date_col=None, amount_col=None, by=None, intraday=False, date_format=None, excel_serial=None, decimal=False, axis=None, compounding='annual')"
+)]
+#[allow(clippy::too_many_arguments)]
+fn xnpv<'py>(
+    py: Python<'py>,
+    rate: Arg<'py, f64>,
+    dates: &Bound<'py, PyAny>,
+    amounts: Option<&Bound<'py, PyAny>>,
     silent: Option<bool>,
     day_count: Option<PyDayCount>,
-) -> PyResult<Option<Arg<f64, 'a>>> {
-    let (dates, amounts) = conversions::extract_payments(dates, amounts)?;
-    let day_count = day_count.map(|x| x.try_into()).transpose()?;
+    nan_policy: NanPolicy,
+    date_col: Option<Bound<'py, PyAny>>,
+    amount_col: Option<Bound<'py, PyAny>>,
+    by: Option<Bound<'py, PyAny>>,
+    intraday: bool,
+    date_format: Option<&str>,
+    excel_serial: Option<&Bound<'py, PyAny>>,
+    decimal: bool,
+    axis: Option<isize>,
+    compounding: core::Compounding,
+) -> PyResult<Bound<'py, PyAny>> {
     let silent = silent.unwrap_or(false);
+    let day_count = day_count.map(|x| x.try_into()).transpose()?;
+    let columns = Columns {
+        date: date_col,
+        amount: amount_col,
+        by,
+    };
+    let parser = DateParser::new(date_format, excel_serial)?;
+
+    if let Some(axis) = axis {
+        let Arg::Scalar(rate) = rate else {
+            return Err(PyTypeError::new_err("axis requires a scalar rate"));
+        };
+        if decimal {
+            return Err(PyTypeError::new_err("decimal=True does not support axis"));
+        }
+        let (dates, rows) = rows::extract_dated(dates, amounts, &columns, axis, &parser)?;
+        return rows.solve(
+            py,
+            silent,
+            |amounts| nan_policy.apply_payments(dates.clone(), amounts),
+            |(dates, amounts)| {
+                let dates = conversions::with_precision(dates, intraday);
+                core::xnpv_compounded(rate, &dates, &amounts, day_count, compounding)
+            },
+        );
+    }
 
-    match rate {
-        Arg::Scalar(rate) => {
-            let result = py.allow_threads(move || core::xnpv(rate, &dates, &amounts, day_count));
-            match result {
-                Ok(rate) if rate.is_finite() => Ok(Some(Arg::Scalar(rate))),
-                Ok(_) => Ok(None),
-                Err(e) => {
-                    if silent {
-                        Ok(None)
-                    } else {
-                        Err(e.into())
+    if decimal {
+        let Arg::Scalar(rate) = rate else {
+            return Err(PyTypeError::new_err("decimal=True requires a scalar rate"));
+        };
+        if compounding != core::Compounding::Annual {
+            return Err(PyTypeError::new_err("decimal=True supports only annual compounding"));
+        }
+        if !columns.is_empty() {
+            return Err(PyTypeError::new_err(
+                "decimal=True does not support date_col, amount_col and by",
+            ));
+        }
+        let decimals = Decimals::new(py)?;
+        let result = decimal::extract_payments(&decimals, dates, amounts, &parser)
+            .and_then(|(dates, amounts)| {
+                decimals.apply_nan_policy_payments(nan_policy, dates, amounts)
+            })
+            .and_then(|(dates, amounts)| {
+                let dates = conversions::with_precision(dates, intraday);
+                decimals.xnpv(rate, &dates, &amounts, day_count)
+            });
+        return Ok(silent_invalid_payments(py, result, silent)?
+            .unwrap_or_else(|| py.None().into_bound(py)));
+    }
+
+    let has_numpy_array = matches!(rate, Arg::NumpyArray(_));
+    let scalar = if let Arg::Scalar(rate) = rate {
+        Some(rate)
+    } else {
+        None
+    };
+    let rates = rate.into_arrayd();
+
+    map_cash_flows(
+        dates,
+        amounts,
+        columns,
+        nan_policy,
+        &parser,
+        intraday,
+        silent,
+        |dates, amounts| {
+            if let Some(rate) = scalar {
+                let result = py.allow_threads(move || {
+                    core::xnpv_compounded(rate, &dates, &amounts, day_count, compounding)
+                });
+                return match result {
+                    Ok(rate) if rate.is_finite() => Ok(Some(Arg::Scalar(rate))),
+                    Ok(_) => Ok(None),
+                    Err(e) => {
+                        if silent {
+                            Ok(None)
+                        } else {
+                            Err(e.into())
+                        }
                     }
-                }
+                };
             }
-        }
-        rate => {
-            let has_numpy_array = matches!(rate, Arg::NumpyArray(_));
-            let rate = rate.into_arrayd();
+
+            let rates = rates.view();
             let result = py.allow_threads(move || {
-                let r = rate.mapv(|r| core::xnpv(r, &dates, &amounts, day_count));
+                let r = rates
+                    .mapv(|r| core::xnpv_compounded(r, &dates, &amounts, day_count, compounding));
 
                 if silent {
                     Ok(r.mapv(|e| e.unwrap_or(f64::NAN)))
@@ -144,24 +678,134 @@ fn xnpv<'a>(
                 result.map(Arg::from)
             };
             result.map(Some).map_err(|e| e.into())
-        }
+        },
+    )
+}
+
+/// The date when the cumulative (discounted) cash flows turn non-negative.
+#[pyfunction]
+#[pyo3(signature = (dates, amounts=None, *, rate=None, silent=false, day_count=None, nan_policy=NanPolicy::Raise, compounding=core::Compounding::Annual, intraday=false, date_format=None, excel_serial=None))]
+#[pyo3(
+    text_signature = "(dates, amounts=None, *, rate=None, silent=False, day_count=None, nan_policy='raise', compounding='annual', intraday=False, date_format=None, excel_serial=None)"
+)]
+#[allow(clippy::too_many_arguments)]
+fn xpayback<'py>(
+    py: Python<'py>,
+    dates: &Bound<'py, PyAny>,
+    amounts: Option<&Bound<'py, PyAny>>,
+    rate: Option<f64>,
+    silent: bool,
+    day_count: Option<PyDayCount>,
+    nan_policy: NanPolicy,
+    compounding: core::Compounding,
+    intraday: bool,
+    date_format: Option<&str>,
+    excel_serial: Option<&Bound<'py, PyAny>>,
+) -> PyResult<Bound<'py, PyAny>> {
+    let day_count = day_count.map(|x| x.try_into()).transpose()?;
+    let parser = DateParser::new(date_format, excel_serial)?;
+    let payments = conversions::extract_optional_payments(dates, amounts, &parser)
+        .and_then(|(dates, amounts)| nan_policy.apply_payments(dates, amounts));
+    let Some((dates, amounts)) = silent_invalid_payments(py, payments, silent)? else {
+        return Ok(py.None().into_bound(py));
+    };
+    let dates = conversions::with_precision(dates, intraday);
+
+    let result =
+        py.allow_threads(move || core::xpayback(&dates, &amounts, rate, day_count, compounding));
+
+    match result {
+        Ok(date) => date.into_bound_py_any(py),
+        Err(_) if silent => Ok(py.None().into_bound(py)),
+        Err(e) => Err(e.into()),
     }
 }
 
+/// XNPV at every rate and, given the `other` cash flow, the crossover rate of the two.
+#[pyfunction]
+#[pyo3(signature = (rates, dates, amounts=None, *, other=None, guess=None, day_count=None, nan_policy=NanPolicy::Raise, compounding=core::Compounding::Annual, intraday=false, date_format=None, excel_serial=None))]
+#[pyo3(
+    text_signature = "(rates, dates, amounts=None, *, other=None, guess=None, day_count=None, nan_policy='raise', compounding='annual', intraday=False, date_format=None, excel_serial=None)"
+)]
+#[allow(clippy::too_many_arguments)]
+fn xnpv_profile<'py>(
+    py: Python<'py>,
+    rates: AmountArray,
+    dates: &Bound<'py, PyAny>,
+    amounts: Option<&Bound<'py, PyAny>>,
+    other: Option<&Bound<'py, PyAny>>,
+    guess: Option<f64>,
+    day_count: Option<PyDayCount>,
+    nan_policy: NanPolicy,
+    compounding: core::Compounding,
+    intraday: bool,
+    date_format: Option<&str>,
+    excel_serial: Option<&Bound<'py, PyAny>>,
+) -> PyResult<Bound<'py, PyDict>> {
+    let day_count = day_count.map(|x| x.try_into()).transpose()?;
+    let parser = DateParser::new(date_format, excel_serial)?;
+    let extract = |dates, amounts| {
+        let (dates, amounts) = conversions::extract_optional_payments(dates, amounts, &parser)?;
+        let (dates, amounts) = nan_policy.apply_payments(dates, amounts)?;
+        Ok::<_, PyErr>((conversions::with_precision(dates, intraday), amounts))
+    };
+    let (dates, amounts) = extract(dates, amounts)?;
+    let other = other.map(|other| extract(other, None)).transpose()?;
+
+    let result = PyDict::new(py);
+    let profile = |dates: &[core::DateLike], amounts: &[f64]| {
+        py.allow_threads(|| core::xnpv_profile(&rates, dates, amounts, day_count, compounding))
+    };
+    result.set_item("npv", profile(&dates, &amounts)?)?;
+    if let Some((other_dates, other_amounts)) = other {
+        result.set_item("other", profile(&other_dates, &other_amounts)?)?;
+        let crossover = py.allow_threads(|| {
+            core::xcrossover_rate(
+                (&dates, &amounts),
+                (&other_dates, &other_amounts),
+                guess,
+                day_count,
+                compounding,
+            )
+        });
+        result.set_item("crossover_rate", crossover.ok().and_then(float_or_none))?;
+    }
+    Ok(result)
+}
+
 /// Internal Rate of Return
 #[pyfunction]
-#[pyo3(signature = (amounts, *, guess=None, silent=false))]
-#[pyo3(text_signature = "(amounts, *, guess=None, silent=False)")]
-fn irr(
-    py: Python,
-    amounts: AmountArray,
+#[pyo3(signature = (amounts, *, guess=None, silent=false, nan_policy=NanPolicy::Raise, axis=None))]
+#[pyo3(text_signature = "(amounts, *, guess=None, silent=False, nan_policy='raise', axis=None)")]
+fn irr<'py>(
+    py: Python<'py>,
+    amounts: &Bound<'py, PyAny>,
     guess: Option<f64>,
     silent: Option<bool>,
-) -> PyResult<Option<f64>> {
+    nan_policy: NanPolicy,
+    axis: Option<isize>,
+) -> PyResult<Bound<'py, PyAny>> {
+    let silent = silent.unwrap_or(false);
+
+    if let Some(axis) = axis {
+        return rows::Rows::extract(amounts, axis)?.solve(
+            py,
+            silent,
+            |amounts| nan_policy.apply(amounts),
+            |amounts| core::irr(&amounts, guess),
+        );
+    }
+
+    let amounts = nan_policy.apply(amounts.extract::<AmountArray>()?.into_vec());
+    let Some(amounts) = silent_invalid_payments(py, amounts, silent)? else {
+        return Ok(py.None().into_bound(py));
+    };
+
     py.allow_threads(move || {
         let result = core::irr(&amounts, guess);
-        fallible_float_or_none(result, silent.unwrap_or(false))
-    })
+        fallible_float_or_none(result, silent)
+    })?
+    .into_bound_py_any(py)
 }
 
 /// Net Present Value.
@@ -171,15 +815,46 @@ fn irr(
 /// By default, npv function starts from zero (numpy compatible),
 /// but you can call it with `start_from_zero=False` parameter to make it Excel compatible.
 #[pyfunction]
-#[pyo3(signature = (rate, amounts, *, start_from_zero=true))]
-#[pyo3(text_signature = "(rate, amounts, *, start_from_zero = True)")]
+#[pyo3(signature = (rate, amounts, *, start_from_zero=true, nan_policy=NanPolicy::Raise, decimal=false, axis=None))]
+#[pyo3(
+    text_signature = "(rate, amounts, *, start_from_zero = True, nan_policy='raise', decimal=False, axis=None)"
+)]
 fn npv<'a>(
     py: Python<'a>,
     rate: Arg<f64, 'a>,
-    amounts: AmountArray,
+    amounts: &Bound<'a, PyAny>,
     start_from_zero: Option<bool>,
-) -> Arg<f64, 'a> {
-    match rate {
+    nan_policy: NanPolicy,
+    decimal: bool,
+    axis: Option<isize>,
+) -> PyResult<Bound<'a, PyAny>> {
+    if let Some(axis) = axis {
+        let Arg::Scalar(rate) = rate else {
+            return Err(PyTypeError::new_err("axis requires a scalar rate"));
+        };
+        if decimal {
+            return Err(PyTypeError::new_err("decimal=True does not support axis"));
+        }
+        return rows::Rows::extract(amounts, axis)?.solve(
+            py,
+            false,
+            |amounts| nan_policy.apply(amounts),
+            |amounts| Ok(core::npv(rate, &amounts, start_from_zero)),
+        );
+    }
+
+    if decimal {
+        let Arg::Scalar(rate) = rate else {
+            return Err(PyTypeError::new_err("decimal=True requires a scalar rate"));
+        };
+        let decimals = Decimals::new(py)?;
+        let amounts = decimals.apply_nan_policy(nan_policy, decimals.extract(amounts)?)?;
+        return decimals.npv(rate, &amounts, start_from_zero.unwrap_or(true));
+    }
+
+    let amounts = nan_policy.apply(conversions::extract_amount_series(amounts)?)?;
+
+    let result = match rate {
         Arg::Scalar(rate) => {
             let result = py.allow_threads(move || core::npv(rate, &amounts, start_from_zero));
             Arg::Scalar(result)
@@ -196,24 +871,114 @@ fn npv<'a>(
                 py.allow_threads(move || rates.mapv(|r| core::npv(r, &amounts, start_from_zero)));
             Arg::from(numpy::ToPyArray::to_pyarray(&result, py))
         }
+    };
+
+    result.into_bound_py_any(py)
+}
+
+/// The number of periods until the cumulative cash flows turn non-negative.
+#[pyfunction]
+#[pyo3(signature = (amounts, *, nan_policy=NanPolicy::Raise))]
+#[pyo3(text_signature = "(amounts, *, nan_policy='raise')")]
+fn payback(py: Python, amounts: &Bound<PyAny>, nan_policy: NanPolicy) -> PyResult<Option<f64>> {
+    let amounts = nan_policy.apply(conversions::extract_amount_series(amounts)?)?;
+    Ok(float_or_none(py.allow_threads(move || core::payback(&amounts))))
+}
+
+/// The number of periods until the cumulative cash flows discounted at `rate` turn non-negative.
+#[pyfunction]
+#[pyo3(signature = (rate, amounts, *, nan_policy=NanPolicy::Raise))]
+#[pyo3(text_signature = "(rate, amounts, *, nan_policy='raise')")]
+fn discounted_payback(
+    py: Python,
+    rate: f64,
+    amounts: &Bound<PyAny>,
+    nan_policy: NanPolicy,
+) -> PyResult<Option<f64>> {
+    let amounts = nan_policy.apply(conversions::extract_amount_series(amounts)?)?;
+    Ok(float_or_none(py.allow_threads(move || core::discounted_payback(rate, &amounts))))
+}
+
+/// Present value of the inflows divided by the present value of the outflows.
+#[pyfunction]
+#[pyo3(signature = (rate, amounts, *, start_from_zero=true, nan_policy=NanPolicy::Raise))]
+#[pyo3(text_signature = "(rate, amounts, *, start_from_zero=True, nan_policy='raise')")]
+fn profitability_index(
+    py: Python,
+    rate: f64,
+    amounts: &Bound<PyAny>,
+    start_from_zero: Option<bool>,
+    nan_policy: NanPolicy,
+) -> PyResult<f64> {
+    let amounts = nan_policy.apply(conversions::extract_amount_series(amounts)?)?;
+    py.allow_threads(move || core::profitability_index(rate, &amounts, start_from_zero))
+        .map_err(|e| e.into())
+}
+
+/// Equivalent annual annuity: the level payment over the life of the project with the same NPV.
+#[pyfunction]
+#[pyo3(signature = (rate, amounts, *, start_from_zero=true, nan_policy=NanPolicy::Raise))]
+#[pyo3(text_signature = "(rate, amounts, *, start_from_zero=True, nan_policy='raise')")]
+fn eaa(
+    py: Python,
+    rate: f64,
+    amounts: &Bound<PyAny>,
+    start_from_zero: Option<bool>,
+    nan_policy: NanPolicy,
+) -> PyResult<Option<f64>> {
+    let amounts = nan_policy.apply(conversions::extract_amount_series(amounts)?)?;
+    Ok(float_or_none(py.allow_threads(move || core::eaa(rate, &amounts, start_from_zero))))
+}
+
+/// NPV at every rate and, given the `other` project, the crossover rate of the two projects.
+#[pyfunction]
+#[pyo3(signature = (rates, amounts, *, other=None, start_from_zero=true, guess=None, nan_policy=NanPolicy::Raise))]
+#[pyo3(
+    text_signature = "(rates, amounts, *, other=None, start_from_zero=True, guess=None, nan_policy='raise')"
+)]
+fn npv_profile<'py>(
+    py: Python<'py>,
+    rates: AmountArray,
+    amounts: &Bound<'py, PyAny>,
+    other: Option<&Bound<'py, PyAny>>,
+    start_from_zero: Option<bool>,
+    guess: Option<f64>,
+    nan_policy: NanPolicy,
+) -> PyResult<Bound<'py, PyDict>> {
+    let amounts = nan_policy.apply(conversions::extract_amount_series(amounts)?)?;
+    let other = other
+        .map(|other| nan_policy.apply(conversions::extract_amount_series(other)?))
+        .transpose()?;
+
+    let result = PyDict::new(py);
+    let profile =
+        |amounts: &[f64]| py.allow_threads(|| core::npv_profile(&rates, amounts, start_from_zero));
+    result.set_item("npv", profile(&amounts))?;
+    if let Some(other) = other {
+        result.set_item("other", profile(&other))?;
+        let crossover = py.allow_threads(|| core::crossover_rate(&amounts, &other, guess));
+        result.set_item("crossover_rate", float_or_none(crossover.unwrap_or(f64::NAN)))?;
     }
+    Ok(result)
 }
 
 /// Future Value.
 #[pyfunction]
-#[pyo3(signature = (rate, nper, pmt, pv, *, pmt_at_beginning=Arg::Scalar(false)))]
-#[pyo3(text_signature = "(rate, nper, pmt, pv, *, pmt_at_beginning=False)")]
+#[pyo3(signature = (rate, nper, pmt, pv, *, pmt_at_beginning=Labeled::from(Arg::Scalar(false)), keep_dtype=false))]
+#[pyo3(text_signature = "(rate, nper, pmt, pv, *, pmt_at_beginning=False, keep_dtype=False)")]
 fn fv<'a>(
     py: Python<'a>,
-    rate: Arg<'a, f64>,
-    nper: Arg<'a, f64>,
-    pmt: Arg<'a, f64>,
-    pv: Arg<'a, f64>,
-    pmt_at_beginning: Arg<'a, bool>,
-) -> PyResult<Arg<'a, f64>> {
+    rate: Labeled<'a, f64>,
+    nper: Labeled<'a, f64>,
+    pmt: Labeled<'a, f64>,
+    pv: Labeled<'a, f64>,
+    pmt_at_beginning: Labeled<'a, bool>,
+    keep_dtype: bool,
+) -> PyResult<Bound<'a, PyAny>> {
     dispatch_vectorized!(
         py,
         (rate, nper, pmt, pv, pmt_at_beginning),
+        keep_dtype,
         core::fv(rate, nper, pmt, pv, pmt_at_beginning),
         core::fv_vec(&rate, &nper, &pmt, &pv, &pmt_at_beginning)
     )
@@ -221,8 +986,16 @@ fn fv<'a>(
 
 /// Net Future Value.
 #[pyfunction]
-#[pyo3(text_signature = "(rate, nper, amounts)")]
-fn nfv(py: Python, rate: f64, nper: f64, amounts: AmountArray) -> PyResult<Option<f64>> {
+#[pyo3(signature = (rate, nper, amounts, *, nan_policy=NanPolicy::Raise))]
+#[pyo3(text_signature = "(rate, nper, amounts, *, nan_policy='raise')")]
+fn nfv(
+    py: Python,
+    rate: f64,
+    nper: f64,
+    amounts: AmountArray,
+    nan_policy: NanPolicy,
+) -> PyResult<Option<f64>> {
+    let amounts = nan_policy.apply(amounts.into_vec())?;
     py.allow_threads(move || Ok(float_or_none(core::nfv(rate, nper, &amounts))))
 }
 
@@ -230,8 +1003,8 @@ fn nfv(py: Python, rate: f64, nper: f64, amounts: AmountArray) -> PyResult<Optio
 /// Future value of a cash flow between two dates.
 #[pyfunction]
 #[pyo3(
-    signature = (start_date, cash_flow_date, end_date, cash_flow_rate, end_rate, cash_flow, *, day_count=None),
-    text_signature = "(start_date, cash_flow_date, end_date, cash_flow_rate, end_rate, cash_flow, * day_count=None)"
+    signature = (start_date, cash_flow_date, end_date, cash_flow_rate, end_rate, cash_flow, *, day_count=None, compounding=core::Compounding::Annual),
+    text_signature = "(start_date, cash_flow_date, end_date, cash_flow_rate, end_rate, cash_flow, *, day_count=None, compounding='annual')"
 )]
 #[allow(clippy::too_many_arguments)]
 fn xfv(
@@ -243,11 +1016,12 @@ fn xfv(
     end_rate: f64,
     cash_flow: f64,
     day_count: Option<PyDayCount>,
+    compounding: core::Compounding,
 ) -> PyResult<Option<f64>> {
     let day_count = day_count.map(|x| x.try_into()).transpose()?;
 
     py.allow_threads(move || {
-        let result = core::xfv(
+        let result = core::xfv_compounded(
             &start_date,
             &cash_flow_date,
             &end_date,
@@ -255,6 +1029,7 @@ fn xfv(
             end_rate,
             cash_flow,
             day_count,
+            compounding,
         );
         Ok(float_or_none(result))
     })
@@ -262,39 +1037,70 @@ fn xfv(
 
 /// Net future value of a series of irregular cash flows
 #[pyfunction]
-#[pyo3(signature = (rate, dates, amounts=None, *, silent=false, day_count=None))]
-#[pyo3(text_signature = "(rate, dates, amounts=None, *, silent=False, day_count=None)")]
-fn xnfv(
-    py: Python,
+#[pyo3(signature = (rate, dates, amounts=None, *, silent=false, day_count=None, nan_policy=NanPolicy::Raise, date_col=None, amount_col=None, by=None, intraday=false, date_format=None, excel_serial=None, compounding=core::Compounding::Annual))]
+#[pyo3(
+    text_signature = "(rate, dates, amounts=None, *, silent=False, day_count=None, nan_policy='raise', date_col=None, amount_col=None, by=None, intraday=False, date_format=None, excel_serial=None, compounding='annual')"
+)]
+#[allow(clippy::too_many_arguments)]
+fn xnfv<'py>(
+    py: Python<'py>,
     rate: f64,
-    dates: &Bound<PyAny>,
-    amounts: Option<&Bound<PyAny>>,
+    dates: &Bound<'py, PyAny>,
+    amounts: Option<&Bound<'py, PyAny>>,
     silent: Option<bool>,
     day_count: Option<PyDayCount>,
-) -> PyResult<Option<f64>> {
-    let (dates, amounts) = conversions::extract_payments(dates, amounts)?;
+    nan_policy: NanPolicy,
+    date_col: Option<Bound<'py, PyAny>>,
+    amount_col: Option<Bound<'py, PyAny>>,
+    by: Option<Bound<'py, PyAny>>,
+    intraday: bool,
+    date_format: Option<&str>,
+    excel_serial: Option<&Bound<'py, PyAny>>,
+    compounding: core::Compounding,
+) -> PyResult<Bound<'py, PyAny>> {
+    let silent = silent.unwrap_or(false);
     let day_count = day_count.map(|x| x.try_into()).transpose()?;
-    py.allow_threads(move || {
-        let result = core::xnfv(rate, &dates, &amounts, day_count);
-        fallible_float_or_none(result, silent.unwrap_or(false))
-    })
+    let columns = Columns {
+        date: date_col,
+        amount: amount_col,
+        by,
+    };
+    let parser = DateParser::new(date_format, excel_serial)?;
+
+    map_cash_flows(
+        dates,
+        amounts,
+        columns,
+        nan_policy,
+        &parser,
+        intraday,
+        silent,
+        |dates, amounts| {
+            py.allow_threads(move || {
+                let result = core::xnfv_compounded(rate, &dates, &amounts, day_count, compounding);
+                fallible_float_or_none(result, silent)
+            })
+        },
+    )
 }
 
 /// Present Value
 #[pyfunction]
-#[pyo3(signature = (rate, nper, pmt, fv=Arg::Scalar(0.0), *, pmt_at_beginning=Arg::Scalar(false)))]
-#[pyo3(text_signature = "(rate, nper, pmt, fv=0, *, pmt_at_beginning=False)")]
+#[pyo3(signature = (rate, nper, pmt, fv=Labeled::from(Arg::Scalar(0.0)), *, pmt_at_beginning=Labeled::from(Arg::Scalar(false)), keep_dtype=false))]
+#[pyo3(text_signature = "(rate, nper, pmt, fv=0, *, pmt_at_beginning=False, keep_dtype=False)")]
 fn pv<'a>(
     py: Python<'a>,
-    rate: Arg<'a, f64>,
-    nper: Arg<'a, f64>,
-    pmt: Arg<'a, f64>,
-    fv: Arg<'a, f64>,
-    pmt_at_beginning: Arg<'a, bool>,
-) -> PyResult<Arg<'a, f64>> {
+    rate: Labeled<'a, f64>,
+    nper: Labeled<'a, f64>,
+    pmt: Labeled<'a, f64>,
+    fv: Labeled<'a, f64>,
+    pmt_at_beginning: Labeled<'a, bool>,
+    keep_dtype: bool,
+) -> PyResult<Bound<'a, PyAny>> {
     dispatch_vectorized!(
         py,
         (rate, nper, pmt, fv, pmt_at_beginning),
+        keep_dtype,
         core::pv(rate, nper, pmt, fv, pmt_at_beginning),
         core::pv_vec(&rate, &nper, &pmt, &fv, &pmt_at_beginning)
     )
@@ -302,36 +1108,59 @@ fn pv<'a>(
 
 /// Modified Internal Rate of Return.
 #[pyfunction]
-#[pyo3(signature = (amounts, finance_rate, reinvest_rate, *, silent=false))]
-#[pyo3(text_signature = "(amounts, finance_rate, reinvest_rate, *, silent=False)")]
-fn mirr(
-    py: Python,
-    amounts: AmountArray,
+#[pyo3(signature = (amounts, finance_rate, reinvest_rate, *, silent=false, nan_policy=NanPolicy::Raise, axis=None))]
+#[pyo3(
+    text_signature = "(amounts, finance_rate, reinvest_rate, *, silent=False, nan_policy='raise', axis=None)"
+)]
+fn mirr<'py>(
+    py: Python<'py>,
+    amounts: &Bound<'py, PyAny>,
     finance_rate: f64,
     reinvest_rate: f64,
     silent: Option<bool>,
-) -> PyResult<Option<f64>> {
+    nan_policy: NanPolicy,
+    axis: Option<isize>,
+) -> PyResult<Bound<'py, PyAny>> {
+    let silent = silent.unwrap_or(false);
+
+    if let Some(axis) = axis {
+        return rows::Rows::extract(amounts, axis)?.solve(
+            py,
+            silent,
+            |amounts| nan_policy.apply(amounts),
+            |amounts| core::mirr(&amounts, finance_rate, reinvest_rate),
+        );
+    }
+
+    let amounts = nan_policy.apply(amounts.extract::<AmountArray>()?.into_vec());
+    let Some(amounts) = silent_invalid_payments(py, amounts, silent)? else {
+        return Ok(py.None().into_bound(py));
+    };
+
     py.allow_threads(move || {
         let result = core::mirr(&amounts, finance_rate, reinvest_rate);
-        fallible_float_or_none(result, silent.unwrap_or(false))
-    })
+        fallible_float_or_none(result, silent)
+    })?
+    .into_bound_py_any(py)
 }
 
 /// Compute the payment against loan principal plus interest.
 #[pyfunction]
-#[pyo3(signature = (rate, nper, pv, fv=Arg::Scalar(0.0), *, pmt_at_beginning=Arg::Scalar(false)))]
-#[pyo3(text_signature = "(rate, nper, pv, fv=0, *, pmt_at_beginning=False)")]
+#[pyo3(signature = (rate, nper, pv, fv=Labeled::from(Arg::Scalar(0.0)), *, pmt_at_beginning=Labeled::from(Arg::Scalar(false)), keep_dtype=false))]
+#[pyo3(text_signature = "(rate, nper, pv, fv=0, *, pmt_at_beginning=False, keep_dtype=False)")]
 fn pmt<'a>(
     py: Python<'a>,
-    rate: Arg<'a, f64>,
-    nper: Arg<'a, f64>,
-    pv: Arg<'a, f64>,
-    fv: Arg<'a, f64>,
-    pmt_at_beginning: Arg<'a, bool>,
-) -> PyResult<Arg<'a, f64>> {
+    rate: Labeled<'a, f64>,
+    nper: Labeled<'a, f64>,
+    pv: Labeled<'a, f64>,
+    fv: Labeled<'a, f64>,
+    pmt_at_beginning: Labeled<'a, bool>,
+    keep_dtype: bool,
+) -> PyResult<Bound<'a, PyAny>> {
     dispatch_vectorized!(
         py,
         (rate, nper, pv, fv, pmt_at_beginning),
+        keep_dtype,
         core::pmt(rate, nper, pv, fv, pmt_at_beginning),
         core::pmt_vec(&rate, &nper, &pv, &fv, &pmt_at_beginning)
     )
@@ -339,20 +1168,23 @@ fn pmt<'a>(
 
 /// Compute the interest portion of a payment.
 #[pyfunction]
-#[pyo3(signature = (rate, per, nper, pv, fv=Arg::Scalar(0.0), *, pmt_at_beginning=Arg::Scalar(false)))]
-#[pyo3(text_signature = "(rate, per, nper, pv, fv=0, *, pmt_at_beginning=False)")]
+#[pyo3(signature = (rate, per, nper, pv, fv=Labeled::from(Arg::Scalar(0.0)), *, pmt_at_beginning=Labeled::from(Arg::Scalar(false)), keep_dtype=false))]
+#[pyo3(text_signature = "(rate, per, nper, pv, fv=0, *, pmt_at_beginning=False, keep_dtype=False)")]
+#[allow(clippy::too_many_arguments)]
 fn ipmt<'a>(
     py: Python<'a>,
-    rate: Arg<'a, f64>,
-    per: Arg<'a, f64>,
-    nper: Arg<'a, f64>,
-    pv: Arg<'a, f64>,
-    fv: Arg<'a, f64>,
-    pmt_at_beginning: Arg<'a, bool>,
-) -> PyResult<Arg<'a, f64>> {
+    rate: Labeled<'a, f64>,
+    per: Labeled<'a, f64>,
+    nper: Labeled<'a, f64>,
+    pv: Labeled<'a, f64>,
+    fv: Labeled<'a, f64>,
+    pmt_at_beginning: Labeled<'a, bool>,
+    keep_dtype: bool,
+) -> PyResult<Bound<'a, PyAny>> {
     dispatch_vectorized!(
         py,
         (rate, per, nper, pv, fv, pmt_at_beginning),
+        keep_dtype,
         core::ipmt(rate, per, nper, pv, fv, pmt_at_beginning),
         core::ipmt_vec(&rate, &per, &nper, &pv, &fv, &pmt_at_beginning)
     )
@@ -360,20 +1192,23 @@ fn ipmt<'a>(
 
 /// Compute the payment against loan principal.
 #[pyfunction]
-#[pyo3(signature = (rate, per, nper, pv, fv=Arg::Scalar(0.0), *, pmt_at_beginning=Arg::Scalar(false)))]
-#[pyo3(text_signature = "(rate, per, nper, pv, fv=0, *, pmt_at_beginning=False)")]
+#[pyo3(signature = (rate, per, nper, pv, fv=Labeled::from(Arg::Scalar(0.0)), *, pmt_at_beginning=Labeled::from(Arg::Scalar(false)), keep_dtype=false))]
+#[pyo3(text_signature = "(rate, per, nper, pv, fv=0, *, pmt_at_beginning=False, keep_dtype=False)")]
+#[allow(clippy::too_many_arguments)]
 fn ppmt<'a>(
     py: Python<'a>,
-    rate: Arg<'a, f64>,
-    per: Arg<'a, f64>,
-    nper: Arg<'a, f64>,
-    pv: Arg<'a, f64>,
-    fv: Arg<'a, f64>,
-    pmt_at_beginning: Arg<'a, bool>,
-) -> PyResult<Arg<'a, f64>> {
+    rate: Labeled<'a, f64>,
+    per: Labeled<'a, f64>,
+    nper: Labeled<'a, f64>,
+    pv: Labeled<'a, f64>,
+    fv: Labeled<'a, f64>,
+    pmt_at_beginning: Labeled<'a, bool>,
+    keep_dtype: bool,
+) -> PyResult<Bound<'a, PyAny>> {
     dispatch_vectorized!(
         py,
         (rate, per, nper, pv, fv, pmt_at_beginning),
+        keep_dtype,
         core::ppmt(rate, per, nper, pv, fv, pmt_at_beginning),
         core::ppmt_vec(&rate, &per, &nper, &pv, &fv, &pmt_at_beginning)
     )
@@ -381,19 +1216,21 @@ fn ppmt<'a>(
 
 /// Compute the number of periodic payments.
 #[pyfunction]
-#[pyo3(signature = (rate, pmt, pv, fv=Arg::Scalar(0.0), *, pmt_at_beginning=Arg::Scalar(false)))]
-#[pyo3(text_signature = "(rate, pmt, pv, fv=0, *, pmt_at_beginning=False)")]
+#[pyo3(signature = (rate, pmt, pv, fv=Labeled::from(Arg::Scalar(0.0)), *, pmt_at_beginning=Labeled::from(Arg::Scalar(false)), keep_dtype=false))]
+#[pyo3(text_signature = "(rate, pmt, pv, fv=0, *, pmt_at_beginning=False, keep_dtype=False)")]
 fn nper<'a>(
     py: Python<'a>,
-    rate: Arg<'a, f64>,
-    pmt: Arg<'a, f64>,
-    pv: Arg<'a, f64>,
-    fv: Arg<'a, f64>,
-    pmt_at_beginning: Arg<'a, bool>,
-) -> PyResult<Arg<'a, f64>> {
+    rate: Labeled<'a, f64>,
+    pmt: Labeled<'a, f64>,
+    pv: Labeled<'a, f64>,
+    fv: Labeled<'a, f64>,
+    pmt_at_beginning: Labeled<'a, bool>,
+    keep_dtype: bool,
+) -> PyResult<Bound<'a, PyAny>> {
     dispatch_vectorized!(
         py,
         (rate, pmt, pv, fv, pmt_at_beginning),
+        keep_dtype,
         core::nper(rate, pmt, pv, fv, pmt_at_beginning),
         core::nper_vec(&rate, &pmt, &pv, &fv, &pmt_at_beginning)
     )
@@ -401,20 +1238,25 @@ fn nper<'a>(
 
 /// Compute the number of periodic payments.
 #[pyfunction]
-#[pyo3(signature = (nper, pmt, pv, fv=Arg::Scalar(0.0), *, pmt_at_beginning=Arg::Scalar(false), guess=None))]
-#[pyo3(text_signature = "(nper, pmt, pv, fv=0, *, pmt_at_beginning=False, guess=None)")]
+#[pyo3(signature = (nper, pmt, pv, fv=Labeled::from(Arg::Scalar(0.0)), *, pmt_at_beginning=Labeled::from(Arg::Scalar(false)), guess=None, keep_dtype=false))]
+#[pyo3(
+    text_signature = "(nper, pmt, pv, fv=0, *, pmt_at_beginning=False, guess=None, keep_dtype=False)"
+)]
+#[allow(clippy::too_many_arguments)]
 fn rate<'a>(
     py: Python<'a>,
-    nper: Arg<'a, f64>,
-    pmt: Arg<'a, f64>,
-    pv: Arg<'a, f64>,
-    fv: Arg<'a, f64>,
-    pmt_at_beginning: Arg<'a, bool>,
+    nper: Labeled<'a, f64>,
+    pmt: Labeled<'a, f64>,
+    pv: Labeled<'a, f64>,
+    fv: Labeled<'a, f64>,
+    pmt_at_beginning: Labeled<'a, bool>,
     guess: Option<f64>,
-) -> PyResult<Arg<'a, f64>> {
+    keep_dtype: bool,
+) -> PyResult<Bound<'a, PyAny>> {
     dispatch_vectorized!(
         py,
         (nper, pmt, pv, fv, pmt_at_beginning),
+        keep_dtype,
         core::rate(nper, pmt, pv, fv, pmt_at_beginning, guess),
         core::rate_vec(&nper, &pmt, &pv, &fv, &pmt_at_beginning, guess)
     )
@@ -463,8 +1305,19 @@ fn cumipmt(
 }
 
 #[pyfunction]
-fn year_fraction(d1: core::DateLike, d2: core::DateLike, day_count: PyDayCount) -> PyResult<f64> {
-    Ok(core::year_fraction(&d1, &d2, day_count.try_into()?))
+#[pyo3(signature = (d1, d2, day_count, *, intraday=false, date_format=None, excel_serial=None))]
+fn year_fraction(
+    d1: &Bound<PyAny>,
+    d2: &Bound<PyAny>,
+    day_count: PyDayCount,
+    intraday: bool,
+    date_format: Option<&str>,
+    excel_serial: Option<&Bound<PyAny>>,
+) -> PyResult<f64> {
+    let parser = DateParser::new(date_format, excel_serial)?;
+    let dates = vec![parser.extract(d1)?, parser.extract(d2)?];
+    let dates = conversions::with_precision(dates, intraday);
+    Ok(core::year_fraction(&dates[0], &dates[1], day_count.try_into()?))
 }
 
 #[pyfunction]
@@ -487,11 +1340,22 @@ fn zero_crossing_points(cf: AmountArray) -> Vec<usize> {
 }
 
 mod pe {
+    use std::str::FromStr;
+
     use crate::{
-        conversions::{fallible_float_or_none, AmountArray},
-        core::private_equity,
+        broadcasting::{broadcast_series, Arg, Series},
+        conversions::{
+            extract_date_series, extract_payments, float_or_none, AmountArray, DateParser,
+            NanPolicy, PyDayCount,
+        },
+        core::{private_equity, DateLike},
+        decimal::Decimals,
+    };
+    use pyo3::{
+        exceptions::{PyTypeError, PyValueError},
+        prelude::*,
+        types::PyDict,
     };
-    use pyo3::prelude::*;
 
     pub fn module(_py: Python, m: &Bound<PyModule>) -> PyResult<()> {
         m.add_function(wrap_pyfunction!(dpi, m)?)?;
@@ -519,192 +1383,344 @@ mod pe {
         m.add_function(wrap_pyfunction!(ln_pme_2, m)?)?;
         m.add_function(wrap_pyfunction!(direct_alpha, m)?)?;
         m.add_function(wrap_pyfunction!(direct_alpha_2, m)?)?;
+        m.add_function(wrap_pyfunction!(takahashi_alexander, m)?)?;
+        m.add_function(wrap_pyfunction!(takahashi_alexander_calibrate, m)?)?;
+        m.add_function(wrap_pyfunction!(waterfall, m)?)?;
+        m.add_function(wrap_pyfunction!(management_fees, m)?)?;
+        m.add_function(wrap_pyfunction!(pooled_irr, m)?)?;
+        m.add_function(wrap_pyfunction!(pooled_dpi, m)?)?;
+        m.add_function(wrap_pyfunction!(pooled_rvpi, m)?)?;
+        m.add_function(wrap_pyfunction!(pooled_tvpi, m)?)?;
+        m.add_function(wrap_pyfunction!(pooled_metrics, m)?)?;
+        m.add_function(wrap_pyfunction!(percentile_rank, m)?)?;
+        m.add_function(wrap_pyfunction!(quartile, m)?)?;
+        m.add_function(wrap_pyfunction!(quartile_breakpoints, m)?)?;
+        m.add_function(wrap_pyfunction!(benchmark, m)?)?;
 
         Ok(())
     }
 
+    /// A cash flow series (or an array of them) with `nan_policy` applied.
+    fn series<'py>(obj: &Bound<'py, PyAny>, nan_policy: NanPolicy) -> PyResult<Series<'py>> {
+        obj.extract::<Series>()?.apply_nan_policy(nan_policy)
+    }
+
+    /// The amounts of the decimal mode with `nan_policy` applied.
+    fn decimal_amounts<'py>(
+        decimals: &Decimals<'py>,
+        obj: &Bound<'py, PyAny>,
+        nan_policy: NanPolicy,
+    ) -> PyResult<Vec<Bound<'py, PyAny>>> {
+        decimals.apply_nan_policy(nan_policy, decimals.extract(obj)?)
+    }
+
+    /// The scalar NAV of the decimal mode.
+    fn scalar_nav(nav: Arg<f64>) -> PyResult<f64> {
+        match nav {
+            Arg::Scalar(nav) => Ok(nav),
+            _ => Err(PyTypeError::new_err("decimal=True requires a scalar nav")),
+        }
+    }
+
     #[pyfunction]
+    #[pyo3(signature = (amounts, *, nan_policy=NanPolicy::Raise, decimal=false))]
     #[doc = include_str!("../docs/_inline/pe/dpi.md")]
-    fn dpi(py: Python, amounts: AmountArray) -> PyResult<f64> {
-        py.allow_threads(move || Ok(private_equity::dpi(&amounts)?))
+    fn dpi<'py>(
+        py: Python<'py>,
+        amounts: &Bound<'py, PyAny>,
+        nan_policy: NanPolicy,
+        decimal: bool,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        if decimal {
+            let decimals = Decimals::new(py)?;
+            return decimals.dpi(&decimal_amounts(&decimals, amounts, nan_policy)?);
+        }
+        let amounts = series(amounts, nan_policy)?;
+        broadcast_series(py, [amounts], [], |[amounts], []| private_equity::dpi(amounts))?
+            .into_pyobject(py)
     }
 
     #[pyfunction]
+    #[pyo3(signature = (contributions, distributions, *, nan_policy=NanPolicy::Raise, decimal=false))]
     #[doc = include_str!("../docs/_inline/pe/dpi.md")]
-    fn dpi_2(py: Python, contributions: AmountArray, distributions: AmountArray) -> PyResult<f64> {
-        py.allow_threads(move || Ok(private_equity::dpi_2(&contributions, &distributions)?))
+    fn dpi_2<'py>(
+        py: Python<'py>,
+        contributions: &Bound<'py, PyAny>,
+        distributions: &Bound<'py, PyAny>,
+        nan_policy: NanPolicy,
+        decimal: bool,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        if decimal {
+            let decimals = Decimals::new(py)?;
+            return decimals.dpi_2(
+                &decimal_amounts(&decimals, contributions, nan_policy)?,
+                &decimal_amounts(&decimals, distributions, nan_policy)?,
+            );
+        }
+        let series = [series(contributions, nan_policy)?, series(distributions, nan_policy)?];
+        broadcast_series(py, series, [], |[contributions, distributions], []| {
+            private_equity::dpi_2(contributions, distributions)
+        })?
+        .into_pyobject(py)
     }
 
     #[pyfunction]
+    #[pyo3(signature = (contributions, nav, *, nan_policy=NanPolicy::Raise, decimal=false))]
     #[doc = include_str!("../docs/_inline/pe/rvpi.md")]
-    fn rvpi(py: Python, contributions: AmountArray, nav: f64) -> PyResult<f64> {
-        py.allow_threads(move || Ok(private_equity::rvpi(&contributions, nav)?))
+    fn rvpi<'py>(
+        py: Python<'py>,
+        contributions: &Bound<'py, PyAny>,
+        nav: Arg<'py, f64>,
+        nan_policy: NanPolicy,
+        decimal: bool,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        if decimal {
+            let decimals = Decimals::new(py)?;
+            let contributions = decimal_amounts(&decimals, contributions, nan_policy)?;
+            return decimals.rvpi(&contributions, scalar_nav(nav)?);
+        }
+        let contributions = series(contributions, nan_policy)?;
+        broadcast_series(py, [contributions], [nav], |[contributions], [nav]| {
+            private_equity::rvpi(contributions, nav)
+        })?
+        .into_pyobject(py)
     }
 
     #[pyfunction]
+    #[pyo3(signature = (amounts, nav=None, *, nan_policy=NanPolicy::Raise, decimal=false))]
     #[doc = include_str!("../docs/_inline/pe/tvpi.md")]
-    pub fn tvpi(py: Python, amounts: AmountArray, nav: Option<f64>) -> PyResult<f64> {
-        py.allow_threads(move || Ok(private_equity::tvpi(&amounts, nav.unwrap_or(0.0))?))
+    pub fn tvpi<'py>(
+        py: Python<'py>,
+        amounts: &Bound<'py, PyAny>,
+        nav: Option<Arg<'py, f64>>,
+        nan_policy: NanPolicy,
+        decimal: bool,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let nav = nav.unwrap_or(Arg::Scalar(0.0));
+        if decimal {
+            let decimals = Decimals::new(py)?;
+            let amounts = decimal_amounts(&decimals, amounts, nan_policy)?;
+            return decimals.tvpi(&amounts, scalar_nav(nav)?);
+        }
+        let amounts = series(amounts, nan_policy)?;
+        broadcast_series(py, [amounts], [nav], |[amounts], [nav]| {
+            private_equity::tvpi(amounts, nav)
+        })?
+        .into_pyobject(py)
     }
 
     #[pyfunction]
+    #[pyo3(signature = (contributions, distributions, nav=None, *, nan_policy=NanPolicy::Raise, decimal=false))]
     #[doc = include_str!("../docs/_inline/pe/tvpi.md")]
-    pub fn tvpi_2(
-        py: Python,
-        contributions: AmountArray,
-        distributions: AmountArray,
-        nav: Option<f64>,
-    ) -> PyResult<f64> {
-        py.allow_threads(move || {
-            Ok(private_equity::tvpi_2(&contributions, &distributions, nav.unwrap_or(0.0))?)
-        })
+    pub fn tvpi_2<'py>(
+        py: Python<'py>,
+        contributions: &Bound<'py, PyAny>,
+        distributions: &Bound<'py, PyAny>,
+        nav: Option<Arg<'py, f64>>,
+        nan_policy: NanPolicy,
+        decimal: bool,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let nav = nav.unwrap_or(Arg::Scalar(0.0));
+        if decimal {
+            let decimals = Decimals::new(py)?;
+            return decimals.tvpi_2(
+                &decimal_amounts(&decimals, contributions, nan_policy)?,
+                &decimal_amounts(&decimals, distributions, nan_policy)?,
+                scalar_nav(nav)?,
+            );
+        }
+        let series = [series(contributions, nan_policy)?, series(distributions, nan_policy)?];
+        broadcast_series(py, series, [nav], |[contributions, distributions], [nav]| {
+            private_equity::tvpi_2(contributions, distributions, nav)
+        })?
+        .into_pyobject(py)
     }
 
     #[pyfunction]
+    #[pyo3(signature = (amounts, nav=None, *, nan_policy=NanPolicy::Raise, decimal=false))]
     #[doc = include_str!("../docs/_inline/pe/moic.md")]
-    pub fn moic(py: Python, amounts: AmountArray, nav: Option<f64>) -> PyResult<f64> {
-        py.allow_threads(move || Ok(private_equity::moic(&amounts, nav.unwrap_or(0.0))?))
+    pub fn moic<'py>(
+        py: Python<'py>,
+        amounts: &Bound<'py, PyAny>,
+        nav: Option<Arg<'py, f64>>,
+        nan_policy: NanPolicy,
+        decimal: bool,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        // the same math as TVPI
+        tvpi(py, amounts, nav, nan_policy, decimal)
     }
 
     #[pyfunction]
+    #[pyo3(signature = (contributions, distributions, nav=None, *, nan_policy=NanPolicy::Raise, decimal=false))]
     #[doc = include_str!("../docs/_inline/pe/moic.md")]
-    pub fn moic_2(
-        py: Python,
-        contributions: AmountArray,
-        distributions: AmountArray,
-        nav: Option<f64>,
-    ) -> PyResult<f64> {
-        py.allow_threads(move || {
-            Ok(private_equity::moic_2(&contributions, &distributions, nav.unwrap_or(0.0))?)
-        })
+    pub fn moic_2<'py>(
+        py: Python<'py>,
+        contributions: &Bound<'py, PyAny>,
+        distributions: &Bound<'py, PyAny>,
+        nav: Option<Arg<'py, f64>>,
+        nan_policy: NanPolicy,
+        decimal: bool,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        tvpi_2(py, contributions, distributions, nav, nan_policy, decimal)
     }
 
     #[pyfunction]
+    #[pyo3(signature = (amounts, index, nav=None, *, nan_policy=NanPolicy::Raise))]
     #[doc = include_str!("../docs/_inline/pe/ks_pme.md")]
-    fn ks_pme(
-        py: Python,
-        amounts: AmountArray,
-        index: AmountArray,
-        nav: Option<f64>,
-    ) -> PyResult<f64> {
-        py.allow_threads(move || Ok(private_equity::ks_pme(&amounts, &index, nav.unwrap_or(0.0))?))
+    fn ks_pme<'py>(
+        py: Python<'py>,
+        amounts: Series<'py>,
+        index: Series<'py>,
+        nav: Option<Arg<'py, f64>>,
+        nan_policy: NanPolicy,
+    ) -> PyResult<Arg<'py, f64>> {
+        let nav = nav.unwrap_or(Arg::Scalar(0.0));
+        let amounts = amounts.apply_nan_policy(nan_policy)?;
+        broadcast_series(py, [amounts, index], [nav], |[amounts, index], [nav]| {
+            private_equity::ks_pme(amounts, index, nav)
+        })
     }
 
     #[pyfunction]
+    #[pyo3(signature = (contributions, distributions, index, nav=None, *, nan_policy=NanPolicy::Raise))]
     #[doc = include_str!("../docs/_inline/pe/ks_pme.md")]
-    fn ks_pme_2(
-        py: Python,
-        contributions: AmountArray,
-        distributions: AmountArray,
-        index: AmountArray,
-        nav: Option<f64>,
-    ) -> PyResult<f64> {
-        py.allow_threads(move || {
-            Ok(private_equity::ks_pme_2(
-                &contributions,
-                &distributions,
-                &index,
-                nav.unwrap_or(0.0),
-            )?)
+    fn ks_pme_2<'py>(
+        py: Python<'py>,
+        contributions: Series<'py>,
+        distributions: Series<'py>,
+        index: Series<'py>,
+        nav: Option<Arg<'py, f64>>,
+        nan_policy: NanPolicy,
+    ) -> PyResult<Arg<'py, f64>> {
+        let nav = nav.unwrap_or(Arg::Scalar(0.0));
+        let contributions = contributions.apply_nan_policy(nan_policy)?;
+        let distributions = distributions.apply_nan_policy(nan_policy)?;
+        let series = [contributions, distributions, index];
+        broadcast_series(py, series, [nav], |[contributions, distributions, index], [nav]| {
+            private_equity::ks_pme_2(contributions, distributions, index, nav)
         })
     }
 
     #[pyfunction]
+    #[pyo3(signature = (amounts, index, *, nan_policy=NanPolicy::Raise))]
     #[doc = include_str!("../docs/_inline/pe/ks_pme_flows.md")]
-    fn ks_pme_flows(py: Python, amounts: AmountArray, index: AmountArray) -> PyResult<Vec<f64>> {
+    fn ks_pme_flows(
+        py: Python,
+        amounts: AmountArray,
+        index: AmountArray,
+        nan_policy: NanPolicy,
+    ) -> PyResult<Vec<f64>> {
+        let amounts = nan_policy.apply(amounts.into_vec())?;
         py.allow_threads(move || Ok(private_equity::ks_pme_flows(&amounts, &index)?))
     }
 
     #[pyfunction]
+    #[pyo3(signature = (contributions, distributions, index, *, nan_policy=NanPolicy::Raise))]
     #[doc = include_str!("../docs/_inline/pe/ks_pme_flows.md")]
     fn ks_pme_flows_2(
         py: Python,
         contributions: AmountArray,
         distributions: AmountArray,
         index: AmountArray,
+        nan_policy: NanPolicy,
     ) -> PyResult<(Vec<f64>, Vec<f64>)> {
+        let contributions = nan_policy.apply(contributions.into_vec())?;
+        let distributions = nan_policy.apply(distributions.into_vec())?;
         py.allow_threads(move || {
             Ok(private_equity::ks_pme_flows_2(&contributions, &distributions, &index)?)
         })
     }
 
     #[pyfunction]
+    #[pyo3(signature = (amounts, index, nav, *, nan_policy=NanPolicy::Raise))]
     #[doc = include_str!("../docs/_inline/pe/m_pme.md")]
-    fn m_pme(
-        py: Python,
-        amounts: AmountArray,
-        index: AmountArray,
-        nav: AmountArray,
-    ) -> PyResult<f64> {
-        py.allow_threads(move || Ok(private_equity::m_pme(&amounts, &index, &nav)?))
+    fn m_pme<'py>(
+        py: Python<'py>,
+        amounts: Series<'py>,
+        index: Series<'py>,
+        nav: Series<'py>,
+        nan_policy: NanPolicy,
+    ) -> PyResult<Arg<'py, f64>> {
+        let amounts = amounts.apply_nan_policy(nan_policy)?;
+        broadcast_series(py, [amounts, index, nav], [], |[amounts, index, nav], []| {
+            private_equity::m_pme(amounts, index, nav)
+        })
     }
 
     #[pyfunction]
+    #[pyo3(signature = (contributions, distributions, index, nav, *, nan_policy=NanPolicy::Raise))]
     #[doc = include_str!("../docs/_inline/pe/m_pme.md")]
-    fn m_pme_2(
-        py: Python,
-        contributions: AmountArray,
-        distributions: AmountArray,
-        index: AmountArray,
-        nav: AmountArray,
-    ) -> PyResult<f64> {
-        py.allow_threads(move || {
-            Ok(private_equity::m_pme_2(&contributions, &distributions, &index, &nav)?)
+    fn m_pme_2<'py>(
+        py: Python<'py>,
+        contributions: Series<'py>,
+        distributions: Series<'py>,
+        index: Series<'py>,
+        nav: Series<'py>,
+        nan_policy: NanPolicy,
+    ) -> PyResult<Arg<'py, f64>> {
+        let contributions = contributions.apply_nan_policy(nan_policy)?;
+        let distributions = distributions.apply_nan_policy(nan_policy)?;
+        let series = [contributions, distributions, index, nav];
+        broadcast_series(py, series, [], |[contributions, distributions, index, nav], []| {
+            private_equity::m_pme_2(contributions, distributions, index, nav)
         })
     }
 
     #[pyfunction]
+    #[pyo3(signature = (amounts, index, nav=None, *, nan_policy=NanPolicy::Raise))]
     #[doc = include_str!("../docs/_inline/pe/pme_plus.md")]
-    fn pme_plus(
-        py: Python,
-        amounts: AmountArray,
-        index: AmountArray,
-        nav: Option<f64>,
-    ) -> PyResult<Option<f64>> {
-        py.allow_threads(move || {
-            fallible_float_or_none(
-                private_equity::pme_plus(&amounts, &index, nav.unwrap_or(0.0)),
-                false,
-            )
+    fn pme_plus<'py>(
+        py: Python<'py>,
+        amounts: Series<'py>,
+        index: Series<'py>,
+        nav: Option<Arg<'py, f64>>,
+        nan_policy: NanPolicy,
+    ) -> PyResult<Arg<'py, f64>> {
+        let nav = nav.unwrap_or(Arg::Scalar(0.0));
+        let amounts = amounts.apply_nan_policy(nan_policy)?;
+        broadcast_series(py, [amounts, index], [nav], |[amounts, index], [nav]| {
+            private_equity::pme_plus(amounts, index, nav)
         })
     }
 
     #[pyfunction]
+    #[pyo3(signature = (contributions, distributions, index, nav=None, *, nan_policy=NanPolicy::Raise))]
     #[doc = include_str!("../docs/_inline/pe/pme_plus.md")]
-    fn pme_plus_2(
-        py: Python,
-        contributions: AmountArray,
-        distributions: AmountArray,
-        index: AmountArray,
-        nav: Option<f64>,
-    ) -> PyResult<Option<f64>> {
-        py.allow_threads(move || {
-            fallible_float_or_none(
-                private_equity::pme_plus_2(
-                    &contributions,
-                    &distributions,
-                    &index,
-                    nav.unwrap_or(0.0),
-                ),
-                false,
-            )
+    fn pme_plus_2<'py>(
+        py: Python<'py>,
+        contributions: Series<'py>,
+        distributions: Series<'py>,
+        index: Series<'py>,
+        nav: Option<Arg<'py, f64>>,
+        nan_policy: NanPolicy,
+    ) -> PyResult<Arg<'py, f64>> {
+        let nav = nav.unwrap_or(Arg::Scalar(0.0));
+        let contributions = contributions.apply_nan_policy(nan_policy)?;
+        let distributions = distributions.apply_nan_policy(nan_policy)?;
+        let series = [contributions, distributions, index];
+        broadcast_series(py, series, [nav], |[contributions, distributions, index], [nav]| {
+            private_equity::pme_plus_2(contributions, distributions, index, nav)
         })
     }
 
     #[pyfunction]
+    #[pyo3(signature = (amounts, index, nav=None, *, nan_policy=NanPolicy::Raise))]
     #[doc = include_str!("../docs/_inline/pe/pme_plus_flows.md")]
     fn pme_plus_flows(
         py: Python,
         amounts: AmountArray,
         index: AmountArray,
         nav: Option<f64>,
+        nan_policy: NanPolicy,
     ) -> PyResult<Vec<f64>> {
+        let amounts = nan_policy.apply(amounts.into_vec())?;
         py.allow_threads(move || {
             Ok(private_equity::pme_plus_flows(&amounts, &index, nav.unwrap_or(0.0))?)
         })
     }
 
     #[pyfunction]
+    #[pyo3(signature = (contributions, distributions, index, nav=None, *, nan_policy=NanPolicy::Raise))]
     #[doc = include_str!("../docs/_inline/pe/pme_plus_flows.md")]
     fn pme_plus_flows_2(
         py: Python,
@@ -712,7 +1728,10 @@ mod pe {
         distributions: AmountArray,
         index: AmountArray,
         nav: Option<f64>,
+        nan_policy: NanPolicy,
     ) -> PyResult<(Vec<f64>, Vec<f64>)> {
+        let contributions = nan_policy.apply(contributions.into_vec())?;
+        let distributions = nan_policy.apply(distributions.into_vec())?;
         py.allow_threads(move || {
             let adj_distributions = private_equity::pme_plus_flows_2(
                 &contributions,
@@ -721,121 +1740,557 @@ mod pe {
                 nav.unwrap_or(0.0),
             )?;
 
-            Ok((contributions.into_vec(), adj_distributions))
+            Ok((contributions, adj_distributions))
         })
     }
 
     #[pyfunction]
+    #[pyo3(signature = (amounts, index, nav=None, *, nan_policy=NanPolicy::Raise))]
     #[doc = include_str!("../docs/_inline/pe/pme_plus_lambda.md")]
-    fn pme_plus_lambda(
-        py: Python,
-        amounts: AmountArray,
-        index: AmountArray,
-        nav: Option<f64>,
-    ) -> PyResult<f64> {
-        py.allow_threads(move || {
-            Ok(private_equity::pme_plus_lambda(&amounts, &index, nav.unwrap_or(0.0))?)
+    fn pme_plus_lambda<'py>(
+        py: Python<'py>,
+        amounts: Series<'py>,
+        index: Series<'py>,
+        nav: Option<Arg<'py, f64>>,
+        nan_policy: NanPolicy,
+    ) -> PyResult<Arg<'py, f64>> {
+        let nav = nav.unwrap_or(Arg::Scalar(0.0));
+        let amounts = amounts.apply_nan_policy(nan_policy)?;
+        broadcast_series(py, [amounts, index], [nav], |[amounts, index], [nav]| {
+            private_equity::pme_plus_lambda(amounts, index, nav)
         })
     }
 
     #[pyfunction]
+    #[pyo3(signature = (contributions, distributions, index, nav=None, *, nan_policy=NanPolicy::Raise))]
     #[doc = include_str!("../docs/_inline/pe/pme_plus_lambda.md")]
-    fn pme_plus_lambda_2(
-        py: Python,
-        contributions: AmountArray,
-        distributions: AmountArray,
-        index: AmountArray,
-        nav: Option<f64>,
-    ) -> PyResult<f64> {
-        py.allow_threads(move || {
-            Ok(private_equity::pme_plus_lambda_2(
-                &contributions,
-                &distributions,
-                &index,
-                nav.unwrap_or(0.0),
-            )?)
+    fn pme_plus_lambda_2<'py>(
+        py: Python<'py>,
+        contributions: Series<'py>,
+        distributions: Series<'py>,
+        index: Series<'py>,
+        nav: Option<Arg<'py, f64>>,
+        nan_policy: NanPolicy,
+    ) -> PyResult<Arg<'py, f64>> {
+        let nav = nav.unwrap_or(Arg::Scalar(0.0));
+        let contributions = contributions.apply_nan_policy(nan_policy)?;
+        let distributions = distributions.apply_nan_policy(nan_policy)?;
+        let series = [contributions, distributions, index];
+        broadcast_series(py, series, [nav], |[contributions, distributions, index], [nav]| {
+            private_equity::pme_plus_lambda_2(contributions, distributions, index, nav)
         })
     }
 
     #[pyfunction]
+    #[pyo3(signature = (amounts, index, *, nan_policy=NanPolicy::Raise))]
     #[doc = include_str!("../docs/_inline/pe/ln_pme_nav.md")]
-    fn ln_pme_nav(py: Python, amounts: AmountArray, index: AmountArray) -> PyResult<f64> {
-        py.allow_threads(move || Ok(private_equity::ln_pme_nav(&amounts, &index)?))
+    fn ln_pme_nav<'py>(
+        py: Python<'py>,
+        amounts: Series<'py>,
+        index: Series<'py>,
+        nan_policy: NanPolicy,
+    ) -> PyResult<Arg<'py, f64>> {
+        let amounts = amounts.apply_nan_policy(nan_policy)?;
+        broadcast_series(py, [amounts, index], [], |[amounts, index], []| {
+            private_equity::ln_pme_nav(amounts, index)
+        })
     }
 
     #[pyfunction]
+    #[pyo3(signature = (contributions, distributions, index, *, nan_policy=NanPolicy::Raise))]
     #[doc = include_str!("../docs/_inline/pe/ln_pme_nav.md")]
-    fn ln_pme_nav_2(
-        py: Python,
-        contributions: AmountArray,
-        distributions: AmountArray,
-        index: AmountArray,
-    ) -> PyResult<f64> {
-        py.allow_threads(move || {
-            Ok(private_equity::ln_pme_nav_2(&contributions, &distributions, &index)?)
+    fn ln_pme_nav_2<'py>(
+        py: Python<'py>,
+        contributions: Series<'py>,
+        distributions: Series<'py>,
+        index: Series<'py>,
+        nan_policy: NanPolicy,
+    ) -> PyResult<Arg<'py, f64>> {
+        let contributions = contributions.apply_nan_policy(nan_policy)?;
+        let distributions = distributions.apply_nan_policy(nan_policy)?;
+        let series = [contributions, distributions, index];
+        broadcast_series(py, series, [], |[contributions, distributions, index], []| {
+            private_equity::ln_pme_nav_2(contributions, distributions, index)
         })
     }
 
     #[pyfunction]
+    #[pyo3(signature = (amounts, index, *, nan_policy=NanPolicy::Raise))]
     #[doc = include_str!("../docs/_inline/pe/ln_pme.md")]
-    fn ln_pme(py: Python, amounts: AmountArray, index: AmountArray) -> PyResult<Option<f64>> {
-        py.allow_threads(move || {
-            fallible_float_or_none(private_equity::ln_pme(&amounts, &index), false)
+    fn ln_pme<'py>(
+        py: Python<'py>,
+        amounts: Series<'py>,
+        index: Series<'py>,
+        nan_policy: NanPolicy,
+    ) -> PyResult<Arg<'py, f64>> {
+        let amounts = amounts.apply_nan_policy(nan_policy)?;
+        broadcast_series(py, [amounts, index], [], |[amounts, index], []| {
+            private_equity::ln_pme(amounts, index)
         })
     }
 
     #[pyfunction]
+    #[pyo3(signature = (contributions, distributions, index, *, nan_policy=NanPolicy::Raise))]
     #[doc = include_str!("../docs/_inline/pe/ln_pme.md")]
-    fn ln_pme_2(
-        py: Python,
-        contributions: AmountArray,
-        distributions: AmountArray,
-        index: AmountArray,
-    ) -> PyResult<Option<f64>> {
-        py.allow_threads(move || {
-            fallible_float_or_none(
-                private_equity::ln_pme_2(&contributions, &distributions, &index),
-                false,
-            )
+    fn ln_pme_2<'py>(
+        py: Python<'py>,
+        contributions: Series<'py>,
+        distributions: Series<'py>,
+        index: Series<'py>,
+        nan_policy: NanPolicy,
+    ) -> PyResult<Arg<'py, f64>> {
+        let contributions = contributions.apply_nan_policy(nan_policy)?;
+        let distributions = distributions.apply_nan_policy(nan_policy)?;
+        let series = [contributions, distributions, index];
+        broadcast_series(py, series, [], |[contributions, distributions, index], []| {
+            private_equity::ln_pme_2(contributions, distributions, index)
         })
     }
 
     #[pyfunction]
+    #[pyo3(signature = (amounts, index, nav=None, *, nan_policy=NanPolicy::Raise))]
     #[doc = include_str!("../docs/_inline/pe/direct_alpha.md")]
-    fn direct_alpha(
-        py: Python,
-        amounts: AmountArray,
-        index: AmountArray,
-        nav: Option<f64>,
-    ) -> PyResult<Option<f64>> {
-        py.allow_threads(move || {
-            fallible_float_or_none(
-                private_equity::direct_alpha(&amounts, &index, nav.unwrap_or(0.0)),
-                false,
-            )
+    fn direct_alpha<'py>(
+        py: Python<'py>,
+        amounts: Series<'py>,
+        index: Series<'py>,
+        nav: Option<Arg<'py, f64>>,
+        nan_policy: NanPolicy,
+    ) -> PyResult<Arg<'py, f64>> {
+        let nav = nav.unwrap_or(Arg::Scalar(0.0));
+        let amounts = amounts.apply_nan_policy(nan_policy)?;
+        broadcast_series(py, [amounts, index], [nav], |[amounts, index], [nav]| {
+            private_equity::direct_alpha(amounts, index, nav)
         })
     }
 
     #[pyfunction]
+    #[pyo3(signature = (contributions, distributions, index, nav=None, *, nan_policy=NanPolicy::Raise))]
     #[doc = include_str!("../docs/_inline/pe/direct_alpha.md")]
-    fn direct_alpha_2(
+    fn direct_alpha_2<'py>(
+        py: Python<'py>,
+        contributions: Series<'py>,
+        distributions: Series<'py>,
+        index: Series<'py>,
+        nav: Option<Arg<'py, f64>>,
+        nan_policy: NanPolicy,
+    ) -> PyResult<Arg<'py, f64>> {
+        let nav = nav.unwrap_or(Arg::Scalar(0.0));
+        let contributions = contributions.apply_nan_policy(nan_policy)?;
+        let distributions = distributions.apply_nan_policy(nan_policy)?;
+        let series = [contributions, distributions, index];
+        broadcast_series(py, series, [nav], |[contributions, distributions, index], [nav]| {
+            private_equity::direct_alpha_2(contributions, distributions, index, nav)
+        })
+    }
+
+    #[pyfunction]
+    #[doc = include_str!("../docs/_inline/pe/takahashi_alexander.md")]
+    #[allow(clippy::too_many_arguments)]
+    fn takahashi_alexander(
         py: Python,
+        commitment: f64,
+        fund_life: f64,
+        rate_of_contribution: f64,
+        bow: f64,
+        growth: f64,
+        yield_: f64,
+        periods: usize,
+    ) -> PyResult<Bound<PyDict>> {
+        let params = private_equity::TakahashiAlexanderParams {
+            rate_of_contribution,
+            bow,
+            growth,
+            yield_,
+        };
+
+        let projection = py.allow_threads(move || {
+            private_equity::takahashi_alexander(commitment, fund_life, &params, periods)
+        })?;
+        let amounts: Vec<f64> = projection
+            .distributions
+            .iter()
+            .zip(&projection.contributions)
+            .map(|(d, c)| d - c)
+            .collect();
+
+        let dict = PyDict::new(py);
+        dict.set_item("contributions", projection.contributions)?;
+        dict.set_item("distributions", projection.distributions)?;
+        dict.set_item("nav", projection.nav)?;
+        dict.set_item("amounts", amounts)?;
+        Ok(dict)
+    }
+
+    #[pyfunction]
+    #[pyo3(signature = (commitment, fund_life, contributions, distributions, nav, *, nan_policy=NanPolicy::Raise))]
+    #[doc = include_str!("../docs/_inline/pe/takahashi_alexander.md")]
+    fn takahashi_alexander_calibrate<'py>(
+        py: Python<'py>,
+        commitment: f64,
+        fund_life: f64,
         contributions: AmountArray,
         distributions: AmountArray,
-        index: AmountArray,
-        nav: Option<f64>,
-    ) -> PyResult<Option<f64>> {
-        py.allow_threads(move || {
-            fallible_float_or_none(
-                private_equity::direct_alpha_2(
-                    &contributions,
-                    &distributions,
-                    &index,
-                    nav.unwrap_or(0.0),
-                ),
-                false,
+        nav: AmountArray,
+        nan_policy: NanPolicy,
+    ) -> PyResult<Bound<'py, PyDict>> {
+        let contributions = nan_policy.apply(contributions.into_vec())?;
+        let distributions = nan_policy.apply(distributions.into_vec())?;
+        let nav = nan_policy.apply(nav.into_vec())?;
+        let params = py.allow_threads(move || {
+            private_equity::takahashi_alexander_calibrate(
+                commitment,
+                fund_life,
+                &contributions,
+                &distributions,
+                &nav,
             )
-        })
+        })?;
+
+        let result = PyDict::new(py);
+        result.set_item("rate_of_contribution", params.rate_of_contribution)?;
+        result.set_item("bow", params.bow)?;
+        result.set_item("growth", params.growth)?;
+        result.set_item("yield_", params.yield_)?;
+        Ok(result)
+    }
+
+    #[pyfunction]
+    #[pyo3(signature = (dates, contributions, distributions, *, hurdle=0.08, carry=0.2, catch_up=1.0, kind="european", deals=None, clawback=true, day_count=None, nan_policy=NanPolicy::Raise, date_format=None, excel_serial=None))]
+    #[doc = include_str!("../docs/_inline/pe/waterfall.md")]
+    #[allow(clippy::too_many_arguments)]
+    fn waterfall<'py>(
+        py: Python<'py>,
+        dates: &Bound<'py, PyAny>,
+        contributions: AmountArray,
+        distributions: AmountArray,
+        hurdle: f64,
+        carry: f64,
+        catch_up: f64,
+        kind: &str,
+        deals: Option<&Bound<'py, PyAny>>,
+        clawback: bool,
+        day_count: Option<PyDayCount>,
+        nan_policy: NanPolicy,
+        date_format: Option<&str>,
+        excel_serial: Option<&Bound<'py, PyAny>>,
+    ) -> PyResult<Bound<'py, PyDict>> {
+        let parser = DateParser::new(date_format, excel_serial)?;
+        let dates = extract_date_series(dates, &parser)?;
+        // the allocations are aligned with the dates, the amounts are not omitted
+        let contributions = nan_policy.apply(contributions.into_vec())?;
+        let distributions = nan_policy.apply(distributions.into_vec())?;
+        let deals = deals.map(extract_labels).transpose()?;
+        let terms = private_equity::WaterfallTerms {
+            hurdle,
+            carry,
+            catch_up,
+            kind: private_equity::WaterfallKind::from_str(kind).map_err(PyValueError::new_err)?,
+            clawback,
+            day_count: day_count.map(|x| x.try_into()).transpose()?,
+        };
+
+        let result = py.allow_threads(move || {
+            private_equity::waterfall(
+                &dates,
+                &contributions,
+                &distributions,
+                deals.as_deref(),
+                &terms,
+            )
+        })?;
+
+        let dict = PyDict::new(py);
+        dict.set_item("return_of_capital", result.return_of_capital)?;
+        dict.set_item("preferred_return", result.preferred_return)?;
+        dict.set_item("lp_catch_up", result.lp_catch_up)?;
+        dict.set_item("gp_catch_up", result.gp_catch_up)?;
+        dict.set_item("lp_split", result.lp_split)?;
+        dict.set_item("gp_carry", result.gp_carry)?;
+        dict.set_item("clawback", result.clawback)?;
+        dict.set_item("lp_flows", result.lp_flows)?;
+        dict.set_item("gp_flows", result.gp_flows)?;
+        Ok(dict)
+    }
+
+    #[pyfunction]
+    #[pyo3(signature = (commitment, rate, start_date, investment_period_end, end_date, *, post_rate=None, step_down=0.0, frequency=4, invested=None, fee_income=None, offset=1.0, expenses=0.0, day_count=None, nan_policy=NanPolicy::Raise))]
+    #[doc = include_str!("../docs/_inline/pe/management_fees.md")]
+    #[allow(clippy::too_many_arguments)]
+    fn management_fees<'py>(
+        py: Python<'py>,
+        commitment: f64,
+        rate: f64,
+        start_date: DateLike,
+        investment_period_end: DateLike,
+        end_date: DateLike,
+        post_rate: Option<f64>,
+        step_down: f64,
+        frequency: u8,
+        invested: Option<&Bound<'py, PyAny>>,
+        fee_income: Option<&Bound<'py, PyAny>>,
+        offset: f64,
+        expenses: f64,
+        day_count: Option<PyDayCount>,
+        nan_policy: NanPolicy,
+    ) -> PyResult<Bound<'py, PyDict>> {
+        let terms = private_equity::FeeTerms {
+            commitment,
+            rate,
+            post_rate: post_rate.unwrap_or(rate),
+            step_down,
+            frequency,
+            offset,
+            expenses,
+            start_date,
+            investment_period_end,
+            end_date,
+            day_count: day_count.map(|x| x.try_into()).transpose()?,
+        };
+        let invested = invested
+            .map(|x| extract_payments(x, None, nan_policy))
+            .transpose()?
+            .unwrap_or_default();
+        let fee_income = fee_income
+            .map(|x| extract_payments(x, None, nan_policy))
+            .transpose()?
+            .unwrap_or_default();
+
+        let result = py.allow_threads(move || {
+            private_equity::management_fees(
+                &terms,
+                (&invested.0, &invested.1),
+                (&fee_income.0, &fee_income.1),
+            )
+        })?;
+
+        let dict = PyDict::new(py);
+        dict.set_item("dates", result.dates)?;
+        dict.set_item("management_fee", result.management_fee)?;
+        dict.set_item("offset", result.offset)?;
+        dict.set_item("expenses", result.expenses)?;
+        dict.set_item("total", result.total)?;
+        Ok(dict)
+    }
+
+    #[pyfunction]
+    #[pyo3(signature = (funds, navs=None, *, day_count=None, nan_policy=NanPolicy::Raise))]
+    #[doc = include_str!("../docs/_inline/pe/pooled.md")]
+    fn pooled_irr(
+        py: Python,
+        funds: &Bound<PyAny>,
+        navs: Option<AmountArray>,
+        day_count: Option<PyDayCount>,
+        nan_policy: NanPolicy,
+    ) -> PyResult<Option<f64>> {
+        let funds = extract_funds(funds, navs, nan_policy)?;
+        let day_count = day_count.map(|x| x.try_into()).transpose()?;
+        let result = py.allow_threads(move || private_equity::pooled_irr(&funds, day_count))?;
+        Ok(float_or_none(result))
+    }
+
+    #[pyfunction]
+    #[pyo3(signature = (funds, *, nan_policy=NanPolicy::Raise))]
+    #[doc = include_str!("../docs/_inline/pe/pooled.md")]
+    fn pooled_dpi(py: Python, funds: &Bound<PyAny>, nan_policy: NanPolicy) -> PyResult<f64> {
+        let funds = extract_funds(funds, None, nan_policy)?;
+        py.allow_threads(move || Ok(private_equity::pooled_dpi(&funds)?))
+    }
+
+    #[pyfunction]
+    #[pyo3(signature = (funds, navs=None, *, nan_policy=NanPolicy::Raise))]
+    #[doc = include_str!("../docs/_inline/pe/pooled.md")]
+    fn pooled_rvpi(
+        py: Python,
+        funds: &Bound<PyAny>,
+        navs: Option<AmountArray>,
+        nan_policy: NanPolicy,
+    ) -> PyResult<f64> {
+        let funds = extract_funds(funds, navs, nan_policy)?;
+        py.allow_threads(move || Ok(private_equity::pooled_rvpi(&funds)?))
+    }
+
+    #[pyfunction]
+    #[pyo3(signature = (funds, navs=None, *, nan_policy=NanPolicy::Raise))]
+    #[doc = include_str!("../docs/_inline/pe/pooled.md")]
+    fn pooled_tvpi(
+        py: Python,
+        funds: &Bound<PyAny>,
+        navs: Option<AmountArray>,
+        nan_policy: NanPolicy,
+    ) -> PyResult<f64> {
+        let funds = extract_funds(funds, navs, nan_policy)?;
+        py.allow_threads(move || Ok(private_equity::pooled_tvpi(&funds)?))
+    }
+
+    #[pyfunction]
+    #[pyo3(signature = (funds, navs=None, *, weights="paid_in", commitments=None, day_count=None, nan_policy=NanPolicy::Raise))]
+    #[doc = include_str!("../docs/_inline/pe/pooled.md")]
+    fn pooled_metrics<'py>(
+        py: Python<'py>,
+        funds: &Bound<'py, PyAny>,
+        navs: Option<AmountArray>,
+        weights: &str,
+        commitments: Option<AmountArray>,
+        day_count: Option<PyDayCount>,
+        nan_policy: NanPolicy,
+    ) -> PyResult<Bound<'py, PyDict>> {
+        let funds = extract_funds(funds, navs, nan_policy)?;
+        let day_count = day_count.map(|x| x.try_into()).transpose()?;
+
+        let weights = weights.to_lowercase();
+        let result = py.allow_threads(move || {
+            let weights = match (weights.as_str(), &commitments) {
+                ("commitment", Some(c)) => private_equity::PooledWeights::Commitment(c),
+                ("commitment", None) => {
+                    return Err(PyValueError::new_err("commitment weights require commitments"))
+                }
+                (w, _) => w.parse().map_err(PyValueError::new_err)?,
+            };
+            Ok(private_equity::pooled_metrics(&funds, weights, day_count)?)
+        })?;
+
+        let floats = |values: Vec<f64>| values.into_iter().map(float_or_none).collect::<Vec<_>>();
+
+        let dict = PyDict::new(py);
+        dict.set_item("irr", float_or_none(result.irr))?;
+        dict.set_item("tvpi", float_or_none(result.tvpi))?;
+        dict.set_item("dpi", float_or_none(result.dpi))?;
+        dict.set_item("rvpi", float_or_none(result.rvpi))?;
+        dict.set_item("average_irr", float_or_none(result.average_irr))?;
+        dict.set_item("median_irr", float_or_none(result.median_irr))?;
+        dict.set_item("fund_irr", floats(result.fund_irr))?;
+        dict.set_item("fund_tvpi", floats(result.fund_tvpi))?;
+        dict.set_item("fund_dpi", floats(result.fund_dpi))?;
+        dict.set_item("fund_rvpi", floats(result.fund_rvpi))?;
+        Ok(dict)
+    }
+
+    #[pyfunction]
+    #[pyo3(signature = (value, peers, *, method="inclusive"))]
+    #[doc = include_str!("../docs/_inline/pe/quartile.md")]
+    fn percentile_rank(py: Python, value: f64, peers: AmountArray, method: &str) -> PyResult<f64> {
+        let method = private_equity::RankMethod::from_str(method).map_err(PyValueError::new_err)?;
+        py.allow_threads(move || Ok(private_equity::percentile_rank(value, &peers, method)?))
+    }
+
+    #[pyfunction]
+    #[pyo3(signature = (value, peers, *, method="inclusive"))]
+    #[doc = include_str!("../docs/_inline/pe/quartile.md")]
+    fn quartile(py: Python, value: f64, peers: AmountArray, method: &str) -> PyResult<u8> {
+        let method = private_equity::RankMethod::from_str(method).map_err(PyValueError::new_err)?;
+        py.allow_threads(move || Ok(private_equity::quartile(value, &peers, method)?))
+    }
+
+    #[pyfunction]
+    #[pyo3(signature = (peers, *, method="inclusive"))]
+    #[doc = include_str!("../docs/_inline/pe/quartile.md")]
+    fn quartile_breakpoints(
+        py: Python,
+        peers: AmountArray,
+        method: &str,
+    ) -> PyResult<(f64, f64, f64)> {
+        let method = private_equity::RankMethod::from_str(method).map_err(PyValueError::new_err)?;
+        let [lower, median, upper] =
+            py.allow_threads(move || private_equity::quartile_breakpoints(&peers, method))?;
+        Ok((lower, median, upper))
+    }
+
+    #[pyfunction]
+    #[pyo3(signature = (flows, peers, *, nav=0.0, index=None, method="inclusive", day_count=None, nan_policy=NanPolicy::Raise))]
+    #[doc = include_str!("../docs/_inline/pe/quartile.md")]
+    #[allow(clippy::too_many_arguments)]
+    fn benchmark<'py>(
+        py: Python<'py>,
+        flows: &Bound<'py, PyAny>,
+        peers: &Bound<'py, PyDict>,
+        nav: f64,
+        index: Option<AmountArray>,
+        method: &str,
+        day_count: Option<PyDayCount>,
+        nan_policy: NanPolicy,
+    ) -> PyResult<Bound<'py, PyDict>> {
+        let (dates, amounts) = extract_payments(flows, None, nan_policy)?;
+        let method = private_equity::RankMethod::from_str(method).map_err(PyValueError::new_err)?;
+        let day_count = day_count.map(|x| x.try_into()).transpose()?;
+
+        let result = PyDict::new(py);
+
+        for (name, values) in peers.iter() {
+            let metric = private_equity::BenchmarkMetric::from_str(&name.extract::<String>()?)
+                .map_err(PyValueError::new_err)?;
+            let values: AmountArray = values.extract()?;
+
+            let ranking = py.allow_threads(|| {
+                let value = private_equity::fund_metric(
+                    metric,
+                    &dates,
+                    &amounts,
+                    nav,
+                    index.as_deref(),
+                    day_count,
+                )?;
+                private_equity::rank_fund(value, &values, method)
+            })?;
+
+            let dict = PyDict::new(py);
+            dict.set_item("value", ranking.value)?;
+            dict.set_item("percentile_rank", ranking.percentile_rank)?;
+            dict.set_item("quartile", ranking.quartile)?;
+            result.set_item(name, dict)?;
+        }
+
+        Ok(result)
+    }
+
+    fn extract_funds(
+        funds: &Bound<PyAny>,
+        navs: Option<AmountArray>,
+        nan_policy: NanPolicy,
+    ) -> PyResult<Vec<private_equity::FundFlows>> {
+        let funds = funds
+            .try_iter()?
+            .map(|fund| {
+                let (dates, amounts) = extract_payments(&fund?, None, nan_policy)?;
+                Ok(private_equity::FundFlows {
+                    dates,
+                    amounts,
+                    nav: 0.0,
+                })
+            })
+            .collect::<PyResult<Vec<_>>>()?;
+
+        match navs {
+            Some(navs) if navs.len() != funds.len() => {
+                Err(PyValueError::new_err("navs must be the same length as funds"))
+            }
+            Some(navs) => Ok(funds
+                .into_iter()
+                .zip(navs.iter())
+                .map(|(fund, &nav)| private_equity::FundFlows {
+                    nav,
+                    ..fund
+                })
+                .collect()),
+            None => Ok(funds),
+        }
+    }
+
+    // map arbitrary hashable labels to sequential ids
+    fn extract_labels(labels: &Bound<PyAny>) -> PyResult<Vec<usize>> {
+        let ids = PyDict::new(labels.py());
+        labels
+            .try_iter()?
+            .map(|label| {
+                let label = label?;
+                if let Some(id) = ids.get_item(&label)? {
+                    return id.extract();
+                }
+                let id = ids.len();
+                ids.set_item(label, id)?;
+                Ok(id)
+            })
+            .collect()
     }
 }
 
@@ -843,9 +2298,9 @@ fn add_submodule<F>(py: Python, parent: &Bound<PyModule>, name: &str, mod_init:
 where
     F: Fn(Python, &Bound<PyModule>) -> PyResult<()>,
 {
-    let ref child_module = PyModule::new(py, name)?;
+    let child_module = &PyModule::new(py, name)?;
     mod_init(py, child_module)?;
-    parent.add(name.split('.').last().unwrap(), child_module)?;
+    parent.add(name.split('.').next_back().unwrap(), child_module)?;
     py.import("sys")?.getattr("modules")?.set_item(name, child_module)?;
     Ok(())
 }
@@ -856,6 +2311,7 @@ pub fn pyxirr(py: Python, m: &Bound<PyModule>) -> PyResult<()> {
     m.add("__version__", env!("CARGO_PKG_VERSION"))?;
 
     add_submodule(py, m, "pyxirr.pe", pe::module)?;
+    add_submodule(py, m, "pyxirr.ufunc", ufunc::module)?;
 
     m.add_class::<core::DayCount>()?;
     m.add_function(wrap_pyfunction!(year_fraction, m)?)?;
@@ -875,14 +2331,32 @@ pub fn pyxirr(py: Python, m: &Bound<PyModule>) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(pv, m)?)?;
     m.add_function(wrap_pyfunction!(npv, m)?)?;
     m.add_function(wrap_pyfunction!(xnpv, m)?)?;
+    m.add_function(wrap_pyfunction!(payback, m)?)?;
+    m.add_function(wrap_pyfunction!(discounted_payback, m)?)?;
+    m.add_function(wrap_pyfunction!(xpayback, m)?)?;
+    m.add_function(wrap_pyfunction!(profitability_index, m)?)?;
+    m.add_function(wrap_pyfunction!(eaa, m)?)?;
+    m.add_function(wrap_pyfunction!(npv_profile, m)?)?;
+    m.add_function(wrap_pyfunction!(xnpv_profile, m)?)?;
     m.add_function(wrap_pyfunction!(irr, m)?)?;
     m.add_function(wrap_pyfunction!(mirr, m)?)?;
     m.add_function(wrap_pyfunction!(xirr, m)?)?;
+    m.add_function(wrap_pyfunction!(xgoal_seek, m)?)?;
+    m.add_function(wrap_pyfunction!(monte_carlo, m)?)?;
+    m.add_function(wrap_pyfunction!(sensitivity_table, m)?)?;
+    m.add_function(wrap_pyfunction!(tornado, m)?)?;
     m.add_function(wrap_pyfunction!(is_conventional_cash_flow, m)?)?;
     m.add_function(wrap_pyfunction!(zero_crossing_points, m)?)?;
 
     m.add("InvalidPaymentsError", py.get_type::<InvalidPaymentsError>())?;
+    m.add("LengthMismatchError", py.get_type::<LengthMismatchError>())?;
+    m.add("NoSignChangeError", py.get_type::<NoSignChangeError>())?;
+    m.add("ZeroContributionsError", py.get_type::<ZeroContributionsError>())?;
+    m.add("EmptyInputError", py.get_type::<EmptyInputError>())?;
+    m.add("NaNAmountError", py.get_type::<NaNAmountError>())?;
+    m.add("MissingDateError", py.get_type::<MissingDateError>())?;
     m.add("BroadcastingError", py.get_type::<BroadcastingError>())?;
+    m.add("AmbiguousDateError", py.get_type::<AmbiguousDateError>())?;
 
     Ok(())
 }
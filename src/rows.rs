@@ -0,0 +1,164 @@
+//! Row-wise evaluation (`axis=`) of the cash flow functions over 2D arrays of amounts,
+//! e.g. the same dates with thousands of simulated amount vectors.
+
+use std::{num::NonZeroUsize, thread};
+
+use ndarray::{Array1, Axis};
+use pyo3::{
+    exceptions::{PyTypeError, PyValueError},
+    prelude::*,
+};
+
+use crate::{
+    broadcasting::Arg,
+    conversions::{extract_optional_date_series, silent_invalid_payments, Columns, DateParser},
+    core::{DateLike, InvalidPaymentsError},
+};
+
+/// Fewer rows than this are solved on the calling thread.
+const MIN_ROWS_PER_THREAD: usize = 64;
+
+pub struct Rows {
+    rows: Vec<Vec<f64>>,
+    is_numpy: bool,
+}
+
+impl Rows {
+    /// Cash flows of a 2D array of amounts (a numpy array or nested sequences) lie along `axis`,
+    /// i.e. `axis=1` (or `-1`) means one cash flow vector per row.
+    pub fn extract(amounts: &Bound<'_, PyAny>, axis: isize) -> PyResult<Self> {
+        let amounts: Arg<f64> = amounts.extract()?;
+        let is_numpy = matches!(amounts, Arg::NumpyArray(_));
+        let amounts = amounts.into_arrayd();
+
+        if amounts.ndim() != 2 {
+            return Err(PyValueError::new_err(format!(
+                "axis requires a 2D array of amounts, got {} dimension(s)",
+                amounts.ndim()
+            )));
+        }
+
+        let axis = match axis {
+            1 | -1 => Axis(1),
+            0 | -2 => Axis(0),
+            _ => {
+                return Err(PyValueError::new_err(format!(
+                    "axis {axis} is out of bounds for a 2D array"
+                )))
+            }
+        };
+
+        Ok(Self {
+            rows: amounts.lanes(axis).into_iter().map(|lane| lane.to_vec()).collect(),
+            is_numpy,
+        })
+    }
+
+    /// Prepares every row with the GIL held (e.g. applies `nan_policy`), then solves the rows
+    /// in parallel without it. With `silent`, invalid rows are NaN instead of raising.
+    pub fn solve<'py, T, P, F>(
+        self,
+        py: Python<'py>,
+        silent: bool,
+        prepare: P,
+        solve: F,
+    ) -> PyResult<Bound<'py, PyAny>>
+    where
+        T: Send,
+        P: Fn(Vec<f64>) -> PyResult<T>,
+        F: Fn(T) -> Result<f64, InvalidPaymentsError> + Sync,
+    {
+        let rows = self
+            .rows
+            .into_iter()
+            .map(|row| silent_invalid_payments(py, prepare(row), silent))
+            .collect::<PyResult<Vec<_>>>()?;
+
+        let results = py.allow_threads(|| {
+            par_map(rows, |row| match row {
+                Some(row) => match solve(row) {
+                    Err(_) if silent => Ok(f64::NAN),
+                    result => result,
+                },
+                None => Ok(f64::NAN),
+            })
+        });
+
+        let result = results.into_iter().collect::<Result<Array1<f64>, _>>()?.into_dyn();
+
+        let result = if self.is_numpy {
+            Arg::from(numpy::PyArray::from_owned_array(py, result))
+        } else {
+            Arg::from(result)
+        };
+
+        result.into_pyobject(py)
+    }
+}
+
+/// The dates shared by all the rows of amounts, for `xirr`, `xnpv` and friends.
+pub fn extract_dated(
+    dates: &Bound<'_, PyAny>,
+    amounts: Option<&Bound<'_, PyAny>>,
+    columns: &Columns,
+    axis: isize,
+    parser: &DateParser,
+) -> PyResult<(Vec<Option<DateLike>>, Rows)> {
+    let Some(amounts) = amounts else {
+        return Err(PyTypeError::new_err("axis requires separate dates and amounts"));
+    };
+    if !columns.is_empty() {
+        return Err(PyTypeError::new_err("axis does not support date_col, amount_col and by"));
+    }
+
+    Ok((extract_optional_date_series(dates, parser)?, Rows::extract(amounts, axis)?))
+}
+
+/// `items.into_iter().map(f).collect()`, split into chunks across the available cores.
+pub fn par_map<T, R, F>(items: Vec<T>, f: F) -> Vec<R>
+where
+    T: Send,
+    R: Send,
+    F: Fn(T) -> R + Sync,
+{
+    let threads = thread::available_parallelism().map_or(1, NonZeroUsize::get);
+    let chunk_size = items.len().div_ceil(threads).max(MIN_ROWS_PER_THREAD);
+
+    if items.len() <= chunk_size {
+        return items.into_iter().map(f).collect();
+    }
+
+    let mut items = items.into_iter();
+    let chunks: Vec<Vec<T>> = std::iter::from_fn(|| {
+        let chunk: Vec<T> = items.by_ref().take(chunk_size).collect();
+        (!chunk.is_empty()).then_some(chunk)
+    })
+    .collect();
+
+    let f = &f;
+    thread::scope(|scope| {
+        chunks
+            .into_iter()
+            .map(|chunk| scope.spawn(move || chunk.into_iter().map(f).collect::<Vec<R>>()))
+            .collect::<Vec<_>>()
+            .into_iter()
+            .flat_map(|handle| handle.join().unwrap())
+            .collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case(0)]
+    #[case(10)]
+    #[case(10_000)]
+    fn test_par_map_keeps_order(#[case] n: usize) {
+        let result = par_map((0..n).collect(), |x| x * 2);
+        assert_eq!(result, (0..n).map(|x| x * 2).collect::<Vec<_>>());
+    }
+}
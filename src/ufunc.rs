@@ -0,0 +1,201 @@
+//! The periodic functions as native numpy ufuncs (`pyxirr.ufunc`).
+//!
+//! Ufuncs are created on first access, so importing pyxirr does not import numpy.
+//! Every input is positional (ufuncs have no defaults), `pmt_at_beginning` is
+//! any number (non-zero is true). There are float64 and float32 loops.
+
+use std::{
+    ffi::{c_char, c_int, c_void, CStr},
+    ptr,
+};
+
+use numpy::npyffi::{npy_intp, PyUFuncGenericFunction, NPY_TYPES, PY_UFUNC_API};
+use pyo3::{exceptions::PyAttributeError, prelude::*, sync::GILOnceCell, types::PyDict};
+
+use crate::core;
+
+/// `PyUFunc_None`: a ufunc without identity (no reduction).
+const NO_IDENTITY: c_int = -1;
+
+trait Float: Copy {
+    const TYPE: NPY_TYPES;
+    fn to_f64(self) -> f64;
+    fn from_f64(value: f64) -> Self;
+}
+
+impl Float for f64 {
+    const TYPE: NPY_TYPES = NPY_TYPES::NPY_DOUBLE;
+
+    fn to_f64(self) -> f64 {
+        self
+    }
+
+    fn from_f64(value: f64) -> Self {
+        value
+    }
+}
+
+impl Float for f32 {
+    const TYPE: NPY_TYPES = NPY_TYPES::NPY_FLOAT;
+
+    fn to_f64(self) -> f64 {
+        self as f64
+    }
+
+    fn from_f64(value: f64) -> Self {
+        value as f32
+    }
+}
+
+/// A scalar function of `N` inputs from [`core::periodic`](crate::core).
+trait Kernel<const N: usize> {
+    const NAME: &'static CStr;
+    const DOC: &'static CStr;
+
+    fn call(inputs: [f64; N]) -> f64;
+}
+
+/// The inner loop of an elementwise ufunc with `N` inputs and one output, computed in `f64`.
+///
+/// # Safety
+///
+/// `args` and `steps` must have `N + 1` elements of type `T`, as numpy passes to a loop
+/// registered with `N + 1` types of `T`.
+unsafe extern "C" fn inner_loop<T: Float, const N: usize, K: Kernel<N>>(
+    args: *mut *mut c_char,
+    dimensions: *mut npy_intp,
+    steps: *mut npy_intp,
+    _data: *mut c_void,
+) {
+    let len = *dimensions;
+    let args = std::slice::from_raw_parts(args, N + 1);
+    let steps = std::slice::from_raw_parts(steps, N + 1);
+
+    for i in 0..len {
+        let inputs = std::array::from_fn(|k| {
+            ptr::read_unaligned(args[k].offset(i * steps[k]) as *const T).to_f64()
+        });
+        let output = args[N].offset(i * steps[N]) as *mut T;
+        ptr::write_unaligned(output, T::from_f64(K::call(inputs)));
+    }
+}
+
+macro_rules! kernel {
+    ($kernel:ident, $name:literal, [$($arg:ident),+], $body:expr) => {
+        struct $kernel;
+
+        impl Kernel<{ [$(stringify!($arg)),+].len() }> for $kernel {
+            const NAME: &'static CStr = cstr(concat!($name, "\0"));
+            const DOC: &'static CStr =
+                cstr(concat!($name, "(", stringify!($($arg),+), ")\0"));
+
+            fn call([$($arg),+]: [f64; { [$(stringify!($arg)),+].len() }]) -> f64 {
+                $body
+            }
+        }
+    };
+}
+
+const fn cstr(s: &'static str) -> &'static CStr {
+    match CStr::from_bytes_with_nul(s.as_bytes()) {
+        Ok(s) => s,
+        Err(_) => panic!("a nul-terminated string without interior nul"),
+    }
+}
+
+kernel!(Fv, "fv", [rate, nper, pmt, pv, pmt_at_beginning], {
+    core::fv(rate, nper, pmt, pv, pmt_at_beginning != 0.0)
+});
+kernel!(Pv, "pv", [rate, nper, pmt, fv, pmt_at_beginning], {
+    core::pv(rate, nper, pmt, fv, pmt_at_beginning != 0.0)
+});
+kernel!(Pmt, "pmt", [rate, nper, pv, fv, pmt_at_beginning], {
+    core::pmt(rate, nper, pv, fv, pmt_at_beginning != 0.0)
+});
+kernel!(Ipmt, "ipmt", [rate, per, nper, pv, fv, pmt_at_beginning], {
+    core::ipmt(rate, per, nper, pv, fv, pmt_at_beginning != 0.0)
+});
+kernel!(Ppmt, "ppmt", [rate, per, nper, pv, fv, pmt_at_beginning], {
+    core::ppmt(rate, per, nper, pv, fv, pmt_at_beginning != 0.0)
+});
+kernel!(Nper, "nper", [rate, pmt, pv, fv, pmt_at_beginning], {
+    core::nper(rate, pmt, pv, fv, pmt_at_beginning != 0.0)
+});
+// ufuncs have no optional inputs, so `rate` takes the default guess of `pyxirr.rate`
+kernel!(Rate, "rate", [nper, pmt, pv, fv, pmt_at_beginning], {
+    core::rate(nper, pmt, pv, fv, pmt_at_beginning != 0.0, None)
+});
+
+const NAMES: [&CStr; 7] =
+    [Fv::NAME, Pv::NAME, Pmt::NAME, Ipmt::NAME, Ppmt::NAME, Nper::NAME, Rate::NAME];
+
+fn create_ufunc<const N: usize, K: Kernel<N>>(py: Python<'_>) -> PyResult<Bound<'_, PyAny>> {
+    // numpy keeps the pointers for the lifetime of the ufunc, which is the lifetime of the process
+    let funcs: &'static mut [PyUFuncGenericFunction] = Box::leak(Box::new([
+        Some(inner_loop::<f64, N, K> as _),
+        Some(inner_loop::<f32, N, K> as _),
+    ]));
+    let data: &'static mut [*mut c_void] = Box::leak(Box::new([ptr::null_mut(); 2]));
+    let types: &'static mut [c_char] = Box::leak(
+        [f64::TYPE, f32::TYPE]
+            .iter()
+            .flat_map(|t| std::iter::repeat_n(*t as c_char, N + 1))
+            .collect::<Box<[c_char]>>(),
+    );
+
+    unsafe {
+        let ufunc = PY_UFUNC_API.PyUFunc_FromFuncAndData(
+            py,
+            funcs.as_mut_ptr(),
+            data.as_mut_ptr(),
+            types.as_mut_ptr(),
+            funcs.len() as c_int,
+            N as c_int,
+            1,
+            NO_IDENTITY,
+            K::NAME.as_ptr(),
+            K::DOC.as_ptr(),
+            0,
+        );
+        Bound::from_owned_ptr_or_err(py, ufunc)
+    }
+}
+
+fn ufuncs(py: Python<'_>) -> PyResult<&Bound<'_, PyDict>> {
+    static UFUNCS: GILOnceCell<Py<PyDict>> = GILOnceCell::new();
+    UFUNCS
+        .get_or_try_init(py, || {
+            let result = PyDict::new(py);
+            for ufunc in [
+                create_ufunc::<5, Fv>(py)?,
+                create_ufunc::<5, Pv>(py)?,
+                create_ufunc::<5, Pmt>(py)?,
+                create_ufunc::<6, Ipmt>(py)?,
+                create_ufunc::<6, Ppmt>(py)?,
+                create_ufunc::<5, Nper>(py)?,
+                create_ufunc::<5, Rate>(py)?,
+            ] {
+                result.set_item(ufunc.getattr("__name__")?, ufunc)?;
+            }
+            Ok(result.unbind())
+        })
+        .map(|dict| dict.bind(py))
+}
+
+/// Module `__getattr__` (PEP 562): creates the ufuncs on first access.
+#[pyfunction]
+fn __getattr__(py: Python<'_>, name: &str) -> PyResult<PyObject> {
+    if !NAMES.iter().any(|n| n.to_bytes() == name.as_bytes()) {
+        return Err(PyAttributeError::new_err(format!(
+            "module 'pyxirr.ufunc' has no attribute '{name}'"
+        )));
+    }
+    Ok(ufuncs(py)?.get_item(name)?.expect("all ufuncs are created").unbind())
+}
+
+pub fn module(_py: Python, m: &Bound<PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(__getattr__, m)?)?;
+    let names = NAMES.iter().map(|n| n.to_str()).collect::<Result<Vec<_>, _>>()?;
+    m.add("__all__", names)?;
+    Ok(())
+}
@@ -88,9 +88,16 @@ fn test_extract_dates_from_strings() {
         let result: f64 = pyxirr_call!(py, "xirr", (dates_iter, amounts.as_ref()));
         assert_almost_eq!(result, EXPECTED);
 
-        // parse from %m/%d/%Y
+        // parse from %m/%d/%Y, ambiguous without a format
         let dates_iter =
             py.eval(c_str!("(x.strftime('%m/%d/%Y') for x in dates)"), Some(locals), None).unwrap();
+        let kwargs = py_dict!(py, "date_format" => "%m/%d/%Y");
+        let result: f64 = pyxirr_call!(py, "xirr", (dates_iter, amounts.as_ref()), kwargs);
+        assert_almost_eq!(result, EXPECTED);
+
+        // parse from %d.%m.%Y
+        let dates_iter =
+            py.eval(c_str!("(x.strftime('%d.%m.%Y') for x in dates)"), Some(locals), None).unwrap();
         let result: f64 = pyxirr_call!(py, "xirr", (dates_iter, amounts.as_ref()));
         assert_almost_eq!(result, EXPECTED);
 
@@ -164,6 +171,111 @@ fn test_extract_from_pandas_series() {
     assert_almost_eq!(result, EXPECTED);
 }
 
+#[rstest]
+#[cfg_attr(feature = "nonumpy", ignore)]
+fn test_vectorized_keeps_pandas_index() {
+    Python::with_gil(|py| {
+        let locals = &get_locals(py, Some(&["pandas"]));
+        let eval = |code| py.eval(code, Some(locals), None).unwrap();
+        let rates = eval(c_str!("pandas.Series([0.05, 0.06], index=['a', 'b'], name='rate')"));
+
+        let result = pyxirr_call_impl!(py, "pmt", (&rates, 12, 1000)).unwrap();
+        assert_eq!(result.get_type().name().unwrap(), "Series");
+        let index = result.getattr("index").unwrap();
+        assert!(index
+            .call_method1("equals", (rates.getattr("index").unwrap(),))
+            .unwrap()
+            .is_truthy()
+            .unwrap());
+        let expected: f64 = pyxirr_call!(py, "pmt", (0.06, 12, 1000));
+        assert_almost_eq!(result.get_item("b").unwrap().extract::<f64>().unwrap(), expected);
+
+        let frame =
+            eval(c_str!("pandas.DataFrame({'x': [100, 200], 'y': [300, 400]}, index=['a', 'b'])"));
+        let result = pyxirr_call_impl!(
+            py,
+            "fv",
+            (
+                &rates.call_method0("to_numpy").unwrap().call_method1("reshape", (2, 1)).unwrap(),
+                12,
+                0,
+                &frame
+            )
+        )
+        .unwrap();
+        assert_eq!(result.get_type().name().unwrap(), "DataFrame");
+        let columns: Vec<String> =
+            result.getattr("columns").unwrap().call_method0("tolist").unwrap().extract().unwrap();
+        assert_eq!(columns, ["x", "y"]);
+
+        // the shape differs, labels are dropped
+        let result = pyxirr_call_impl!(py, "pmt", (&rates, [[12], [24], [36]], 1000)).unwrap();
+        assert_eq!(result.get_type().name().unwrap(), "ndarray");
+    })
+}
+
+#[rstest]
+#[cfg_attr(feature = "nonumpy", ignore)]
+fn test_vectorized_keep_dtype() {
+    Python::with_gil(|py| {
+        let locals = &get_locals(py, Some(&["numpy"]));
+        let rates = py
+            .eval(c_str!("numpy.array([0.05, 0.06], dtype=numpy.float32)"), Some(locals), None)
+            .unwrap();
+        let dtype = |ob: &Bound<PyAny>| ob.getattr("dtype").unwrap().str().unwrap().to_string();
+
+        let result = pyxirr_call_impl!(py, "pmt", (&rates, 12, 1000)).unwrap();
+        assert_eq!(dtype(&result), "float64");
+
+        let kwargs = py_dict!(py, "keep_dtype" => true);
+        let result = pyxirr_call_impl!(py, "pmt", (&rates, 12, 1000), kwargs).unwrap();
+        assert_eq!(dtype(&result), "float32");
+        let expected: f64 = pyxirr_call!(py, "pmt", (0.05f32 as f64, 12, 1000));
+        let actual: Vec<f32> = result.call_method0("tolist").unwrap().extract().unwrap();
+        assert_almost_eq!(actual[0] as f64, expected, 1e-4);
+
+        // a float64 array wins
+        let nper = py.eval(c_str!("numpy.array([12.0, 24.0])"), Some(locals), None).unwrap();
+        let kwargs = py_dict!(py, "keep_dtype" => true);
+        let result = pyxirr_call_impl!(py, "pmt", (&rates, &nper, 1000), kwargs).unwrap();
+        assert_eq!(dtype(&result), "float64");
+    })
+}
+
+#[rstest]
+#[cfg_attr(feature = "nonumpy", ignore)]
+fn test_vectorized_masked_array() {
+    Python::with_gil(|py| {
+        let locals = &get_locals(py, Some(&["numpy"]));
+        let rates = py
+            .eval(
+                c_str!("numpy.ma.masked_array([0.05, 0.06, 0.07], mask=[False, True, False])"),
+                Some(locals),
+                None,
+            )
+            .unwrap();
+        let nper = py
+            .eval(
+                c_str!("numpy.ma.masked_array([[12], [24]], mask=[[True], [False]])"),
+                Some(locals),
+                None,
+            )
+            .unwrap();
+
+        let result = pyxirr_call_impl!(py, "pmt", (&rates, &nper, 1000)).unwrap();
+        assert_eq!(result.get_type().name().unwrap(), "MaskedArray");
+        let mask: Vec<Vec<bool>> =
+            result.getattr("mask").unwrap().call_method0("tolist").unwrap().extract().unwrap();
+        assert_eq!(mask, [[true, true, true], [false, true, false]]);
+
+        let values: Vec<Vec<Option<f64>>> =
+            result.call_method0("tolist").unwrap().extract().unwrap();
+        let expected: f64 = pyxirr_call!(py, "pmt", (0.07, 24, 1000));
+        assert_eq!(values[1][1], None);
+        assert_almost_eq!(values[1][2].unwrap(), expected);
+    })
+}
+
 #[rstest]
 #[cfg_attr(feature = "nonumpy", ignore)]
 fn test_extract_from_pandas_series_with_datetime_index() {
@@ -182,6 +294,89 @@ fn test_extract_from_pandas_series_with_datetime_index() {
     assert_almost_eq!(result, EXPECTED);
 }
 
+#[rstest]
+#[cfg_attr(feature = "nonumpy", ignore)]
+fn test_extract_from_pyarrow() {
+    Python::with_gil(|py| {
+        let locals = &get_locals(py, Some(&["pyarrow"]));
+        let dates = py.eval(c_str!("pyarrow.array(dates)"), Some(locals), None).unwrap();
+        let amounts = py.eval(c_str!("pyarrow.array(amounts)"), Some(locals), None).unwrap();
+        let result: f64 = pyxirr_call!(py, "xirr", (dates, amounts));
+        assert_almost_eq!(result, EXPECTED);
+
+        // chunked arrays are exported as streams
+        let dates = py
+            .eval(
+                c_str!(
+                    "pyarrow.chunked_array([dates[:5], dates[5:]]).cast(pyarrow.timestamp('ns'))"
+                ),
+                Some(locals),
+                None,
+            )
+            .unwrap();
+        let amounts = py
+            .eval(c_str!("pyarrow.chunked_array([amounts[:5], amounts[5:]])"), Some(locals), None)
+            .unwrap();
+        let result: f64 = pyxirr_call!(py, "xirr", (dates, amounts));
+        assert_almost_eq!(result, EXPECTED);
+
+        let table = py
+            .eval(c_str!("pyarrow.table({'date': dates, 'amount': amounts})"), Some(locals), None)
+            .unwrap();
+        let result: f64 = pyxirr_call!(py, "xirr", (table,));
+        assert_almost_eq!(result, EXPECTED);
+
+        // timestamps with a time zone are not read zero-copy, but converted to datetimes
+        let dates = py
+            .eval(
+                c_str!("pyarrow.array(dates).cast(pyarrow.timestamp('us', tz='UTC'))"),
+                Some(locals),
+                None,
+            )
+            .unwrap();
+        let amounts = py
+            .eval(
+                c_str!("pyarrow.array(amounts).cast(pyarrow.decimal128(12, 2))"),
+                Some(locals),
+                None,
+            )
+            .unwrap();
+        let result: f64 = pyxirr_call!(py, "xirr", (dates, amounts));
+        assert_almost_eq!(result, EXPECTED);
+    });
+}
+
+#[rstest]
+#[cfg_attr(feature = "nonumpy", ignore)]
+fn test_extract_from_polars() {
+    Python::with_gil(|py| {
+        let locals = &get_locals(py, Some(&["polars"]));
+        let dates = py.eval(c_str!("polars.Series(dates)"), Some(locals), None).unwrap();
+        let amounts = py.eval(c_str!("polars.Series(amounts)"), Some(locals), None).unwrap();
+        let result: f64 = pyxirr_call!(py, "xirr", (dates, amounts));
+        assert_almost_eq!(result, EXPECTED);
+
+        let frame = py
+            .eval(
+                c_str!("polars.DataFrame({'date': dates, 'amount': amounts})"),
+                Some(locals),
+                None,
+            )
+            .unwrap();
+        let result: f64 = pyxirr_call!(py, "xirr", (frame,));
+        assert_almost_eq!(result, EXPECTED);
+
+        // missing values are nulls
+        let amounts =
+            py.eval(c_str!("polars.Series([*amounts, None])"), Some(locals), None).unwrap();
+        let dates =
+            py.eval(c_str!("polars.Series([*dates, dates[-1]])"), Some(locals), None).unwrap();
+        let kwargs = py_dict!(py, "nan_policy" => "omit");
+        let result: f64 = pyxirr_call!(py, "xirr", (dates, amounts), kwargs);
+        assert_almost_eq!(result, EXPECTED);
+    });
+}
+
 #[rstest]
 #[cfg_attr(feature = "nonumpy", ignore)]
 fn test_failed_extract_from_pandas_series_with_int64_index() {
@@ -235,6 +430,7 @@ fn test_payments_different_sign() {
         let amounts = py.eval(c_str!("(abs(x) for x in amounts)"), Some(locals), None).unwrap();
         let err = pyxirr_call_impl!(py, "xirr", (dates.clone(), amounts.clone())).unwrap_err();
         assert!(err.is_instance_of::<pyxirr::InvalidPaymentsError>(py));
+        assert!(err.is_instance_of::<pyxirr::NoSignChangeError>(py));
 
         let amounts = py.eval(c_str!("(-abs(x) for x in amounts)"), Some(locals), None).unwrap();
         let err = pyxirr_call_impl!(py, "xirr", (dates, amounts)).unwrap_err();
@@ -248,7 +444,478 @@ fn test_arrays_of_dirrerent_lengths() {
         let locals = &get_locals(py, None);
         let dates = locals.get_item("dates").unwrap();
         let amounts = py.eval(c_str!("amounts[:-2]"), Some(locals), None).unwrap();
+        let (amounts_len, dates_len) =
+            (amounts.len().unwrap(), dates.as_ref().unwrap().len().unwrap());
         let err = pyxirr_call_impl!(py, "xirr", (dates, amounts)).unwrap_err();
         assert!(err.is_instance_of::<pyxirr::InvalidPaymentsError>(py));
+        assert!(err.is_instance_of::<pyxirr::LengthMismatchError>(py));
+
+        let value = err.value(py);
+        assert_eq!(value.getattr("amounts_len").unwrap().extract::<usize>().unwrap(), amounts_len);
+        assert_eq!(value.getattr("dates_len").unwrap().extract::<usize>().unwrap(), dates_len);
+        assert_eq!(
+            value.to_string(),
+            format!(
+                "the amounts and dates arrays are of different lengths \
+                 (amounts: {amounts_len}, dates: {dates_len})"
+            )
+        );
+    })
+}
+
+#[rstest]
+fn test_nan_amount() {
+    Python::with_gil(|py| {
+        let amounts = vec![-100., f64::NAN, 110.];
+        let err = pyxirr_call_impl!(py, "irr", (amounts,)).unwrap_err();
+        assert!(err.is_instance_of::<pyxirr::InvalidPaymentsError>(py));
+        assert!(err.is_instance_of::<pyxirr::NaNAmountError>(py));
+        assert_eq!(err.value(py).to_string(), "amount at position 1 is NaN");
+        assert_eq!(err.value(py).getattr("index").unwrap().extract::<usize>().unwrap(), 1);
+    })
+}
+
+#[rstest]
+fn test_missing_date() {
+    Python::with_gil(|py| {
+        let dates = py.eval(c_str!("['2020-01-01', None, '2021-01-01']"), None, None).unwrap();
+        let amounts = vec![-100., 5., 110.];
+        let err = pyxirr_call_impl!(py, "xirr", (dates, amounts)).unwrap_err();
+        assert!(err.is_instance_of::<pyxirr::InvalidPaymentsError>(py));
+        assert!(err.is_instance_of::<pyxirr::MissingDateError>(py));
+        assert_eq!(err.value(py).to_string(), "date at position 1 is missing");
+        assert_eq!(err.value(py).getattr("index").unwrap().extract::<usize>().unwrap(), 1);
+    })
+}
+
+#[rstest]
+fn test_nan_policy() {
+    Python::with_gil(|py| {
+        let dates = py
+            .eval(c_str!("['2020-01-01', None, '2020-06-01', '2021-01-01']"), None, None)
+            .unwrap();
+        let amounts = vec![-100., 5., f64::NAN, 110.];
+        let expected: f64 =
+            pyxirr_call!(py, "xirr", (vec!["2020-01-01", "2021-01-01"], vec![-100., 110.]));
+
+        let kwargs = py_dict!(py, "nan_policy" => "omit");
+        let result: f64 = pyxirr_call!(py, "xirr", (dates.clone(), amounts.clone()), kwargs);
+        assert_almost_eq!(result, expected);
+
+        let kwargs = py_dict!(py, "nan_policy" => "zero");
+        let result: f64 = pyxirr_call!(py, "xirr", (dates.clone(), amounts.clone()), kwargs);
+        assert_almost_eq!(result, expected);
+
+        let kwargs = py_dict!(py, "nan_policy" => "zero");
+        let result: f64 = pyxirr_call!(py, "npv", (0.1, vec![-100., f64::NAN, 110.]), kwargs);
+        let expected: f64 = pyxirr_call!(py, "npv", (0.1, vec![-100., 0., 110.]));
+        assert_almost_eq!(result, expected);
+
+        // periodic amounts are positional, "omit" would shift the following ones
+        let kwargs = py_dict!(py, "nan_policy" => "omit");
+        let err = pyxirr_call_impl!(py, "npv", (0.1, vec![-100., f64::NAN, 110.]), kwargs);
+        assert!(err.unwrap_err().is_instance_of::<exceptions::PyValueError>(py));
+
+        let kwargs = py_dict!(py, "nan_policy" => "silently");
+        let err = pyxirr_call_impl!(py, "xirr", (dates, amounts), kwargs).unwrap_err();
+        assert!(err.is_instance_of::<exceptions::PyValueError>(py));
+    })
+}
+
+#[rstest]
+fn test_pe_nan_policy() {
+    Python::with_gil(|py| {
+        let pe = common::get_pyxirr_module(py).getattr("pe").unwrap();
+        let func = |name: &str| pe.getattr(name).unwrap();
+        let nan_policy = |nan_policy: &str| py_dict!(py, "nan_policy" => nan_policy);
+
+        let amounts = vec![-100., f64::NAN, 60., 40.];
+        let index = vec![100., 110., 120., 130.];
+        let args = PyTuple::new(py, [&amounts, &index]).unwrap();
+        let err = func("ks_pme").call1(&args).unwrap_err();
+        assert!(err.is_instance_of::<pyxirr::NaNAmountError>(py));
+        assert_eq!(err.value(py).getattr("index").unwrap().extract::<usize>().unwrap(), 1);
+
+        let result: f64 =
+            func("ks_pme").call(&args, Some(&nan_policy("zero"))).unwrap().extract().unwrap();
+        let expected: f64 =
+            func("ks_pme").call1((vec![-100., 0., 60., 40.], &index)).unwrap().extract().unwrap();
+        assert_almost_eq!(result, expected);
+
+        let err = func("ks_pme").call(&args, Some(&nan_policy("omit"))).unwrap_err();
+        assert!(err.is_instance_of::<exceptions::PyValueError>(py));
+
+        // every series of an array of series
+        let amounts = vec![vec![-100., f64::NAN, 60., 40.], vec![-100., -50., 60., 40.]];
+        let args = PyTuple::new(py, [&amounts]).unwrap();
+        let result: Vec<f64> =
+            func("tvpi").call(&args, Some(&nan_policy("zero"))).unwrap().extract().unwrap();
+        assert_almost_eq!(result[0], 1.0);
+        assert_almost_eq!(result[1], 100. / 150.);
+        let err = func("tvpi").call1(&args).unwrap_err();
+        assert!(err.is_instance_of::<pyxirr::NaNAmountError>(py));
+
+        let kwargs = py_dict!(py, "decimal" => true, "nan_policy" => "zero");
+        let result = func("dpi").call((vec![-100., f64::NAN, 50.],), Some(&kwargs)).unwrap();
+        assert!(result.eq(0.5).unwrap());
+
+        // the waterfall allocations are aligned with the dates
+        let args = (["2021-01-01", "2022-01-01"], [100., f64::NAN], [0., 150.]);
+        let err = func("waterfall").call1(args).unwrap_err();
+        assert!(err.is_instance_of::<pyxirr::NaNAmountError>(py));
+        let result = func("waterfall").call(args, Some(&nan_policy("zero"))).unwrap();
+        let gp_flows: Vec<f64> = result.get_item("gp_flows").unwrap().extract().unwrap();
+        assert_eq!(gp_flows, [0., 10.]);
+
+        let args = (100., 5., [10., f64::NAN, 20.], [0., 5., 10.], [10., 20., 30.]);
+        let err = func("takahashi_alexander_calibrate").call1(args).unwrap_err();
+        assert!(err.is_instance_of::<pyxirr::NaNAmountError>(py));
+
+        // dated cash flows can be omitted
+        let fees = |invested: Bound<PyDict>, kwargs: Option<&Bound<PyDict>>| {
+            let args = (100., 0.02, "2020-01-01", "2022-01-01", "2023-01-01");
+            let kwargs = kwargs.cloned().unwrap_or_else(|| PyDict::new(py));
+            kwargs.set_item("invested", invested).unwrap();
+            let result = func("management_fees").call(args, Some(&kwargs))?;
+            result.get_item("total")?.extract::<Vec<f64>>()
+        };
+        let invested = py_dict!(py, "2021-01-01" => 80., "2021-06-01" => f64::NAN);
+        let err = fees(invested.clone(), None).unwrap_err();
+        assert!(err.is_instance_of::<pyxirr::NaNAmountError>(py));
+        let result = fees(invested, Some(&nan_policy("omit"))).unwrap();
+        let expected = fees(py_dict!(py, "2021-01-01" => 80.), None).unwrap();
+        assert_eq!(result, expected);
+    })
+}
+
+#[rstest]
+fn test_takahashi_alexander() {
+    Python::with_gil(|py| {
+        let pe = common::get_pyxirr_module(py).getattr("pe").unwrap();
+        let func = |name: &str| pe.getattr(name).unwrap();
+
+        let p = func("takahashi_alexander").call1((100., 12., 0.25, 2.5, 0.12, 0.1, 12)).unwrap();
+        let item = |key: &str| p.get_item(key).unwrap();
+        let nav = item("nav").get_item(-1).unwrap();
+
+        let tvpi: f64 = func("tvpi_2")
+            .call1((item("contributions"), item("distributions"), &nav))
+            .unwrap()
+            .extract()
+            .unwrap();
+        assert!(tvpi > 1.0);
+
+        // the signed net amounts feed irr
+        let (c, d, amounts): (Vec<f64>, Vec<f64>, Vec<f64>) = (
+            item("contributions").extract().unwrap(),
+            item("distributions").extract().unwrap(),
+            item("amounts").extract().unwrap(),
+        );
+        for i in 0..12 {
+            assert_almost_eq!(amounts[i], d[i] - c[i]);
+        }
+        let rate: f64 = pyxirr_call!(py, "irr", (amounts,));
+        assert!(rate.is_finite());
+
+        // the distribution rate 0.1 * (48 / 12)^2.5 exceeds 1
+        let err = func("takahashi_alexander").call1((100., 12., 0.25, 2.5, 0.12, 0.1, 48));
+        assert!(err.unwrap_err().is_instance_of::<pyxirr::InvalidPaymentsError>(py));
+    })
+}
+
+#[rstest]
+fn test_date_format() {
+    Python::with_gil(|py| {
+        let amounts = vec![-100., 110.];
+        let expected: f64 =
+            pyxirr_call!(py, "xirr", (vec!["2024-03-04", "2025-04-03"], amounts.clone()));
+
+        let dates = vec!["04.03.2024", "03.04.2025"];
+        let result: f64 = pyxirr_call!(py, "xirr", (dates, amounts.clone()));
+        assert_almost_eq!(result, expected);
+
+        let dates = vec!["2024-W10-1", "2025-W14-4"];
+        let result: f64 = pyxirr_call!(py, "xirr", (dates, amounts.clone()));
+        assert_almost_eq!(result, expected);
+
+        let dates = vec!["04/03/2024", "03/04/2025"];
+        let err = pyxirr_call_impl!(py, "xirr", (dates.clone(), amounts.clone())).unwrap_err();
+        assert!(err.is_instance_of::<pyxirr::AmbiguousDateError>(py));
+        assert!(err.is_instance_of::<exceptions::PyValueError>(py));
+
+        let kwargs = py_dict!(py, "date_format" => "%d/%m/%Y");
+        let result: f64 = pyxirr_call!(py, "xirr", (dates.clone(), amounts.clone()), kwargs);
+        assert_almost_eq!(result, expected);
+
+        let pe = common::get_pyxirr_module(py).getattr("pe").unwrap();
+        let waterfall = pe.getattr("waterfall").unwrap();
+        let args = (dates.clone(), [100., 0.], [0., 150.]);
+        let err = waterfall.call1(args.clone()).unwrap_err();
+        assert!(err.is_instance_of::<pyxirr::AmbiguousDateError>(py));
+        let result = waterfall.call(args, Some(&kwargs)).unwrap();
+        let lp_flows: Vec<f64> = result.get_item("lp_flows").unwrap().extract().unwrap();
+        assert_eq!(lp_flows, [-100., 140.]);
+
+        let kwargs = py_dict!(py, "date_format" => "%d %b %y");
+        let dates = vec!["4 Mar 24", "3 Apr 25"];
+        let result: f64 = pyxirr_call!(py, "xirr", (dates, amounts.clone()), kwargs);
+        assert_almost_eq!(result, expected);
+
+        let kwargs = py_dict!(py, "date_format" => "%d.%m.%Y");
+        let err = pyxirr_call_impl!(py, "xirr", (vec!["2024-03-04"], vec![1.]), kwargs);
+        assert!(err.unwrap_err().is_instance_of::<exceptions::PyValueError>(py));
+    })
+}
+
+#[rstest]
+fn test_excel_serial() {
+    Python::with_gil(|py| {
+        let amounts = vec![-100., 110.];
+        let expected: f64 =
+            pyxirr_call!(py, "xirr", (vec!["2024-03-04", "2025-04-03"], amounts.clone()));
+
+        let kwargs = py_dict!(py, "excel_serial" => true);
+        let result: f64 = pyxirr_call!(py, "xirr", (vec![45355, 45750], amounts.clone()), kwargs);
+        assert_almost_eq!(result, expected);
+
+        let kwargs = py_dict!(py, "excel_serial" => 1904);
+        let result: f64 = pyxirr_call!(py, "xirr", (vec![43893., 44288.], amounts.clone()), kwargs);
+        assert_almost_eq!(result, expected);
+
+        // date objects and strings are still accepted
+        let dates = py.eval(c_str!("[45355.0, '2025-04-03', None]"), None, None).unwrap();
+        let kwargs = py_dict!(py, "excel_serial" => "1900", "nan_policy" => "omit");
+        let result: f64 = pyxirr_call!(py, "xnpv", (0.1, dates, vec![-100., 110., 5.]), kwargs);
+        let expected: f64 =
+            pyxirr_call!(py, "xnpv", (0.1, vec!["2024-03-04", "2025-04-03"], amounts.clone()));
+        assert_almost_eq!(result, expected);
+
+        let kwargs = py_dict!(py, "excel_serial" => 1901);
+        let err = pyxirr_call_impl!(py, "xirr", (vec![45355, 45750], amounts), kwargs);
+        assert!(err.unwrap_err().is_instance_of::<exceptions::PyValueError>(py));
+
+        let kwargs = py_dict!(py, "excel_serial" => true, "intraday" => true);
+        let result: f64 = pyxirr_call!(py, "year_fraction", (45355.5, 45356.0, "act/360"), kwargs);
+        assert_almost_eq!(result, 0.5 / 360.);
+    })
+}
+
+#[rstest]
+fn test_decimal() {
+    Python::with_gil(|py| {
+        let locals = &get_locals(py, Some(&["decimal"]));
+        let eval = |code| py.eval(code, Some(locals), None).unwrap();
+        let is_decimal = |obj: &Bound<PyAny>| obj.is_instance(&eval(c_str!("decimal.Decimal")));
+
+        let amounts = eval(c_str!("[-0.1] * 10 + [decimal.Decimal('0.3')] * 3 + [0.1]"));
+        let kwargs = py_dict!(py, "decimal" => true);
+        let result = pyxirr_call_impl!(py, "npv", (0, &amounts), kwargs).unwrap();
+        assert!(is_decimal(&result).unwrap());
+        assert!(result.eq(eval(c_str!("decimal.Decimal('0.0')"))).unwrap());
+
+        let kwargs = py_dict!(py, "decimal" => true, "start_from_zero" => false);
+        let result = pyxirr_call_impl!(py, "npv", (0.1, vec![110, 121]), kwargs).unwrap();
+        assert!(result.eq(200).unwrap());
+
+        let kwargs = py_dict!(py, "decimal" => true);
+        let err = pyxirr_call_impl!(py, "npv", (vec![0.1, 0.2], vec![110, 121]), kwargs);
+        assert!(err.unwrap_err().is_instance_of::<exceptions::PyTypeError>(py));
+
+        let dates = vec!["2020-01-01", "2021-01-01", "2022-01-01"];
+        let amounts = eval(c_str!("[decimal.Decimal('-100'), None, 121]"));
+        let kwargs = py_dict!(py, "decimal" => true, "nan_policy" => "zero");
+        let result = pyxirr_call_impl!(py, "xnpv", (0.1, dates.clone(), &amounts), kwargs);
+        let result = result.unwrap();
+        assert!(is_decimal(&result).unwrap());
+        let expected: f64 = pyxirr_call!(py, "xnpv", (0.1, dates, vec![-100., 0., 121.]));
+        assert_almost_eq!(result.extract::<f64>().unwrap(), expected);
+
+        let pe = common::get_pyxirr_module(py).getattr("pe").unwrap();
+        let amounts = eval(c_str!("[-0.1, -0.2, 0.3]"));
+        let kwargs = py_dict!(py, "decimal" => true);
+        let result = pe.getattr("dpi").unwrap().call((&amounts,), Some(&kwargs)).unwrap();
+        assert!(result.eq(1).unwrap());
+        let result = pe.getattr("tvpi").unwrap().call((&amounts, 0.3), Some(&kwargs)).unwrap();
+        assert!(result.eq(2).unwrap());
+        let result = pe.getattr("rvpi").unwrap().call(([-0.1, -0.2], 0.3), Some(&kwargs)).unwrap();
+        assert!(result.eq(1).unwrap());
+        let err = pe.getattr("dpi").unwrap().call(([0.0, 0.3],), Some(&kwargs)).unwrap_err();
+        assert!(err.is_instance_of::<pyxirr::ZeroContributionsError>(py));
+    })
+}
+
+#[rstest]
+fn test_pe_broadcasting() {
+    Python::with_gil(|py| {
+        let pe = common::get_pyxirr_module(py).getattr("pe").unwrap();
+        let func = |name: &str| pe.getattr(name).unwrap();
+
+        let amounts = vec![-100., -50., 60., 40.];
+        let navs = vec![0., 50., 100.];
+        let result: Vec<f64> = func("tvpi").call1((&amounts, &navs)).unwrap().extract().unwrap();
+        for (nav, tvpi) in navs.iter().zip(result) {
+            let expected: f64 = func("tvpi").call1((&amounts, nav)).unwrap().extract().unwrap();
+            assert_almost_eq!(tvpi, expected);
+        }
+
+        // a matrix of index paths with a NAV per path
+        let index = vec![vec![100., 110., 120., 130.], vec![100., 90., 95., 105.]];
+        let navs = vec![10., 20.];
+        let result: Vec<f64> =
+            func("ks_pme").call1((&amounts, &index, &navs)).unwrap().extract().unwrap();
+        assert_eq!(result.len(), 2);
+        for ((index, nav), ks_pme) in index.iter().zip(&navs).zip(result) {
+            let expected: f64 =
+                func("ks_pme").call1((&amounts, index, nav)).unwrap().extract().unwrap();
+            assert_almost_eq!(ks_pme, expected);
+        }
+
+        // leading dimensions broadcast against each other
+        let navs = vec![vec![10.], vec![20.], vec![30.]];
+        let result: Vec<Vec<f64>> =
+            func("direct_alpha").call1((&amounts, &index, &navs)).unwrap().extract().unwrap();
+        assert_eq!((result.len(), result[0].len()), (3, 2));
+        let expected: f64 =
+            func("direct_alpha").call1((&amounts, &index[1], 30.)).unwrap().extract().unwrap();
+        assert_almost_eq!(result[2][1], expected);
+
+        // an explicit None is no NAV
+        let none = py.None();
+        let result: f64 = func("tvpi").call1((&amounts, &none)).unwrap().extract().unwrap();
+        assert_almost_eq!(result, 100. / 150.);
+        let result: f64 =
+            func("ks_pme").call1((&amounts, &index[0], &none)).unwrap().extract().unwrap();
+        let expected: f64 = func("ks_pme").call1((&amounts, &index[0])).unwrap().extract().unwrap();
+        assert_almost_eq!(result, expected);
+
+        let err = func("ks_pme").call1((&amounts, &index, vec![1., 2., 3.])).unwrap_err();
+        assert!(err.is_instance_of::<exceptions::PyValueError>(py));
+
+        let err = func("dpi").call1((vec![vec![-100., 50.], vec![0., 50.]],)).unwrap_err();
+        assert!(err.is_instance_of::<pyxirr::ZeroContributionsError>(py));
+    })
+}
+
+#[rstest]
+fn test_named_columns() {
+    Python::with_gil(|py| {
+        let locals = &get_locals(py, None);
+        let frame = py
+            .eval(
+                c_str!("{'id': [1] * len(dates), 'amount': amounts, 'date': dates}"),
+                Some(locals),
+                None,
+            )
+            .unwrap();
+        let kwargs = py_dict!(py, "date_col" => "date", "amount_col" => "amount");
+        let result: f64 = pyxirr_call!(py, "xirr", (frame.clone(),), kwargs);
+        assert_almost_eq!(result, EXPECTED);
+
+        // amounts are the first column that is not selected
+        let kwargs = py_dict!(py, "date_col" => "date", "by" => "id");
+        let result: Bound<PyDict> = pyxirr_call!(py, "xirr", (frame.clone(),), kwargs);
+        let result: f64 = result.get_item(1).unwrap().unwrap().extract().unwrap();
+        assert_almost_eq!(result, EXPECTED);
+
+        let kwargs = py_dict!(py, "date_col" => "missing", "amount_col" => "amount");
+        let err = pyxirr_call_impl!(py, "xirr", (frame,), kwargs).unwrap_err();
+        assert!(err.is_instance_of::<exceptions::PyKeyError>(py));
+    })
+}
+
+#[rstest]
+fn test_group_by() {
+    Python::with_gil(|py| {
+        let frame = py
+            .eval(
+                c_str!(
+                    "{
+                    'fund': ['a', 'b', 'a', 'b', 'c'],
+                    'date': ['2020-01-01', '2020-01-01', '2021-01-01', '2021-01-01', '2021-01-01'],
+                    'amount': [-100, -100, 110, 120, 1],
+                }"
+                ),
+                None,
+                None,
+            )
+            .unwrap();
+        let kwargs = py_dict!(py, "by" => "fund", "silent" => true);
+        let result: Bound<PyAny> = pyxirr_call!(py, "xnfv", (0.1, frame.clone()), kwargs);
+        let result = result.downcast::<PyDict>().unwrap();
+        let keys: Vec<String> = result.keys().extract().unwrap();
+        assert_eq!(keys, ["a", "b", "c"]);
+
+        let a: f64 = result.get_item("a").unwrap().unwrap().extract().unwrap();
+        let expected: f64 =
+            pyxirr_call!(py, "xnfv", (0.1, vec!["2020-01-01", "2021-01-01"], vec![-100, 110]));
+        assert_almost_eq!(a, expected);
+        // invalid payments are None with silent=True
+        assert!(result.get_item("c").unwrap().unwrap().is_none());
+
+        let kwargs = py_dict!(py, "by" => "fund");
+        let err = pyxirr_call_impl!(py, "xirr", (frame,), kwargs).unwrap_err();
+        assert!(err.is_instance_of::<pyxirr::NoSignChangeError>(py));
+    })
+}
+
+#[rstest]
+#[cfg_attr(feature = "nonumpy", ignore)]
+fn test_pandas_group_by() {
+    Python::with_gil(|py| {
+        let locals = &get_locals(py, Some(&["pandas"]));
+        let frame = py
+            .eval(
+                c_str!(
+                    "pandas.concat([
+                    pandas.DataFrame({'fund': 'a', 'date': dates, 'amount': amounts}),
+                    pandas.DataFrame({'fund': 'b', 'date': dates, 'amount': amounts}),
+                ])"
+                ),
+                Some(locals),
+                None,
+            )
+            .unwrap();
+        let kwargs = py_dict!(py, "by" => "fund");
+        let result: Bound<PyAny> = pyxirr_call!(py, "xirr", (frame,), kwargs);
+        assert_eq!(result.get_type().name().unwrap(), "Series");
+        let values: Vec<f64> = result.call_method0("tolist").unwrap().extract().unwrap();
+        assert_almost_eq!(values[0], EXPECTED);
+        assert_almost_eq!(values[1], EXPECTED);
+
+        // one rate per column with a DatetimeIndex
+        let frame = py
+            .eval(
+                c_str!("pandas.DataFrame({'a': amounts, 'b': amounts}, index=pandas.to_datetime(dates))"),
+                Some(locals),
+                None,
+            )
+            .unwrap();
+        let result: Bound<PyAny> = pyxirr_call!(py, "xirr", (frame,));
+        let keys: Vec<String> = result.getattr("index").unwrap().extract().unwrap();
+        assert_eq!(keys, ["a", "b"]);
+        let values: Vec<f64> = result.call_method0("tolist").unwrap().extract().unwrap();
+        assert_almost_eq!(values[1], EXPECTED);
+
+        // a date column keeps the first two columns as dates and amounts
+        let frame = py
+            .eval(
+                c_str!("pandas.DataFrame({'date': pandas.to_datetime(dates), 'amount': amounts}, index=pandas.to_datetime(dates))"),
+                Some(locals),
+                None,
+            )
+            .unwrap();
+        let result: f64 = pyxirr_call!(py, "xirr", (frame,));
+        assert_almost_eq!(result, EXPECTED);
+
+        // a single amount column is a single cash flow
+        let frame = py
+            .eval(
+                c_str!("pandas.DataFrame({'amount': amounts}, index=pandas.to_datetime(dates))"),
+                Some(locals),
+                None,
+            )
+            .unwrap();
+        let result: f64 = pyxirr_call!(py, "xirr", (frame,));
+        assert_almost_eq!(result, EXPECTED);
     })
 }
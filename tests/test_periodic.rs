@@ -126,6 +126,50 @@ fn test_fv_vectorized_ndarray() {
     })
 }
 
+#[rstest]
+#[cfg_attr(feature = "nonumpy", ignore)]
+fn test_fv_ufunc() {
+    Python::with_gil(|py| {
+        let locals = py_dict!(
+            py,
+            "np" => py.import("numpy").unwrap(),
+            "ufunc" => common::get_pyxirr_module(py).getattr("ufunc").unwrap()
+        );
+        let eval = |code| py.eval(code, Some(&locals), None).unwrap();
+
+        let rates = eval(c_str!("np.array([[0.05 / 12, 0.06 / 12], [0.07 / 12, 0.0]])"));
+        let actual = eval(c_str!("ufunc.fv(rates, 10 * 12, -100, -100, False)"));
+        let expected: Bound<PyAny> =
+            pyxirr_call_impl!(py, "fv", (&rates, 10 * 12, -100, -100)).unwrap();
+        locals.set_item("actual", actual).unwrap();
+        locals.set_item("expected", expected).unwrap();
+        assert!(eval(c_str!("np.allclose(actual, expected)")).is_truthy().unwrap());
+
+        // out=, where= and dtype come from numpy
+        let result = eval(c_str!(
+            "ufunc.fv(rates, 120, -100, -100, 0, out=np.zeros((2, 2)), where=rates > 0)"
+        ));
+        assert_eq!(result.get_item((1, 1)).unwrap().extract::<f64>().unwrap(), 0.0);
+        let dtype = eval(c_str!("ufunc.pmt(rates.astype(np.float32), 120, 1000, 0, 0).dtype.name"));
+        assert_eq!(dtype.extract::<String>().unwrap(), "float32");
+        // the default guess, as in pyxirr.rate
+        let result = eval(c_str!("ufunc.rate(10, 0, -3500, 10000, 0)"));
+        let expected: f64 = pyxirr_call!(py, "rate", (10, 0, -3500, 10000));
+        assert_almost_eq!(result.extract::<f64>().unwrap(), expected, 1e-8);
+    })
+}
+
+#[rstest]
+fn test_ufunc_module() {
+    Python::with_gil(|py| {
+        let ufunc = common::get_pyxirr_module(py).getattr("ufunc").unwrap();
+        let names: Vec<String> = ufunc.getattr("__all__").unwrap().extract().unwrap();
+        assert_eq!(names, ["fv", "pv", "pmt", "ipmt", "ppmt", "nper", "rate"]);
+        let err = ufunc.getattr("npv").unwrap_err();
+        assert!(err.is_instance_of::<pyo3::exceptions::PyAttributeError>(py));
+    })
+}
+
 // ------------ PV ----------------
 
 #[rstest]
@@ -209,6 +253,124 @@ fn test_npv_zero_rate() {
     });
 }
 
+#[rstest]
+fn test_npv_axis() {
+    Python::with_gil(|py| {
+        let rows =
+            vec![vec![-40_000., 5_000., 8_000., 12_000., 30_000.], vec![-100., 0., 0., 0., 100.]];
+        let kwargs = py_dict!(py, "axis" => 1);
+        let result: Vec<f64> = pyxirr_call!(py, "npv", (0.08, rows.clone()), kwargs);
+        assert_almost_eq!(result[0], 3065.222668179);
+        assert_almost_eq!(result[1], -100. + 100. / 1.08_f64.powi(4));
+
+        // cash flows along the columns
+        let columns: Vec<Vec<f64>> = (0..5).map(|i| rows.iter().map(|r| r[i]).collect()).collect();
+        let kwargs = py_dict!(py, "axis" => 0);
+        let transposed: Vec<f64> = pyxirr_call!(py, "npv", (0.08, columns), kwargs);
+        assert_eq!(result, transposed);
+    });
+}
+
+// ------------ PROFITABILITY INDEX / EAA / NPV PROFILE ----------------
+
+#[rstest]
+fn test_profitability_index() {
+    Python::with_gil(|py| {
+        let values = [-40_000., 5_000., 8_000., 12_000., 30_000.];
+        let result: f64 = pyxirr_call!(py, "profitability_index", (0.08, values));
+        assert_almost_eq!(result, (3065.222668179 + 40_000.) / 40_000.);
+
+        let result: f64 = pyxirr_call!(py, "profitability_index", (0.0, [-100., -100., 300.]));
+        assert_almost_eq!(result, 1.5);
+
+        let err = pyxirr_call_impl!(py, "profitability_index", (0.08, [100., 200.])).unwrap_err();
+        assert!(err.is_instance_of::<pyxirr::InvalidPaymentsError>(py));
+    });
+}
+
+#[rstest]
+fn test_eaa() {
+    Python::with_gil(|py| {
+        let values = [-40_000., 5_000., 8_000., 12_000., 30_000.];
+        let result: f64 = pyxirr_call!(py, "eaa", (0.08, values));
+        let expected: f64 = pyxirr_call!(py, "pmt", (0.08, 4, -3065.222668179));
+        assert_almost_eq!(result, expected);
+
+        // Excel compatible: the first value is discounted, the life is 5 periods
+        let kwargs = py_dict!(py, "start_from_zero" => false);
+        let result: f64 = pyxirr_call!(py, "eaa", (0.08, values), kwargs);
+        let expected: f64 = pyxirr_call!(py, "pmt", (0.08, 5, -2838.169137203));
+        assert_almost_eq!(result, expected);
+
+        let result: Option<f64> = pyxirr_call!(py, "eaa", (0.08, [-100.]));
+        assert_eq!(result, None);
+    });
+}
+
+#[rstest]
+fn test_npv_profile() {
+    Python::with_gil(|py| {
+        let (a, b) = ([-1000., 800., 400.], [-1000., 100., 1300.]);
+        let rates = [0.0, 0.1, 0.2];
+
+        let result = pyxirr_call_impl!(py, "npv_profile", (rates, a)).unwrap();
+        let npv: Vec<f64> = result.get_item("npv").unwrap().extract().unwrap();
+        for (value, rate) in npv.iter().zip(rates) {
+            let expected: f64 = pyxirr_call!(py, "npv", (rate, a));
+            assert_almost_eq!(value, expected);
+        }
+        assert!(result.get_item("crossover_rate").is_err());
+
+        let kwargs = py_dict!(py, "other" => b);
+        let result = pyxirr_call_impl!(py, "npv_profile", (rates, a), kwargs).unwrap();
+        let other: Vec<f64> = result.get_item("other").unwrap().extract().unwrap();
+        assert_almost_eq!(other[0], 400.);
+
+        // -700 / (1 + r) + 900 / (1 + r)^2 = 0
+        let crossover: f64 = result.get_item("crossover_rate").unwrap().extract().unwrap();
+        assert_almost_eq!(crossover, 900. / 700. - 1.);
+
+        let kwargs = py_dict!(py, "other" => a);
+        let result = pyxirr_call_impl!(py, "npv_profile", (rates, a), kwargs).unwrap();
+        assert!(result.get_item("crossover_rate").unwrap().is_none());
+    });
+}
+
+// ------------ PAYBACK ----------------
+
+#[rstest]
+#[case(&[-1000., 500., 500., 500.], Some(2.0))]
+#[case(&[-1000., 300., 300., 600.], Some(2.0 + 400. / 600.))]
+#[case(&[-500., -500., 400., 800.], Some(2.0 + 600. / 800.))]
+#[case(&[-1000., 300., 300.], None)]
+#[case(&[1000., -300., -300.], None)]
+fn test_payback(#[case] values: &[f64], #[case] expected: Option<f64>) {
+    Python::with_gil(|py| {
+        let result: Option<f64> = pyxirr_call!(py, "payback", (values.to_vec(),));
+        match (result, expected) {
+            (Some(result), Some(expected)) => assert_almost_eq!(result, expected),
+            (result, expected) => assert_eq!(result, expected),
+        }
+    });
+}
+
+#[rstest]
+fn test_discounted_payback() {
+    Python::with_gil(|py| {
+        let values = [-1000., 500., 500., 500.];
+        let result: f64 = pyxirr_call!(py, "discounted_payback", (0.1, values));
+        // -1000 + 454.55 + 413.22 = -132.23, paid back by 375.66 in the third period
+        let last = 500. / 1.1_f64.powi(3);
+        assert_almost_eq!(result, 2. + (1000. - 500. / 1.1 - 500. / 1.21) / last);
+
+        let result: Option<f64> = pyxirr_call!(py, "discounted_payback", (0.5, values));
+        assert_eq!(result, None);
+
+        let result: f64 = pyxirr_call!(py, "discounted_payback", (0.0, values));
+        assert_almost_eq!(result, 2.0);
+    });
+}
+
 // ------------ PMT ----------------
 
 #[rstest]
@@ -746,6 +908,61 @@ fn test_irr_samples(#[case] input: &str, #[case] expected: f64) {
     });
 }
 
+#[rstest]
+fn test_irr_axis() {
+    Python::with_gil(|py| {
+        let rows = [[-100.0, 39.0, 59.0, 55.0, 20.0], [100.0, 39.0, 59.0, 55.0, 20.0]];
+        let kwargs = py_dict!(py, "axis" => -1);
+        let err = pyxirr_call_impl!(py, "irr", (rows,), kwargs).unwrap_err();
+        assert!(err.is_instance_of::<pyxirr::InvalidPaymentsError>(py));
+
+        let kwargs = py_dict!(py, "axis" => -1, "silent" => true);
+        let result: Vec<Option<f64>> = pyxirr_call!(py, "irr", (rows,), kwargs);
+        assert_almost_eq!(result[0].unwrap(), 0.28094842116);
+        assert!(result[1].is_none());
+
+        let kwargs = py_dict!(py, "axis" => 1, "nan_policy" => "zero");
+        let rows = [[-100.0, f64::NAN, 39.0, 59.0, 55.0, 20.0]];
+        let result: Vec<f64> = pyxirr_call!(py, "irr", (rows,), kwargs);
+        let expected: f64 = pyxirr_call!(py, "irr", ([-100.0, 0.0, 39.0, 59.0, 55.0, 20.0],));
+        assert_almost_eq!(result[0], expected);
+
+        // dropping the NaN would shift the following amounts by one period
+        let kwargs = py_dict!(py, "axis" => 1, "nan_policy" => "omit", "silent" => true);
+        let err = pyxirr_call_impl!(py, "irr", (rows,), kwargs).unwrap_err();
+        assert!(err.is_instance_of::<pyo3::exceptions::PyValueError>(py));
+    });
+}
+
+#[rstest]
+fn test_irr_axis_requires_2d() {
+    Python::with_gil(|py| {
+        let kwargs = py_dict!(py, "axis" => 1);
+        let err =
+            pyxirr_call_impl!(py, "irr", ([-100.0, 39.0, 59.0, 55.0, 20.0],), kwargs).unwrap_err();
+        assert!(err.is_instance_of::<pyo3::exceptions::PyValueError>(py));
+
+        let kwargs = py_dict!(py, "axis" => 2);
+        let err = pyxirr_call_impl!(py, "irr", ([[-100.0, 110.0]],), kwargs).unwrap_err();
+        assert!(err.is_instance_of::<pyo3::exceptions::PyValueError>(py));
+    });
+}
+
+#[rstest]
+#[cfg_attr(feature = "nonumpy", ignore)]
+fn test_irr_axis_ndarray() {
+    Python::with_gil(|py| {
+        let rows = pyarray![py, [-100.0, 39.0, 59.0, 55.0, 20.0], [-100.0, 0.0, 0.0, 0.0, 110.0]];
+        let kwargs = py_dict!(py, "axis" => 1);
+        let result: Bound<PyAny> = pyxirr_call_impl!(py, "irr", (rows,), kwargs).unwrap();
+        let result = result.extract::<numpy::PyReadonlyArrayDyn<f64>>().unwrap();
+        let result = result.as_array();
+        assert_eq!(result.shape(), &[2]);
+        assert_almost_eq!(result[0], 0.28094842116);
+        assert_almost_eq!(result[1], 1.1_f64.powf(0.25) - 1.);
+    });
+}
+
 // ------------ MIRR ----------------
 
 #[rstest]
@@ -778,6 +995,17 @@ fn test_mirr_same_sign() {
     });
 }
 
+#[rstest]
+fn test_mirr_axis() {
+    Python::with_gil(|py| {
+        let rows = [[-1000, 100, 250, 500, 500], [100_000, 50_000, 25_000, 0, 0]];
+        let kwargs = py_dict!(py, "axis" => 1, "silent" => true);
+        let result: Vec<Option<f64>> = pyxirr_call!(py, "mirr", (rows, 0.1, 0.1), kwargs);
+        assert_almost_eq!(result[0].unwrap(), 0.10401626745);
+        assert!(result[1].is_none());
+    });
+}
+
 // ------------ CUMPRINC ----------------
 
 #[rstest]
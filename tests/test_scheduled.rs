@@ -1,3 +1,5 @@
+use std::ffi::CString;
+
 use pyo3::{
     ffi::c_str,
     prelude::*,
@@ -114,6 +116,275 @@ fn test_xirr_silent() {
     })
 }
 
+#[rstest]
+fn test_xirr_xnpv_axis() {
+    Python::with_gil(|py| {
+        let dates = ["2020-01-01", "2020-06-01", "2021-01-01", "2022-01-01"];
+        let rows =
+            [[-1000., 100., 200., 900.], [-1000., 0., f64::NAN, 1100.], [-500., 100., 200., 300.]];
+
+        let kwargs = py_dict!(py, "axis" => 1, "nan_policy" => "zero");
+        let result: Vec<f64> = pyxirr_call!(py, "xirr", (dates, rows), kwargs);
+        assert_eq!(result.len(), rows.len());
+
+        for (row, rate) in rows.iter().zip(&result) {
+            let row = row.map(|a| {
+                if a.is_nan() {
+                    0.
+                } else {
+                    a
+                }
+            });
+            let expected: f64 = pyxirr_call!(py, "xirr", (dates, row));
+            assert_almost_eq!(*rate, expected);
+
+            let npv: f64 = pyxirr_call!(py, "xnpv", (*rate, dates, row));
+            assert_almost_eq!(npv, 0.0, 1e-5);
+        }
+
+        let kwargs = py_dict!(py, "axis" => 1, "nan_policy" => "zero");
+        let result: Vec<f64> = pyxirr_call!(py, "xnpv", (0.1, dates, rows), kwargs);
+        let expected: f64 = pyxirr_call!(py, "xnpv", (0.1, dates, rows[0]));
+        assert_almost_eq!(result[0], expected);
+
+        // the dates are shared by every row
+        let kwargs = py_dict!(py, "axis" => 1);
+        let err = pyxirr_call_impl!(py, "xirr", (&dates[..3], rows), kwargs).unwrap_err();
+        assert!(err.is_instance_of::<pyxirr::InvalidPaymentsError>(py));
+
+        let kwargs = py_dict!(py, "axis" => 1);
+        let err = pyxirr_call_impl!(py, "xnpv", ([0.1, 0.2], dates, rows), kwargs).unwrap_err();
+        assert!(err.is_instance_of::<pyo3::exceptions::PyTypeError>(py));
+    })
+}
+
+#[rstest]
+#[case("'annual'")]
+#[case("'continuous'")]
+#[case("'simple'")]
+#[case("12")]
+fn test_xirr_xnpv_compounding(#[case] compounding: &str) {
+    Python::with_gil(|py| {
+        let compounding = &py.eval(&CString::new(compounding).unwrap(), None, None).unwrap();
+        let dates = ["2020-01-01", "2020-06-01", "2021-01-01", "2022-03-01"];
+        let amounts = [-1000., 100., 200., 900.];
+
+        let kwargs = py_dict!(py, "compounding" => compounding);
+        let rate: f64 = pyxirr_call!(py, "xirr", (dates, amounts), kwargs);
+        let kwargs = py_dict!(py, "compounding" => compounding);
+        let npv: f64 = pyxirr_call!(py, "xnpv", (rate, dates, amounts), kwargs);
+        assert_almost_eq!(npv, 0.0, 1e-7);
+
+        let kwargs = py_dict!(py, "compounding" => compounding);
+        let rate: f64 = pyxirr_call!(py, "xirr", (&dates[..2], &amounts[..2]), kwargs);
+        let kwargs = py_dict!(py, "compounding" => compounding);
+        let npv: f64 = pyxirr_call!(py, "xnpv", (rate, &dates[..2], &amounts[..2]), kwargs);
+        assert_almost_eq!(npv, 0.0, 1e-7);
+    })
+}
+
+#[rstest]
+fn test_compounding_equivalent_rates() {
+    Python::with_gil(|py| {
+        let dates = ["2020-01-01", "2020-06-01", "2021-01-01", "2022-03-01"];
+        let amounts = [-1000., 100., 200., 900.];
+        let xirr = |compounding: &str| -> f64 {
+            let kwargs = py_dict!(py, "compounding" => compounding);
+            pyxirr_call!(py, "xirr", (dates, amounts), kwargs)
+        };
+
+        let annual = xirr("annual");
+        let expected: f64 = pyxirr_call!(py, "xirr", (dates, amounts));
+        assert_almost_eq!(annual, expected);
+        assert_almost_eq!(xirr("continuous"), annual.ln_1p(), 1e-7);
+
+        let kwargs = py_dict!(py, "compounding" => 4);
+        let quarterly: f64 = pyxirr_call!(py, "xirr", (dates, amounts), kwargs);
+        assert_almost_eq!((1.0 + quarterly / 4.0).powi(4), 1.0 + annual, 1e-7);
+
+        // the future value of the present value
+        let kwargs = py_dict!(py, "compounding" => "continuous");
+        let npv: f64 = pyxirr_call!(py, "xnpv", (0.05, dates, amounts), kwargs);
+        let kwargs = py_dict!(py, "compounding" => "continuous");
+        let nfv: f64 = pyxirr_call!(py, "xnfv", (0.05, dates, amounts), kwargs);
+        assert_almost_eq!(nfv, npv * (0.05f64 * 790.0 / 365.0).exp(), 1e-7);
+
+        let kwargs = py_dict!(py, "compounding" => "simple");
+        let fv: f64 = pyxirr_call!(
+            py,
+            "xfv",
+            ("2020-01-01", "2020-01-01", "2020-12-31", 0.05, 0.05, 100.0),
+            kwargs
+        );
+        assert_almost_eq!(fv, 100.0 * (1.0 + 0.05 * 365.0 / 365.0));
+    })
+}
+
+#[rstest]
+#[case("'monthly'", "ValueError")]
+#[case("0", "ValueError")]
+#[case("1.5", "TypeError")]
+#[case("True", "TypeError")]
+fn test_invalid_compounding(#[case] compounding: &str, #[case] error: &str) {
+    Python::with_gil(|py| {
+        let compounding = py.eval(&CString::new(compounding).unwrap(), None, None).unwrap();
+        let kwargs = py_dict!(py, "compounding" => compounding);
+        let err =
+            pyxirr_call_impl!(py, "xirr", (["2020-01-01", "2021-01-01"], [-100, 110]), kwargs)
+                .unwrap_err();
+        assert_eq!(err.get_type(py).name().unwrap(), error);
+    })
+}
+
+#[rstest]
+fn test_xirr_money_market() {
+    Python::with_gil(|py| {
+        let dates = ["2024-01-15", "2024-03-15", "2024-07-15"];
+        let amounts = [-1000., 10., 1020.];
+
+        let rate: f64 =
+            pyxirr_call!(py, "xirr", (dates, amounts), py_dict!(py, "money_market" => true));
+        let kwargs = py_dict!(py, "compounding" => "simple", "day_count" => "act/360");
+        let expected: f64 = pyxirr_call!(py, "xirr", (dates, amounts), kwargs);
+        assert_almost_eq!(rate, expected);
+
+        // 60 and 182 days
+        let npv = amounts[0]
+            + amounts[1] / (1.0 + rate * 60.0 / 360.0)
+            + amounts[2] / (1.0 + rate * 182.0 / 360.0);
+        assert_almost_eq!(npv, 0.0, 1e-7);
+
+        let kwargs = py_dict!(py, "money_market" => true, "day_count" => "act/365f");
+        let rate: f64 = pyxirr_call!(py, "xirr", (dates, amounts), kwargs);
+        assert_almost_eq!(rate, expected * 365.0 / 360.0);
+
+        let kwargs = py_dict!(py, "money_market" => true, "compounding" => "continuous");
+        let err = pyxirr_call_impl!(py, "xirr", (dates, amounts), kwargs).unwrap_err();
+        assert!(err.is_instance_of::<pyo3::exceptions::PyValueError>(py));
+    })
+}
+
+#[rstest]
+fn test_xgoal_seek_amount() {
+    Python::with_gil(|py| {
+        let dates = ["2020-01-01", "2020-06-01", "2021-01-01", "2022-03-01"];
+        let amounts = [Some(-1000.), Some(100.), Some(200.), None];
+
+        let amount: f64 = pyxirr_call!(py, "xgoal_seek", (0.15, dates, amounts));
+        let rate: f64 = pyxirr_call!(py, "xirr", (dates, [-1000., 100., 200., amount]));
+        assert_almost_eq!(rate, 0.15);
+
+        // a negative index counts from the end, the value at the index is ignored
+        let kwargs = py_dict!(py, "index" => 1);
+        let amount: f64 =
+            pyxirr_call!(py, "xgoal_seek", (0.15, dates, [-1000., 0., 200., 900.]), kwargs);
+        let rate: f64 = pyxirr_call!(py, "xirr", (dates, [-1000., amount, 200., 900.]));
+        assert_almost_eq!(rate, 0.15);
+
+        let kwargs = py_dict!(py, "index" => 4);
+        let err = pyxirr_call_impl!(py, "xgoal_seek", (0.15, dates, amounts), kwargs).unwrap_err();
+        assert!(err.is_instance_of::<pyo3::exceptions::PyIndexError>(py));
+    })
+}
+
+#[rstest]
+fn test_xgoal_seek_nan_policy() {
+    Python::with_gil(|py| {
+        let dates = [Some("2020-01-01"), None, Some("2021-01-01"), Some("2022-03-01")];
+        let amounts = [Some(-1000.), Some(100.), Some(200.), None];
+
+        let err = pyxirr_call_impl!(py, "xgoal_seek", (0.15, dates, amounts)).unwrap_err();
+        assert!(err.is_instance_of::<pyxirr::MissingDateError>(py));
+
+        // the unknown amount is kept, the payment without a date is dropped
+        let expected: f64 = pyxirr_call!(
+            py,
+            "xgoal_seek",
+            (0.15, ["2020-01-01", "2021-01-01", "2022-03-01"], [Some(-1000.), Some(200.), None])
+        );
+        for nan_policy in ["omit", "zero"] {
+            let kwargs = py_dict!(py, "nan_policy" => nan_policy);
+            let amount: f64 = pyxirr_call!(py, "xgoal_seek", (0.15, dates, amounts), kwargs);
+            assert_almost_eq!(amount, expected);
+        }
+
+        // the position of the unknown payment is counted before omitting
+        let dates = ["2020-01-01", "2020-06-01", "2021-01-01", "2022-03-01"];
+        let kwargs = py_dict!(py, "nan_policy" => "omit", "index" => 2);
+        let amount: f64 =
+            pyxirr_call!(py, "xgoal_seek", (0.15, dates, [-1000., f64::NAN, 0., 900.]), kwargs);
+        let rate: f64 =
+            pyxirr_call!(py, "xirr", ((dates[0], dates[2], dates[3]), [-1000., amount, 900.]));
+        assert_almost_eq!(rate, 0.15);
+    })
+}
+
+#[rstest]
+fn test_xgoal_seek_date() {
+    Python::with_gil(|py| {
+        let dates = [Some("2020-01-01"), Some("2020-06-01"), None];
+        let amounts = [-1000., 100., 1100.];
+
+        let kwargs = py_dict!(py, "solve_for" => "date");
+        let date: Bound<PyDate> =
+            pyxirr_call_impl!(py, "xgoal_seek", (0.08, dates, amounts), kwargs)
+                .unwrap()
+                .downcast_into()
+                .unwrap();
+        let next_day =
+            py.import("datetime").unwrap().getattr("timedelta").unwrap().call1((1,)).unwrap();
+
+        let rate: f64 = pyxirr_call!(py, "xirr", (("2020-01-01", "2020-06-01", &date), amounts));
+        assert!(rate >= 0.08);
+        let rate: f64 = pyxirr_call!(
+            py,
+            "xirr",
+            (("2020-01-01", "2020-06-01", date.add(next_day).unwrap()), amounts)
+        );
+        assert!(rate < 0.08);
+
+        // a missing date elsewhere is an error
+        let kwargs = py_dict!(py, "solve_for" => "date", "index" => 0);
+        let err = pyxirr_call_impl!(py, "xgoal_seek", (0.08, dates, amounts), kwargs).unwrap_err();
+        assert!(err.is_instance_of::<pyxirr::MissingDateError>(py));
+
+        // the date is solved to a whole day
+        let kwargs = py_dict!(py, "solve_for" => "date", "intraday" => true);
+        let err = pyxirr_call_impl!(py, "xgoal_seek", (0.08, dates, amounts), kwargs).unwrap_err();
+        assert!(err.is_instance_of::<pyo3::exceptions::PyValueError>(py));
+    })
+}
+
+#[rstest]
+fn test_xgoal_seek_scale() {
+    Python::with_gil(|py| {
+        let dates = ["2020-01-01", "2020-06-01", "2021-01-01", "2022-03-01"];
+        let amounts = [-1000., 100., -200., 900.];
+
+        let kwargs = py_dict!(py, "solve_for" => "scale");
+        let scale: f64 = pyxirr_call!(py, "xgoal_seek", (0.1, dates, amounts), kwargs);
+        let scaled = amounts.map(|a| {
+            if a > 0.0 {
+                a * scale
+            } else {
+                a
+            }
+        });
+        let rate: f64 = pyxirr_call!(py, "xirr", (dates, scaled));
+        assert_almost_eq!(rate, 0.1);
+
+        let kwargs = py_dict!(py, "solve_for" => "rate");
+        let err = pyxirr_call_impl!(py, "xgoal_seek", (0.1, dates, amounts), kwargs).unwrap_err();
+        assert!(err.is_instance_of::<pyo3::exceptions::PyValueError>(py));
+
+        // solve_for is validated before the cash flows
+        let kwargs = py_dict!(py, "solve_for" => "rate");
+        let err =
+            pyxirr_call_impl!(py, "xgoal_seek", (0.1, ["bad date"], [1.]), kwargs).unwrap_err();
+        assert!(err.value(py).to_string().contains("solve_for"));
+    })
+}
+
 #[rstest]
 fn test_xfv() {
     // http://westclintech.com/SQL-Server-Financial-Functions/SQL-Server-XFV-function
@@ -200,9 +471,348 @@ fn test_xirr_day_count(#[case] day_count: &str, #[case] expected: f64) {
         let dates = ["01/12/2007", "02/14/2008", "03/03/2008", "06/14/2008", "12/01/2008"];
         let amounts = [-10000, 2500, 2000, 3000, 4000];
 
-        let kwargs = py_dict!(py, "day_count" => day_count);
+        let kwargs = py_dict!(py, "day_count" => day_count, "date_format" => "%m/%d/%Y");
         let value: f64 = pyxirr_call!(py, "xirr", (dates, amounts), kwargs);
 
         assert_almost_eq!(value, expected);
     })
 }
+
+#[rstest]
+fn test_xirr_intraday() {
+    Python::with_gil(|py| {
+        let dates = ["2024-01-01T09:00", "2024-01-01T21:00+02:00"];
+        let amounts = [-1000, 1001];
+
+        // 10 hours, a tenth of a percent
+        let kwargs = py_dict!(py, "intraday" => true);
+        let result: f64 = pyxirr_call!(py, "xirr", (dates, amounts), kwargs);
+        assert_almost_eq!(result, 1.001_f64.powf(365. * 24. / 10.) - 1., 1e-6);
+
+        let kwargs = py_dict!(py, "intraday" => true);
+        let result: f64 =
+            pyxirr_call!(py, "year_fraction", (dates[0], dates[1], "act/360"), kwargs);
+        assert_almost_eq!(result, 10. / 24. / 360.);
+    })
+}
+
+#[rstest]
+fn test_intraday_datetime() {
+    Python::with_gil(|py| {
+        let locals = py_dict!(py, "datetime" => py.import("datetime").unwrap());
+        let dates = py
+            .eval(
+                c_str!("[
+                    datetime.datetime(2024, 1, 1, 18, tzinfo=datetime.timezone(datetime.timedelta(hours=-6))),
+                    datetime.datetime(2024, 1, 2, 6),
+                ]"),
+                Some(&locals),
+                None,
+            )
+            .unwrap();
+        let kwargs = py_dict!(py, "intraday" => true);
+        let result: f64 = pyxirr_call!(
+            py,
+            "year_fraction",
+            (dates.get_item(0).unwrap(), dates.get_item(1).unwrap(), "act/365f"),
+            kwargs
+        );
+        assert_almost_eq!(result, 6. / 24. / 365.);
+
+        let result: f64 = pyxirr_call!(
+            py,
+            "year_fraction",
+            (dates.get_item(0).unwrap(), dates.get_item(1).unwrap(), "act/365f")
+        );
+        assert_almost_eq!(result, 1. / 365.);
+    })
+}
+
+#[rstest]
+fn test_monte_carlo() {
+    Python::with_gil(|py| {
+        let dates = ["2020-01-01", "2021-01-01", "2022-01-01", "2023-01-01"];
+        let amounts = [-1000., 300., 400., 500.];
+        let kwargs = py_dict!(py,
+            "scenarios" => 2000,
+            "seed" => 42,
+            "shock" => "lognormal",
+            "volatility" => [0.0, 0.2, 0.2, 0.3],
+            "jitter_days" => 15,
+            "default_probability" => 0.01
+        );
+
+        let result = pyxirr_call_impl!(py, "monte_carlo", (0.05, dates, amounts), kwargs).unwrap();
+        let xirr = result.get_item("xirr").unwrap();
+        let values: Vec<f64> = xirr.get_item("values").unwrap().extract().unwrap();
+        assert_eq!(values.len(), 2000);
+
+        let median: f64 =
+            xirr.get_item("percentiles").unwrap().get_item(50).unwrap().extract().unwrap();
+        let base: f64 = pyxirr_call!(py, "xirr", (dates, amounts));
+        assert_almost_eq!(median, base, 0.02);
+
+        let (p5, p95): (f64, f64) = (
+            xirr.get_item("percentiles").unwrap().get_item(5).unwrap().extract().unwrap(),
+            xirr.get_item("percentiles").unwrap().get_item(95).unwrap().extract().unwrap(),
+        );
+        assert!(p5 < median && median < p95);
+
+        // the same seed gives the same scenarios
+        let again = pyxirr_call_impl!(py, "monte_carlo", (0.05, dates, amounts), kwargs).unwrap();
+        let again: Vec<f64> =
+            again.get_item("xirr").unwrap().get_item("values").unwrap().extract().unwrap();
+        // a defaulted contribution has no xirr, NaN != NaN
+        assert!(values.iter().zip(&again).all(|(a, b)| a.to_bits() == b.to_bits()));
+
+        let seed: u64 = result.get_item("seed").unwrap().extract().unwrap();
+        assert_eq!(seed, 42);
+
+        // the percentiles are keyed by the requested values
+        kwargs.set_item("percentiles", [7, 29]).unwrap();
+        let result = pyxirr_call_impl!(py, "monte_carlo", (0.05, dates, amounts), kwargs).unwrap();
+        let keys: Vec<f64> = result
+            .get_item("xirr")
+            .unwrap()
+            .get_item("percentiles")
+            .unwrap()
+            .call_method0("keys")
+            .unwrap()
+            .try_iter()
+            .unwrap()
+            .map(|k| k.unwrap().extract().unwrap())
+            .collect();
+        assert_eq!(keys, [7.0, 29.0]);
+        for metric in ["xnpv", "tvpi", "dpi"] {
+            let failed: usize =
+                result.get_item(metric).unwrap().get_item("failed").unwrap().extract().unwrap();
+            assert!(failed < 2000);
+        }
+    })
+}
+
+#[rstest]
+fn test_monte_carlo_nan_policy() {
+    Python::with_gil(|py| {
+        let dates = ["2020-01-01", "2021-01-01", "2022-01-01", "2023-01-01"];
+        let amounts = [-1000., 300., f64::NAN, 500.];
+
+        let err = pyxirr_call_impl!(py, "monte_carlo", (0.05, dates, amounts)).unwrap_err();
+        assert!(err.is_instance_of::<pyxirr::NaNAmountError>(py));
+
+        let kwargs = py_dict!(py, "scenarios" => 10, "seed" => 1, "nan_policy" => "omit");
+        let result = pyxirr_call_impl!(py, "monte_carlo", (0.05, dates, amounts), kwargs).unwrap();
+        let mean: f64 =
+            result.get_item("xirr").unwrap().get_item("mean").unwrap().extract().unwrap();
+        let expected: f64 = pyxirr_call!(
+            py,
+            "xirr",
+            (("2020-01-01", "2021-01-01", "2023-01-01"), [-1000., 300., 500.])
+        );
+        assert_almost_eq!(mean, expected);
+    })
+}
+
+#[rstest]
+#[case("{'volatility': [0.1, 0.2]}", "volatility has 2 values for 4 payments")]
+#[case("{'shock': 'uniform'}", "shock must be one of: 'normal', 'lognormal'")]
+#[case("{'default_probability': 2.0}", "default_probability must be between 0 and 1")]
+#[case("{'percentiles': [150]}", "percentile 150 is not between 0 and 100")]
+fn test_monte_carlo_invalid(#[case] kwargs: &str, #[case] message: &str) {
+    Python::with_gil(|py| {
+        let dates = ["2020-01-01", "2021-01-01", "2022-01-01", "2023-01-01"];
+        let amounts = [-1000., 300., 400., 500.];
+        let kwargs = py.eval(&CString::new(kwargs).unwrap(), None, None).unwrap();
+        let kwargs = kwargs.downcast_into::<pyo3::types::PyDict>().unwrap();
+
+        let err = pyxirr_call_impl!(py, "monte_carlo", (0.05, dates, amounts), kwargs).unwrap_err();
+        assert_eq!(err.value(py).to_string(), message);
+    })
+}
+
+#[rstest]
+fn test_sensitivity_table() {
+    Python::with_gil(|py| {
+        let dates = ["2020-01-01", "2020-09-15", "2021-06-30", "2023-01-01"];
+        let amounts = [-1000., 100., 300., 900.];
+        let (rates, shocks) = ([0.05, 0.1], [-0.2, 0.0, 0.1]);
+
+        let table: Vec<Vec<f64>> =
+            pyxirr_call!(py, "sensitivity_table", (rates, shocks, dates, amounts));
+        assert_eq!((table.len(), table[0].len()), (2, 3));
+
+        for (row, rate) in table.iter().zip(rates) {
+            for (cell, shock) in row.iter().zip(shocks) {
+                let shocked = amounts.map(|a| {
+                    if a > 0.0 {
+                        a * (1.0 + shock)
+                    } else {
+                        a
+                    }
+                });
+                let expected: f64 = pyxirr_call!(py, "xnpv", (rate, dates, shocked));
+                assert_almost_eq!(cell, expected);
+            }
+        }
+
+        // only the last flow, the index counts from the end
+        let kwargs = py_dict!(py, "flows" => [-1]);
+        let table: Vec<Vec<f64>> =
+            pyxirr_call!(py, "sensitivity_table", (rates, shocks, dates, amounts), kwargs);
+        let expected: f64 = pyxirr_call!(py, "xnpv", (0.1, dates, [-1000., 100., 300., 990.]));
+        assert_almost_eq!(table[1][2], expected);
+
+        let kwargs = py_dict!(py, "flows" => [4]);
+        let err =
+            pyxirr_call_impl!(py, "sensitivity_table", (rates, shocks, dates, amounts), kwargs)
+                .unwrap_err();
+        assert!(err.is_instance_of::<pyo3::exceptions::PyIndexError>(py));
+
+        // `flows` index the payments before omitting
+        let nan_amounts = [-1000., f64::NAN, 300., 900.];
+        let err = pyxirr_call_impl!(py, "sensitivity_table", (rates, shocks, dates, nan_amounts))
+            .unwrap_err();
+        assert!(err.is_instance_of::<pyxirr::InvalidPaymentsError>(py));
+        let kwargs = py_dict!(py, "flows" => [3], "nan_policy" => "omit");
+        let table: Vec<Vec<f64>> =
+            pyxirr_call!(py, "sensitivity_table", (rates, shocks, dates, nan_amounts), kwargs);
+        let expected: f64 = pyxirr_call!(
+            py,
+            "xnpv",
+            (0.1, ("2020-01-01", "2021-06-30", "2023-01-01"), [-1000., 300., 990.])
+        );
+        assert_almost_eq!(table[1][2], expected);
+    })
+}
+
+#[rstest]
+#[cfg_attr(feature = "nonumpy", ignore)]
+fn test_sensitivity_table_pandas() {
+    Python::with_gil(|py| {
+        let dates = ["2020-01-01", "2020-09-15", "2021-06-30", "2023-01-01"];
+        let amounts = [-1000., 100., 300., 900.];
+
+        let table =
+            pyxirr_call_impl!(py, "sensitivity_table", ([0.05, 0.1], [-0.2, 0.1], dates, amounts))
+                .unwrap();
+        let shape: (usize, usize) = table.getattr("shape").unwrap().extract().unwrap();
+        assert_eq!(shape, (2, 2));
+        let rates: Vec<f64> = table.getattr("index").unwrap().extract().unwrap();
+        assert_eq!(rates, [0.05, 0.1]);
+    })
+}
+
+#[rstest]
+fn test_tornado() {
+    Python::with_gil(|py| {
+        let dates = ["2020-01-01", "2020-09-15", "2021-06-30", "2023-01-01"];
+        let amounts = [-1000., 100., 300., 900.];
+
+        let result = pyxirr_call_impl!(py, "tornado", (dates, amounts)).unwrap();
+        let base: f64 = result.get_item("base").unwrap().extract().unwrap();
+        let high: Vec<f64> = result.get_item("high").unwrap().extract().unwrap();
+        let order: Vec<usize> = result.get_item("order").unwrap().extract().unwrap();
+
+        let expected: f64 = pyxirr_call!(py, "xirr", (dates, amounts));
+        assert_almost_eq!(base, expected);
+        let expected: f64 = pyxirr_call!(py, "xirr", (dates, [-1000., 100., 330., 900.]));
+        assert_almost_eq!(high[2], expected);
+        assert_eq!(order, [0, 3, 2, 1]);
+
+        let kwargs = py_dict!(py, "shock" => 0.2, "rate" => 0.1);
+        let result = pyxirr_call_impl!(py, "tornado", (dates, amounts), kwargs).unwrap();
+        let low: Vec<f64> = result.get_item("low").unwrap().extract().unwrap();
+        let expected: f64 = pyxirr_call!(py, "xnpv", (0.1, dates, [-1000., 100., 300., 720.]));
+        assert_almost_eq!(low[3], expected);
+
+        // an omitted payment keeps its position, without a swing
+        let kwargs = py_dict!(py, "nan_policy" => "omit");
+        let result =
+            pyxirr_call_impl!(py, "tornado", (dates, [-1000., f64::NAN, 300., 900.]), kwargs)
+                .unwrap();
+        let high: Vec<f64> = result.get_item("high").unwrap().extract().unwrap();
+        let order: Vec<usize> = result.get_item("order").unwrap().extract().unwrap();
+        assert_eq!(high.len(), 4);
+        assert!(high[1].is_nan());
+        let expected: f64 = pyxirr_call!(
+            py,
+            "xirr",
+            (("2020-01-01", "2021-06-30", "2023-01-01"), [-1000., 330., 900.])
+        );
+        assert_almost_eq!(high[2], expected);
+        assert_eq!(order[3], 1);
+    })
+}
+
+#[rstest]
+fn test_xpayback() {
+    Python::with_gil(|py| {
+        let dates = ["2019-01-01", "2020-01-01", "2021-01-01"];
+
+        let date: Bound<PyDate> = pyxirr_call_impl!(py, "xpayback", (dates, [-1000., 500., 1000.]))
+            .unwrap()
+            .downcast_into()
+            .unwrap();
+        assert_eq!(date.to_string(), "2020-07-02");
+
+        let kwargs = py_dict!(py, "rate" => 0.1);
+        let result = pyxirr_call_impl!(py, "xpayback", (dates, [-1000., 500., 600.]), kwargs);
+        assert!(result.unwrap().is_none());
+
+        let kwargs = py_dict!(py, "rate" => 0.1);
+        let date: Bound<PyDate> =
+            pyxirr_call_impl!(py, "xpayback", (dates, [-1000., 600., 600.]), kwargs)
+                .unwrap()
+                .downcast_into()
+                .unwrap();
+        assert_eq!(date.to_string(), "2020-12-02");
+
+        let err = pyxirr_call_impl!(py, "xpayback", (dates, [-1000., 500.])).unwrap_err();
+        assert!(err.is_instance_of::<pyxirr::InvalidPaymentsError>(py));
+        let kwargs = py_dict!(py, "silent" => true);
+        let result = pyxirr_call_impl!(py, "xpayback", (dates, [-1000., 500.]), kwargs);
+        assert!(result.unwrap().is_none());
+    })
+}
+
+#[rstest]
+fn test_xnpv_profile() {
+    Python::with_gil(|py| {
+        let dates = ["2020-01-01", "2021-01-01", "2023-01-01"];
+        let (a, b) = ([-1000., 800., 400.], [-1000., 100., 1300.]);
+        let rates = [0.0, 0.1, 0.2];
+
+        let result = pyxirr_call_impl!(py, "xnpv_profile", (rates, dates, a)).unwrap();
+        let npv: Vec<f64> = result.get_item("npv").unwrap().extract().unwrap();
+        for (value, rate) in npv.iter().zip(rates) {
+            let expected: f64 = pyxirr_call!(py, "xnpv", (rate, dates, a));
+            assert_almost_eq!(value, expected);
+        }
+
+        // the other cash flow may have its own dates, in any supported format
+        let other =
+            py_dict!(py, "2020-01-01" => -1000., "2021-06-30" => 100., "2023-01-01" => 1300.);
+        let kwargs = py_dict!(py, "other" => other);
+        let result = pyxirr_call_impl!(py, "xnpv_profile", (rates, dates, a), kwargs).unwrap();
+        let crossover: f64 = result.get_item("crossover_rate").unwrap().extract().unwrap();
+
+        let other_dates = ["2020-01-01", "2021-06-30", "2023-01-01"];
+        let npv_a: f64 = pyxirr_call!(py, "xnpv", (crossover, dates, a));
+        let npv_b: f64 = pyxirr_call!(py, "xnpv", (crossover, other_dates, b));
+        assert_almost_eq!(npv_a, npv_b);
+
+        // a later start, the profiles meet at the crossover rate
+        let later =
+            py_dict!(py, "2020-07-01" => -1000., "2021-07-01" => 100., "2024-01-01" => 1300.);
+        let kwargs = py_dict!(py, "other" => &later);
+        let result = pyxirr_call_impl!(py, "xnpv_profile", (rates, dates, a), kwargs).unwrap();
+        let crossover: f64 = result.get_item("crossover_rate").unwrap().extract().unwrap();
+
+        let kwargs = py_dict!(py, "other" => &later);
+        let result =
+            pyxirr_call_impl!(py, "xnpv_profile", ([crossover], dates, a), kwargs).unwrap();
+        let npv: Vec<f64> = result.get_item("npv").unwrap().extract().unwrap();
+        let other: Vec<f64> = result.get_item("other").unwrap().extract().unwrap();
+        assert_almost_eq!(npv[0], other[0]);
+    })
+}